@@ -1,22 +1,45 @@
+use self::error::ValidationError;
 use self::utils::rand_server_key_256bit;
-use self::{tls_mode::TlsMode, uri_path::UriPath};
-use core::net::{IpAddr, Ipv4Addr};
-use std::{collections::HashMap, num::NonZeroUsize, path::PathBuf};
+use self::{
+  cert_fingerprint::CertFingerprint,
+  namespace_type::NamespaceType,
+  poll_schedule::{DevicePollConfig, PollRule, PollSchedule},
+  proxy_url::ProxyUrl,
+  tls_mode::TlsMode,
+  uri_path::UriPath,
+};
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use nut_webgui_upsmc::{UpsIdentifier, UpsName, ups_status::UpsStatus};
+use std::{
+  collections::{HashMap, HashSet},
+  num::NonZeroUsize,
+  path::PathBuf,
+  time::Duration,
+};
 use tracing::level_filters::LevelFilter;
 
 mod utils;
 
+pub mod cert_fingerprint;
 pub mod cfg_arg;
 pub mod cfg_env;
 pub mod cfg_fallback;
 pub mod cfg_toml;
 pub mod cfg_user;
+pub mod client_alias;
+pub mod driver_socket;
 pub mod error;
+pub mod namespace_type;
+pub mod poll_schedule;
+pub mod proxy_url;
 pub mod tls_mode;
 pub mod uri_path;
 
 pub const DEFAULT_UPSD_KEY: &str = "default";
 
+/// Namespace names which collide with fixed routes under `/ups/`.
+pub const RESERVED_UPSD_KEYS: &[&str] = &["by-id"];
+
 pub trait ConfigLayer {
   fn apply_layer(self, config: ServerConfig) -> ServerConfig;
 }
@@ -42,6 +65,15 @@ pub struct ServerConfig {
 
   /// Authentication scheme configurations
   pub auth: AuthConfig,
+
+  /// NUT protocol server configurations
+  pub nut_server: NutServerConfig,
+
+  /// Reverse DNS settings for attached clients
+  pub reverse_dns: ReverseDnsConfig,
+
+  /// Static client names by IP address, used instead of reverse DNS
+  pub client_aliases: HashMap<IpAddr, Box<str>>,
 }
 
 #[derive(Debug)]
@@ -52,14 +84,60 @@ pub struct HttpServerConfig {
   pub worker_count: Option<NonZeroUsize>,
 }
 
+#[derive(Debug)]
+pub struct NutServerConfig {
+  /// Serves the cached upsd state to NUT clients when enabled
+  pub enabled: bool,
+  pub listen: IpAddr,
+  pub port: u16,
+
+  /// Upsd namespace served to NUT clients
+  pub namespace: Box<str>,
+}
+
+#[derive(Debug)]
+pub struct ReverseDnsConfig {
+  /// Resolves attached client addresses with reverse DNS when enabled
+  pub enabled: bool,
+
+  /// Cache duration in seconds for resolved names
+  pub positive_ttl: u64,
+
+  /// Cache duration in seconds for failed lookups
+  pub negative_ttl: u64,
+
+  /// Lookup timeout in seconds
+  pub timeout: u64,
+}
+
 #[derive(Clone)]
 pub struct UpsdConfig {
+  /// Namespace transport, upsd or NUT driver sockets
+  pub namespace_type: NamespaceType,
+
+  /// Driver state sockets by UPS name, only used by [NamespaceType::Driver]
+  pub drivers: HashMap<UpsName, PathBuf>,
+
+  /// Allows `INSTCMD` and `SET VAR` on driver sockets, only used by [NamespaceType::Driver]
+  pub allow_driver_writes: bool,
+
   /// Poll frequency in seconds for less critical parameters
   pub poll_freq: u64,
 
   /// Poll interval in seconds for ups status
   pub poll_interval: u64,
 
+  /// Status driven poll intervals, the first matching rule is used
+  pub poll_rules: Vec<PollRule>,
+
+  /// Per-device poll intervals, used instead of [UpsdConfig::poll_interval] and
+  /// [UpsdConfig::poll_freq]
+  pub poll_overrides: HashMap<UpsName, DevicePollConfig>,
+
+  /// Device data older than this (in seconds) is reported as stale. Zero uses three times the
+  /// longest configured poll frequency.
+  pub stale_threshold: u64,
+
   /// UPSD TCP address
   /// It can be hostname, IPv4, or IPv6
   pub addr: Box<str>,
@@ -67,6 +145,9 @@ pub struct UpsdConfig {
   /// UPSD TCP port
   pub port: u16,
 
+  /// Lower priority UPSD addresses in failover order. Entries without a port use [UpsdConfig::port].
+  pub fallback_addrs: Vec<Box<str>>,
+
   /// UPSD username
   pub user: Option<Box<str>>,
 
@@ -76,8 +157,49 @@ pub struct UpsdConfig {
   /// Maximum allowed connection limit aka pool size
   pub max_conn: NonZeroUsize,
 
+  /// Pooled connections used within this window (in seconds) are reused without a `NETVER`
+  /// check.
+  pub pool_validation_window: u64,
+
+  /// Maximum lifetime in seconds for pooled connections. Zero disables the limit.
+  pub pool_max_lifetime: u64,
+
+  /// Idle timeout in seconds for pooled connections. Zero disables the limit.
+  pub pool_idle_timeout: u64,
+
+  /// Connection timeout in seconds, including proxy handshake and `STARTTLS`
+  pub connect_timeout: u64,
+
+  /// Request timeout in seconds for a single command sent to UPSD
+  pub request_timeout: u64,
+
+  /// Upper limit in seconds for the reconnect backoff while UPSD is unreachable
+  pub reconnect_max_backoff: u64,
+
   /// UPSD starts with TLS
   pub tls_mode: TlsMode,
+
+  /// PEM encoded CA bundle used instead of the platform trust store
+  pub ca_file: Option<PathBuf>,
+
+  /// PEM encoded client certificate chain for mutual TLS
+  pub client_cert: Option<PathBuf>,
+
+  /// PEM encoded private key of [UpsdConfig::client_cert]
+  pub client_key: Option<PathBuf>,
+
+  /// Accepted server certificate SHA-256 fingerprints. When set, it replaces CA and hostname
+  /// validation.
+  pub cert_fingerprints: Vec<CertFingerprint>,
+
+  /// Fixture file for recording upsd traffic, only used by [NamespaceType::Upsd]
+  pub record_file: Option<PathBuf>,
+
+  /// `MONITOR`-style device identifiers. When set, only these devices are synchronized.
+  pub monitor: Vec<UpsIdentifier>,
+
+  /// SOCKS5 or HTTP CONNECT proxy for reaching upsd, only used by [NamespaceType::Upsd]
+  pub proxy: Option<ProxyUrl>,
 }
 
 #[derive(Debug)]
@@ -93,8 +215,125 @@ impl AuthConfig {
 }
 
 impl UpsdConfig {
+  /// Sets monitored devices, and takes the upsd address and port from the first identifier with
+  /// a hostname.
+  pub fn set_monitor(&mut self, monitor: Vec<UpsIdentifier>) {
+    if let Some(identifier) = monitor.iter().find(|v| v.host().is_some()) {
+      self.addr = identifier.host().map(Box::from).unwrap_or_default();
+      self.port = identifier.port_or_default();
+    }
+
+    self.monitor = monitor;
+  }
+
+  /// Returns `true` if the device is monitored by the namespace.
+  pub fn is_monitored(&self, ups_name: &UpsName) -> bool {
+    self.monitor.is_empty() || self.monitor.iter().any(|v| v.name() == ups_name)
+  }
+
+  /// Returns `true` if the identifier's hostname and port address this namespace's upsd.
+  /// Identifiers without a hostname match any namespace.
+  pub fn is_identified_by(&self, identifier: &UpsIdentifier) -> bool {
+    identifier.host().is_none_or(|host| {
+      self.namespace_type == NamespaceType::Upsd
+        && self.get_host().eq_ignore_ascii_case(host)
+        && self.port == identifier.port_or_default()
+    })
+  }
+
+  /// Resolves poll intervals of a device. A matching [PollRule] takes precedence over device
+  /// overrides, and device overrides take precedence over namespace defaults.
+  pub fn poll_schedule(
+    &self,
+    ups_name: &UpsName,
+    status: UpsStatus,
+    stable_for: Duration,
+  ) -> PollSchedule {
+    let mut poll_interval = self.poll_interval;
+    let mut poll_freq = self.poll_freq;
+
+    if let Some(device) = self.poll_overrides.get(ups_name) {
+      poll_interval = device.poll_interval.unwrap_or(poll_interval);
+      poll_freq = device.poll_freq.unwrap_or(poll_freq);
+    }
+
+    if let Some(rule) = self
+      .poll_rules
+      .iter()
+      .find(|v| v.is_match(status, stable_for))
+    {
+      poll_interval = rule.poll_interval.unwrap_or(poll_interval);
+      poll_freq = rule.poll_freq.unwrap_or(poll_freq);
+    }
+
+    PollSchedule::from_secs(poll_interval, poll_freq)
+  }
+
+  /// Returns the staleness threshold, see [UpsdConfig::stale_threshold].
+  pub fn get_stale_threshold(&self) -> Duration {
+    if self.stale_threshold > 0 {
+      return Duration::from_secs(self.stale_threshold);
+    }
+
+    let max_poll_freq = self
+      .poll_overrides
+      .values()
+      .filter_map(|v| v.poll_freq)
+      .chain(self.poll_rules.iter().filter_map(|v| v.poll_freq))
+      .fold(self.poll_freq, u64::max);
+
+    Duration::from_secs(max_poll_freq.max(1) * 3)
+  }
+
+  /// Returns the upsd hostname or IP address without brackets and port.
+  pub fn get_host(&self) -> &str {
+    split_host_port(&self.addr, self.port).0
+  }
+
   pub fn get_socket_addr(&self) -> String {
-    format!("{address}:{port}", address = self.addr, port = self.port)
+    let (host, port) = split_host_port(&self.addr, self.port);
+    join_host_port(host, port)
+  }
+
+  /// Returns `(host, socket address)` pairs for the fallback addresses. Addresses are parsed the
+  /// same way as [UpsdConfig::addr], entries without a port use [UpsdConfig::port].
+  pub fn get_fallback_socket_addrs(&self) -> impl Iterator<Item = (&str, String)> {
+    self.fallback_addrs.iter().map(|addr| {
+      let (host, port) = split_host_port(addr, self.port);
+      (host, join_host_port(host, port))
+    })
+  }
+}
+
+/// Splits an address into host and port. Accepts `host`, `host:port`, `ipv4`, `ipv4:port`, bare
+/// `ipv6`, `[ipv6]` and `[ipv6]:port` formats.
+fn split_host_port(addr: &str, default_port: u16) -> (&str, u16) {
+  if let Some(bracketed) = addr.strip_prefix('[')
+    && let Some((host, rest)) = bracketed.split_once(']')
+  {
+    let port = rest
+      .strip_prefix(':')
+      .and_then(|port| port.parse::<u16>().ok())
+      .unwrap_or(default_port);
+
+    (host, port)
+  } else if addr.parse::<IpAddr>().is_ok() {
+    (addr, default_port)
+  } else if let Some((host, port)) = addr.rsplit_once(':')
+    && !host.contains(':')
+    && let Ok(port) = port.parse::<u16>()
+  {
+    (host, port)
+  } else {
+    (addr, default_port)
+  }
+}
+
+fn join_host_port(host: &str, port: u16) -> String {
+  if host.parse::<Ipv6Addr>().is_ok() {
+    format!("[{host}]:{port}")
+  } else {
+    format!("{host}:{port}")
   }
 }
 
@@ -104,6 +343,12 @@ impl HttpServerConfig {
   }
 }
 
+impl NutServerConfig {
+  pub fn get_listen_addr(&self) -> String {
+    format!("{ip}:{port}", ip = self.listen, port = self.port)
+  }
+}
+
 impl Default for AuthConfig {
   fn default() -> Self {
     Self {
@@ -124,15 +369,57 @@ impl Default for HttpServerConfig {
   }
 }
 
+impl Default for ReverseDnsConfig {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      positive_ttl: 3600,
+      negative_ttl: 300,
+      timeout: 2,
+    }
+  }
+}
+
+impl Default for NutServerConfig {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      listen: Ipv4Addr::UNSPECIFIED.into(),
+      namespace: Box::from(DEFAULT_UPSD_KEY),
+      port: 3493,
+    }
+  }
+}
+
 impl Default for UpsdConfig {
   fn default() -> Self {
     Self {
       addr: "localhost".into(),
+      allow_driver_writes: false,
+      ca_file: None,
+      cert_fingerprints: Vec::new(),
+      client_cert: None,
+      client_key: None,
+      connect_timeout: 5,
+      drivers: HashMap::new(),
+      fallback_addrs: Vec::new(),
       max_conn: NonZeroUsize::new(4).expect("static non-zero max_conn is provided as default"),
+      monitor: Vec::new(),
+      namespace_type: NamespaceType::Upsd,
       pass: None,
       poll_freq: 30,
       poll_interval: 2,
+      poll_overrides: HashMap::new(),
+      poll_rules: Vec::new(),
+      pool_idle_timeout: 60,
+      pool_max_lifetime: 0,
+      pool_validation_window: 10,
       port: 3493,
+      proxy: None,
+      reconnect_max_backoff: 300,
+      record_file: None,
+      request_timeout: 10,
+      stale_threshold: 0,
       tls_mode: TlsMode::Disable,
       user: None,
     }
@@ -149,6 +436,9 @@ impl Default for ServerConfig {
       server_key: rand_server_key_256bit(),
       upsd: Default::default(),
       auth: Default::default(),
+      nut_server: Default::default(),
+      reverse_dns: Default::default(),
+      client_aliases: HashMap::new(),
     }
   }
 }
@@ -166,6 +456,27 @@ impl ServerConfig {
   {
     layer.apply_layer(self)
   }
+
+  /// Checks constraints spanning multiple namespaces, after all layers are applied.
+  pub fn validate(&self) -> Result<(), ValidationError> {
+    if let Some(name) = self
+      .upsd
+      .keys()
+      .find(|name| RESERVED_UPSD_KEYS.contains(&name.as_ref()))
+    {
+      return Err(ValidationError::ReservedNamespace { name: name.clone() });
+    }
+
+    let mut record_files = HashSet::new();
+
+    for path in self.upsd.values().filter_map(|v| v.record_file.as_ref()) {
+      if !record_files.insert(path) {
+        return Err(ValidationError::DuplicateRecordFile { path: path.clone() });
+      }
+    }
+
+    Ok(())
+  }
 }
 
 impl core::fmt::Debug for UpsdConfig {
@@ -173,12 +484,32 @@ impl core::fmt::Debug for UpsdConfig {
     f.debug_struct("UpsdConfig")
       .field("pass", &self.pass.as_ref().map(|_| "******"))
       .field("user", &self.user.as_ref().map(|_| "******"))
+      .field("namespace_type", &self.namespace_type)
+      .field("drivers", &self.drivers)
+      .field("allow_driver_writes", &self.allow_driver_writes)
       .field("addr", &self.addr)
       .field("port", &self.port)
+      .field("fallback_addrs", &self.fallback_addrs)
       .field("poll_freq", &self.poll_freq)
       .field("poll_interval", &self.poll_interval)
+      .field("poll_rules", &self.poll_rules)
+      .field("poll_overrides", &self.poll_overrides)
+      .field("stale_threshold", &self.stale_threshold)
       .field("max_conn", &self.max_conn)
+      .field("pool_validation_window", &self.pool_validation_window)
+      .field("pool_max_lifetime", &self.pool_max_lifetime)
+      .field("pool_idle_timeout", &self.pool_idle_timeout)
+      .field("connect_timeout", &self.connect_timeout)
+      .field("request_timeout", &self.request_timeout)
+      .field("reconnect_max_backoff", &self.reconnect_max_backoff)
       .field("tls_mode", &self.tls_mode)
+      .field("ca_file", &self.ca_file)
+      .field("client_cert", &self.client_cert)
+      .field("client_key", &self.client_key)
+      .field("cert_fingerprints", &self.cert_fingerprints)
+      .field("record_file", &self.record_file)
+      .field("monitor", &self.monitor)
+      .field("proxy", &self.proxy.as_ref().map(|v| v.to_string()))
       .finish()
   }
 }
@@ -193,6 +524,209 @@ impl core::fmt::Debug for ServerConfig {
       .field("http_server", &self.http_server)
       .field("upsd", &self.upsd)
       .field("auth", &self.auth)
+      .field("nut_server", &self.nut_server)
+      .field("reverse_dns", &self.reverse_dns)
+      .field("client_aliases", &self.client_aliases)
       .finish()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    ServerConfig, UpsdConfig,
+    error::ValidationError,
+    poll_schedule::{DevicePollConfig, PollRule, PollSchedule},
+  };
+  use core::time::Duration;
+  use nut_webgui_upsmc::{UpsIdentifier, UpsName, ups_status::UpsStatus};
+
+  #[test]
+  fn upsd_socket_addrs() {
+    let config = UpsdConfig {
+      addr: Box::from("::1"),
+      port: 3493,
+      fallback_addrs: vec![
+        Box::from("[::1]"),
+        Box::from("[fd00::2]:3494"),
+        Box::from("fd00::3"),
+        Box::from("10.0.0.4"),
+        Box::from("10.0.0.5:3495"),
+        Box::from("backup.local"),
+        Box::from("backup.local:3496"),
+      ],
+      ..Default::default()
+    };
+
+    assert_eq!(config.get_host(), "::1");
+    assert_eq!(config.get_socket_addr(), "[::1]:3493");
+    assert_eq!(
+      config.get_fallback_socket_addrs().collect::<Vec<_>>(),
+      vec![
+        ("::1", String::from("[::1]:3493")),
+        ("fd00::2", String::from("[fd00::2]:3494")),
+        ("fd00::3", String::from("[fd00::3]:3493")),
+        ("10.0.0.4", String::from("10.0.0.4:3493")),
+        ("10.0.0.5", String::from("10.0.0.5:3495")),
+        ("backup.local", String::from("backup.local:3493")),
+        ("backup.local", String::from("backup.local:3496")),
+      ]
+    );
+
+    let config = UpsdConfig {
+      addr: Box::from("[fd00::1]"),
+      port: 3493,
+      ..Default::default()
+    };
+
+    assert_eq!(config.get_host(), "fd00::1");
+    assert_eq!(config.get_socket_addr(), "[fd00::1]:3493");
+  }
+
+  #[test]
+  fn monitor_sets_upsd_address() {
+    let mut config = UpsdConfig::default();
+    config.set_monitor(vec![
+      "ups0".parse().unwrap(),
+      "ups1@10.0.0.2:3494".parse().unwrap(),
+    ]);
+
+    assert_eq!(config.addr.as_ref(), "10.0.0.2");
+    assert_eq!(config.port, 3494);
+    assert!(config.is_monitored(&UpsName::new_unchecked("ups1")));
+    assert!(!config.is_monitored(&UpsName::new_unchecked("ups2")));
+  }
+
+  #[test]
+  fn identified_by() {
+    let config = UpsdConfig::default();
+    let matches = |v: &str| config.is_identified_by(&v.parse::<UpsIdentifier>().unwrap());
+
+    assert!(matches("ups"));
+    assert!(matches("ups@LOCALHOST"));
+    assert!(matches("ups@localhost:3493"));
+    assert!(!matches("ups@localhost:3494"));
+    assert!(!matches("ups@10.0.0.2"));
+  }
+
+  #[test]
+  fn rejects_duplicate_record_files() {
+    let recorded = |path: &str| UpsdConfig {
+      record_file: Some(path.into()),
+      ..Default::default()
+    };
+
+    let mut config = ServerConfig::new();
+    config.upsd.insert(Box::from("a"), recorded("/tmp/a.txt"));
+    config.upsd.insert(Box::from("b"), recorded("/tmp/b.txt"));
+    config.upsd.insert(Box::from("c"), UpsdConfig::default());
+
+    assert!(config.validate().is_ok());
+
+    config.upsd.insert(Box::from("c"), recorded("/tmp/a.txt"));
+
+    assert!(matches!(
+      config.validate(),
+      Err(ValidationError::DuplicateRecordFile { path }) if path.as_os_str() == "/tmp/a.txt"
+    ));
+  }
+
+  #[test]
+  fn rejects_reserved_namespaces() {
+    let mut config = ServerConfig::new();
+    config
+      .upsd
+      .insert(Box::from("by-id"), UpsdConfig::default());
+
+    assert!(matches!(
+      config.validate(),
+      Err(ValidationError::ReservedNamespace { name }) if name.as_ref() == "by-id"
+    ));
+  }
+
+  #[test]
+  fn poll_schedule_resolution() {
+    let mut config = UpsdConfig {
+      poll_interval: 2,
+      poll_freq: 30,
+      ..Default::default()
+    };
+
+    config.poll_rules = vec![
+      PollRule {
+        status: UpsStatus::ON_BATTERY,
+        stable_for: 0,
+        poll_interval: Some(1),
+        poll_freq: Some(5),
+      },
+      PollRule {
+        status: UpsStatus::ONLINE,
+        stable_for: 600,
+        poll_interval: Some(10),
+        poll_freq: Some(120),
+      },
+    ];
+    config.poll_overrides.insert(
+      UpsName::new_unchecked("slow"),
+      DevicePollConfig {
+        poll_interval: Some(5),
+        poll_freq: None,
+      },
+    );
+
+    let ups = UpsName::new_unchecked("ups");
+    let slow = UpsName::new_unchecked("slow");
+    let stable = Duration::from_secs(900);
+
+    assert_eq!(
+      config.poll_schedule(&ups, UpsStatus::ONLINE, Duration::ZERO),
+      PollSchedule::from_secs(2, 30)
+    );
+    assert_eq!(
+      config.poll_schedule(&ups, UpsStatus::ONLINE, stable),
+      PollSchedule::from_secs(10, 120)
+    );
+    assert_eq!(
+      config.poll_schedule(&slow, UpsStatus::ONLINE, Duration::ZERO),
+      PollSchedule::from_secs(5, 30)
+    );
+    assert_eq!(
+      config.poll_schedule(&slow, UpsStatus::ON_BATTERY | UpsStatus::DISCHARGE, stable),
+      PollSchedule::from_secs(1, 5)
+    );
+  }
+
+  #[test]
+  fn poll_interval_is_limited_by_freq() {
+    let schedule = PollSchedule::from_secs(60, 30);
+
+    assert_eq!(schedule.interval, Duration::from_secs(30));
+    assert_eq!(
+      PollSchedule::from_secs(0, 0).interval,
+      Duration::from_secs(1)
+    );
+  }
+
+  #[test]
+  fn stale_threshold() {
+    let mut config = UpsdConfig {
+      poll_freq: 30,
+      ..Default::default()
+    };
+
+    assert_eq!(config.get_stale_threshold(), Duration::from_secs(90));
+
+    config.poll_rules.push(PollRule {
+      status: UpsStatus::ONLINE,
+      stable_for: 600,
+      poll_interval: None,
+      poll_freq: Some(120),
+    });
+
+    assert_eq!(config.get_stale_threshold(), Duration::from_secs(360));
+
+    config.stale_threshold = 45;
+
+    assert_eq!(config.get_stale_threshold(), Duration::from_secs(45));
+  }
+}
@@ -0,0 +1,144 @@
+use super::error::InvalidCertFingerprintError;
+use serde::{Deserialize, Serialize, de::Visitor};
+use std::str::FromStr;
+
+/// SHA-256 fingerprint of a DER encoded certificate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CertFingerprint([u8; 32]);
+
+impl From<[u8; 32]> for CertFingerprint {
+  #[inline]
+  fn from(value: [u8; 32]) -> Self {
+    Self(value)
+  }
+}
+
+/// Accepts hex strings with or without `:` separators, e.g. output of
+/// `openssl x509 -noout -fingerprint -sha256`.
+impl FromStr for CertFingerprint {
+  type Err = InvalidCertFingerprintError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut bytes = [0u8; 32];
+    let mut digits = s.trim().bytes().filter(|v| *v != b':');
+
+    for byte in bytes.iter_mut() {
+      let high = digits.next().and_then(hex_value);
+      let low = digits.next().and_then(hex_value);
+
+      match (high, low) {
+        (Some(high), Some(low)) => *byte = (high << 4) | low,
+        _ => return Err(InvalidCertFingerprintError),
+      }
+    }
+
+    if digits.next().is_some() {
+      Err(InvalidCertFingerprintError)
+    } else {
+      Ok(Self(bytes))
+    }
+  }
+}
+
+#[inline]
+const fn hex_value(digit: u8) -> Option<u8> {
+  match digit {
+    b'0'..=b'9' => Some(digit - b'0'),
+    b'a'..=b'f' => Some(digit - b'a' + 10),
+    b'A'..=b'F' => Some(digit - b'A' + 10),
+    _ => None,
+  }
+}
+
+impl core::fmt::Display for CertFingerprint {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    for (idx, byte) in self.0.iter().enumerate() {
+      if idx > 0 {
+        f.write_str(":")?;
+      }
+
+      f.write_fmt(format_args!("{:02X}", byte))?;
+    }
+
+    Ok(())
+  }
+}
+
+struct CertFingerprintVisitor;
+
+impl<'de> Visitor<'de> for CertFingerprintVisitor {
+  type Value = CertFingerprint;
+
+  fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+    formatter.write_str("expecting SHA-256 certificate fingerprint")
+  }
+
+  fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+  where
+    E: serde::de::Error,
+  {
+    CertFingerprint::from_str(v).map_err(|err| E::custom(err))
+  }
+}
+
+impl<'de> Deserialize<'de> for CertFingerprint {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    deserializer.deserialize_str(CertFingerprintVisitor)
+  }
+}
+
+impl Serialize for CertFingerprint {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    serializer.collect_str(self)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::CertFingerprint;
+  use std::str::FromStr;
+
+  const FINGERPRINT: &str = "0F:1E:2D:3C:4B:5A:69:78:87:96:A5:B4:C3:D2:E1:F0:00:11:22:33:44:55:66:77:88:99:AA:BB:CC:DD:EE:FF";
+
+  #[test]
+  fn parse_with_separators() {
+    let fingerprint = CertFingerprint::from_str(FINGERPRINT).unwrap();
+    let mut expected = [0u8; 32];
+    expected[..16].copy_from_slice(&[
+      0x0F, 0x1E, 0x2D, 0x3C, 0x4B, 0x5A, 0x69, 0x78, 0x87, 0x96, 0xA5, 0xB4, 0xC3, 0xD2, 0xE1,
+      0xF0,
+    ]);
+    expected[16..].copy_from_slice(&[
+      0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE,
+      0xFF,
+    ]);
+
+    assert_eq!(fingerprint, CertFingerprint::from(expected));
+    assert_eq!(fingerprint.to_string(), FINGERPRINT);
+  }
+
+  #[test]
+  fn parse_without_separators() {
+    let compact = FINGERPRINT.replace(':', "").to_ascii_lowercase();
+    let fingerprint = CertFingerprint::from_str(&compact).unwrap();
+
+    assert_eq!(fingerprint, CertFingerprint::from_str(FINGERPRINT).unwrap());
+  }
+
+  #[test]
+  fn invalid_length() {
+    assert!(CertFingerprint::from_str(&FINGERPRINT[3..]).is_err());
+    assert!(CertFingerprint::from_str(&format!("{FINGERPRINT}:00")).is_err());
+  }
+
+  #[test]
+  fn invalid_digit() {
+    assert!(CertFingerprint::from_str(&FINGERPRINT.replace("0F", "0G")).is_err());
+  }
+}
@@ -1,8 +1,11 @@
 use super::{
-  ConfigLayer, DEFAULT_UPSD_KEY, ServerConfig, TlsMode, UpsdConfig, UriPath, error::EnvConfigError,
+  ConfigLayer, DEFAULT_UPSD_KEY, ServerConfig, TlsMode, UpsdConfig, UriPath,
+  cert_fingerprint::CertFingerprint, client_alias::ClientAlias, driver_socket::DriverSocket,
+  error::EnvConfigError, namespace_type::NamespaceType, proxy_url::ProxyUrl,
   utils::override_opt_field,
 };
 use core::net::IpAddr;
+use nut_webgui_upsmc::UpsIdentifier;
 use std::{
   env,
   fs::File,
@@ -16,6 +19,7 @@ use tracing::level_filters::LevelFilter;
 pub struct ServerEnvArgs {
   pub auth_allow_anoymous_metrics: Option<bool>,
   pub auth_users_file: Option<PathBuf>,
+  pub client_aliases: Option<Vec<ClientAlias>>,
   pub config_file: Option<PathBuf>,
   pub default_theme: Option<Box<str>>,
   pub http_base_path: Option<UriPath>,
@@ -23,15 +27,41 @@ pub struct ServerEnvArgs {
   pub http_port: Option<u16>,
   pub http_worker_count: Option<NonZeroUsize>,
   pub log_level: Option<tracing::level_filters::LevelFilter>,
+  pub nut_server_enabled: Option<bool>,
+  pub nut_server_listen: Option<IpAddr>,
+  pub nut_server_namespace: Option<Box<str>>,
+  pub nut_server_port: Option<u16>,
+  pub reverse_dns_enabled: Option<bool>,
+  pub reverse_dns_negative_ttl: Option<u64>,
+  pub reverse_dns_positive_ttl: Option<u64>,
+  pub reverse_dns_timeout: Option<u64>,
   pub server_key: Option<Box<[u8]>>,
   pub upsd_addr: Option<Box<str>>,
+  pub upsd_allow_driver_writes: Option<bool>,
+  pub upsd_ca_file: Option<PathBuf>,
+  pub upsd_cert_fingerprints: Option<Vec<CertFingerprint>>,
+  pub upsd_client_cert: Option<PathBuf>,
+  pub upsd_client_key: Option<PathBuf>,
+  pub upsd_connect_timeout: Option<u64>,
+  pub upsd_drivers: Option<Vec<DriverSocket>>,
+  pub upsd_fallback_addrs: Option<Vec<Box<str>>>,
   pub upsd_max_conn: Option<NonZeroUsize>,
+  pub upsd_monitor: Option<Vec<UpsIdentifier>>,
   pub upsd_name: Option<Box<str>>,
   pub upsd_pass: Option<Box<str>>,
   pub upsd_poll_freq: Option<u64>,
   pub upsd_poll_interval: Option<u64>,
+  pub upsd_pool_idle_timeout: Option<u64>,
+  pub upsd_pool_max_lifetime: Option<u64>,
+  pub upsd_pool_validation_window: Option<u64>,
   pub upsd_port: Option<u16>,
+  pub upsd_proxy: Option<ProxyUrl>,
+  pub upsd_reconnect_max_backoff: Option<u64>,
+  pub upsd_record_file: Option<PathBuf>,
+  pub upsd_request_timeout: Option<u64>,
+  pub upsd_stale_threshold: Option<u64>,
   pub upsd_tls: Option<TlsMode>,
+  pub upsd_type: Option<NamespaceType>,
   pub upsd_user: Option<Box<str>>,
 }
 
@@ -72,6 +102,32 @@ macro_rules! load_var {
     }
   };
 
+  (@rule $env_name:literal, $target_field:expr, boxed_str_list) => {
+    if let Some(value) = $crate::config::cfg_env::load_from_env($env_name)? {
+      $target_field = Some(
+        value
+          .split(',')
+          .map(|v| v.trim())
+          .filter(|v| !v.is_empty())
+          .map(Box::from)
+          .collect(),
+      );
+    }
+  };
+
+  (@rule $env_name:literal, $target_field:expr, [$item_type:ty]) => {
+    if let Some(value) = $crate::config::cfg_env::load_from_env($env_name)? {
+      $target_field = Some(
+        value
+          .split(',')
+          .map(|v| v.trim())
+          .filter(|v| !v.is_empty())
+          .map(|v| v.parse::<$item_type>())
+          .collect::<Result<Vec<_>, _>>()?,
+      );
+    }
+  };
+
   (@rule $env_name:literal, $target_field:expr, boxed_bytes) => {
     if let Some(value) = $crate::config::cfg_env::load_from_env($env_name)? {
       $target_field = Some(Box::from(value.trim().as_bytes()));
@@ -123,17 +179,46 @@ impl ServerEnvArgs {
       ("NUTWG__HTTP_SERVER__PORT"            ,env_config.http_port                  ,u16);
       ("NUTWG__HTTP_SERVER__WORKER_COUNT"    ,env_config.http_worker_count          ,NonZeroUsize);
 
+      ("NUTWG__NUT_SERVER__ENABLED"          ,env_config.nut_server_enabled         ,boolean);
+      ("NUTWG__NUT_SERVER__LISTEN"           ,env_config.nut_server_listen          ,IpAddr);
+      ("NUTWG__NUT_SERVER__NAMESPACE"        ,env_config.nut_server_namespace       ,boxed_str);
+      ("NUTWG__NUT_SERVER__PORT"             ,env_config.nut_server_port            ,u16);
+
+      ("NUTWG__REVERSE_DNS__ENABLED"         ,env_config.reverse_dns_enabled        ,boolean);
+      ("NUTWG__REVERSE_DNS__NEGATIVE_TTL"    ,env_config.reverse_dns_negative_ttl   ,u64);
+      ("NUTWG__REVERSE_DNS__POSITIVE_TTL"    ,env_config.reverse_dns_positive_ttl   ,u64);
+      ("NUTWG__REVERSE_DNS__TIMEOUT"         ,env_config.reverse_dns_timeout        ,u64);
+      ("NUTWG__CLIENT_ALIASES"               ,env_config.client_aliases             ,[ClientAlias]);
+
       ("NUTWG__AUTH__USERS_FILE"             ,env_config.auth_users_file            ,path_buf);
       ("NUTWG__AUTH__ALLOW_ANONYMOUS_METRICS",env_config.auth_allow_anoymous_metrics,boolean);
 
       ("NUTWG__UPSD__NAME"                   ,env_config.upsd_name                  ,boxed_str);
       ("NUTWG__UPSD__ADDRESS"                ,env_config.upsd_addr                  ,boxed_str);
+      ("NUTWG__UPSD__ALLOW_DRIVER_WRITES"    ,env_config.upsd_allow_driver_writes   ,boolean);
+      ("NUTWG__UPSD__CA_FILE"                ,env_config.upsd_ca_file               ,path_buf);
+      ("NUTWG__UPSD__CERT_FINGERPRINTS"      ,env_config.upsd_cert_fingerprints     ,[CertFingerprint]);
+      ("NUTWG__UPSD__CLIENT_CERT"            ,env_config.upsd_client_cert           ,path_buf);
+      ("NUTWG__UPSD__CLIENT_KEY"             ,env_config.upsd_client_key            ,path_buf);
+      ("NUTWG__UPSD__CONNECT_TIMEOUT"        ,env_config.upsd_connect_timeout       ,u64);
+      ("NUTWG__UPSD__DRIVERS"                ,env_config.upsd_drivers               ,[DriverSocket]);
+      ("NUTWG__UPSD__FALLBACK_ADDRESSES"     ,env_config.upsd_fallback_addrs        ,boxed_str_list);
       ("NUTWG__UPSD__MAX_CONNECTION"         ,env_config.upsd_max_conn              ,NonZeroUsize);
+      ("NUTWG__UPSD__MONITOR"                ,env_config.upsd_monitor               ,[UpsIdentifier]);
       ("NUTWG__UPSD__PASSWORD"               ,env_config.upsd_pass                  ,boxed_str);
       ("NUTWG__UPSD__POLL_FREQ"              ,env_config.upsd_poll_freq             ,u64);
       ("NUTWG__UPSD__POLL_INTERVAL"          ,env_config.upsd_poll_interval         ,u64);
+      ("NUTWG__UPSD__POOL_IDLE_TIMEOUT"      ,env_config.upsd_pool_idle_timeout     ,u64);
+      ("NUTWG__UPSD__POOL_MAX_LIFETIME"      ,env_config.upsd_pool_max_lifetime     ,u64);
+      ("NUTWG__UPSD__POOL_VALIDATION_WINDOW" ,env_config.upsd_pool_validation_window,u64);
       ("NUTWG__UPSD__PORT"                   ,env_config.upsd_port                  ,u16);
+      ("NUTWG__UPSD__PROXY"                  ,env_config.upsd_proxy                 ,ProxyUrl);
+      ("NUTWG__UPSD__RECONNECT_MAX_BACKOFF"  ,env_config.upsd_reconnect_max_backoff ,u64);
+      ("NUTWG__UPSD__RECORD_FILE"            ,env_config.upsd_record_file           ,path_buf);
+      ("NUTWG__UPSD__REQUEST_TIMEOUT"        ,env_config.upsd_request_timeout       ,u64);
+      ("NUTWG__UPSD__STALE_THRESHOLD"        ,env_config.upsd_stale_threshold       ,u64);
       ("NUTWG__UPSD__TLS_MODE"               ,env_config.upsd_tls                   ,TlsMode);
+      ("NUTWG__UPSD__TYPE"                   ,env_config.upsd_type                  ,NamespaceType);
       ("NUTWG__UPSD__USERNAME"               ,env_config.upsd_user                  ,boxed_str);
     );
 
@@ -153,6 +238,28 @@ impl ConfigLayer for ServerEnvArgs {
     override_opt_field!(config.http_server.port, inner_value: self.http_port);
     override_opt_field!(config.http_server.worker_count, self.http_worker_count);
 
+    override_opt_field!(config.nut_server.enabled, inner_value: self.nut_server_enabled);
+    override_opt_field!(config.nut_server.listen, inner_value: self.nut_server_listen);
+    override_opt_field!(config.nut_server.namespace, inner_value: self.nut_server_namespace);
+    override_opt_field!(config.nut_server.port, inner_value: self.nut_server_port);
+
+    override_opt_field!(config.reverse_dns.enabled, inner_value: self.reverse_dns_enabled);
+    override_opt_field!(
+      config.reverse_dns.negative_ttl,
+      inner_value: self.reverse_dns_negative_ttl
+    );
+    override_opt_field!(
+      config.reverse_dns.positive_ttl,
+      inner_value: self.reverse_dns_positive_ttl
+    );
+    override_opt_field!(config.reverse_dns.timeout, inner_value: self.reverse_dns_timeout);
+
+    if let Some(client_aliases) = self.client_aliases {
+      config
+        .client_aliases
+        .extend(client_aliases.into_iter().map(|v| (v.addr, v.name)));
+    }
+
     override_opt_field!(config.auth.users_file, self.auth_users_file);
     override_opt_field!(
       config.auth.allow_anonymous_metrics,
@@ -164,20 +271,57 @@ impl ConfigLayer for ServerEnvArgs {
       .as_ref()
       .map_or(DEFAULT_UPSD_KEY, |v| v.as_ref());
 
-    if self.upsd_addr.is_some() && !config.upsd.contains_key(default_upsd_key) {
+    if (self.upsd_addr.is_some() || self.upsd_drivers.is_some())
+      && !config.upsd.contains_key(default_upsd_key)
+    {
       config
         .upsd
         .insert(Box::from(default_upsd_key), UpsdConfig::default());
     }
 
     if let Some(default_upsd) = config.upsd.get_mut(default_upsd_key) {
+      if let Some(monitor) = self.upsd_monitor {
+        default_upsd.set_monitor(monitor);
+      }
+
       override_opt_field!(default_upsd.addr, inner_value: self.upsd_addr);
+      override_opt_field!(
+        default_upsd.allow_driver_writes,
+        inner_value: self.upsd_allow_driver_writes
+      );
+      override_opt_field!(default_upsd.ca_file, self.upsd_ca_file);
+      override_opt_field!(default_upsd.cert_fingerprints, inner_value: self.upsd_cert_fingerprints);
+      override_opt_field!(default_upsd.client_cert, self.upsd_client_cert);
+      override_opt_field!(default_upsd.client_key, self.upsd_client_key);
+      override_opt_field!(default_upsd.connect_timeout, inner_value: self.upsd_connect_timeout);
+      override_opt_field!(
+        default_upsd.drivers,
+        inner_value: self
+          .upsd_drivers
+          .map(|drivers| drivers.into_iter().map(|v| (v.ups, v.path)).collect())
+      );
+      override_opt_field!(default_upsd.fallback_addrs, inner_value: self.upsd_fallback_addrs);
       override_opt_field!(default_upsd.max_conn, inner_value: self.upsd_max_conn);
       override_opt_field!(default_upsd.pass, self.upsd_pass);
       override_opt_field!(default_upsd.poll_freq, inner_value: self.upsd_poll_freq);
       override_opt_field!(default_upsd.poll_interval, inner_value: self.upsd_poll_interval);
+      override_opt_field!(default_upsd.pool_idle_timeout, inner_value: self.upsd_pool_idle_timeout);
+      override_opt_field!(default_upsd.pool_max_lifetime, inner_value: self.upsd_pool_max_lifetime);
+      override_opt_field!(
+        default_upsd.pool_validation_window,
+        inner_value: self.upsd_pool_validation_window
+      );
       override_opt_field!(default_upsd.port, inner_value: self.upsd_port);
+      override_opt_field!(default_upsd.proxy, self.upsd_proxy);
+      override_opt_field!(
+        default_upsd.reconnect_max_backoff,
+        inner_value: self.upsd_reconnect_max_backoff
+      );
+      override_opt_field!(default_upsd.record_file, self.upsd_record_file);
+      override_opt_field!(default_upsd.request_timeout, inner_value: self.upsd_request_timeout);
+      override_opt_field!(default_upsd.stale_threshold, inner_value: self.upsd_stale_threshold);
       override_opt_field!(default_upsd.tls_mode, inner_value: self.upsd_tls);
+      override_opt_field!(default_upsd.namespace_type, inner_value: self.upsd_type);
       override_opt_field!(default_upsd.user, self.upsd_user);
     }
 
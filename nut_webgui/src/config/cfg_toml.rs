@@ -1,8 +1,16 @@
 use super::{
-  ConfigLayer, ServerConfig, UpsdConfig, error::TomlConfigError, tls_mode::TlsMode,
-  uri_path::UriPath, utils::override_opt_field,
+  ConfigLayer, ServerConfig, UpsdConfig,
+  cert_fingerprint::CertFingerprint,
+  error::TomlConfigError,
+  namespace_type::NamespaceType,
+  poll_schedule::{DevicePollConfig, PollRule},
+  proxy_url::ProxyUrl,
+  tls_mode::TlsMode,
+  uri_path::UriPath,
+  utils::override_opt_field,
 };
 use core::{net::IpAddr, str};
+use nut_webgui_upsmc::{UpsIdentifier, UpsName};
 use serde::{Deserialize, de::Visitor};
 use std::{
   collections::HashMap,
@@ -63,6 +71,9 @@ pub struct ServerTomlArgs {
   pub http_server: Option<HttpServerConfigSection>,
   pub upsd: Option<HashMap<Box<str>, UpsdConfigSection>>,
   pub auth: Option<AuthConfigSection>,
+  pub nut_server: Option<NutServerConfigSection>,
+  pub reverse_dns: Option<ReverseDnsConfigSection>,
+  pub client_aliases: Option<HashMap<IpAddr, Box<str>>>,
 }
 
 #[derive(Deserialize, Default, Debug)]
@@ -73,16 +84,53 @@ pub struct HttpServerConfigSection {
   pub worker_count: Option<NonZeroUsize>,
 }
 
+#[derive(Deserialize, Default, Debug)]
+pub struct ReverseDnsConfigSection {
+  pub enabled: Option<bool>,
+  pub positive_ttl: Option<u64>,
+  pub negative_ttl: Option<u64>,
+  pub timeout: Option<u64>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+pub struct NutServerConfigSection {
+  pub enabled: Option<bool>,
+  pub listen: Option<IpAddr>,
+  pub namespace: Option<Box<str>>,
+  pub port: Option<u16>,
+}
+
 #[derive(Deserialize, Default, Debug)]
 pub struct UpsdConfigSection {
   pub address: Option<Box<str>>,
+  pub allow_driver_writes: Option<bool>,
+  pub ca_file: Option<PathBuf>,
+  pub cert_fingerprints: Option<Vec<CertFingerprint>>,
+  pub client_cert: Option<PathBuf>,
+  pub client_key: Option<PathBuf>,
+  pub connect_timeout: Option<u64>,
+  pub drivers: Option<HashMap<UpsName, PathBuf>>,
+  pub fallback_addresses: Option<Vec<Box<str>>>,
   pub password: Option<Box<str>>,
   pub poll_freq: Option<u64>,
   pub poll_interval: Option<u64>,
+  pub poll_overrides: Option<HashMap<UpsName, DevicePollConfig>>,
+  pub poll_rules: Option<Vec<PollRule>>,
   pub port: Option<u16>,
+  pub proxy: Option<ProxyUrl>,
+  pub reconnect_max_backoff: Option<u64>,
+  pub record_file: Option<PathBuf>,
+  pub request_timeout: Option<u64>,
+  pub stale_threshold: Option<u64>,
   pub username: Option<Box<str>>,
   pub max_connection: Option<NonZeroUsize>,
+  pub monitor: Option<Vec<UpsIdentifier>>,
+  pub pool_validation_window: Option<u64>,
+  pub pool_max_lifetime: Option<u64>,
+  pub pool_idle_timeout: Option<u64>,
   pub tls_mode: Option<TlsMode>,
+  #[serde(rename = "type")]
+  pub namespace_type: Option<NamespaceType>,
 }
 
 #[derive(Deserialize, Default, Debug)]
@@ -128,12 +176,41 @@ impl ConfigLayer for ServerTomlArgs {
       for (key, val) in upsd_section.into_iter() {
         let mut upsd_cfg = UpsdConfig::default();
 
+        if let Some(monitor) = val.monitor {
+          upsd_cfg.set_monitor(monitor);
+        }
+
         override_opt_field!(upsd_cfg.addr, inner_value: val.address);
+        override_opt_field!(upsd_cfg.allow_driver_writes, inner_value: val.allow_driver_writes);
+        override_opt_field!(upsd_cfg.ca_file, val.ca_file);
+        override_opt_field!(upsd_cfg.cert_fingerprints, inner_value: val.cert_fingerprints);
+        override_opt_field!(upsd_cfg.client_cert, val.client_cert);
+        override_opt_field!(upsd_cfg.client_key, val.client_key);
+        override_opt_field!(upsd_cfg.connect_timeout, inner_value: val.connect_timeout);
+        override_opt_field!(upsd_cfg.drivers, inner_value: val.drivers);
+        override_opt_field!(upsd_cfg.fallback_addrs, inner_value: val.fallback_addresses);
         override_opt_field!(upsd_cfg.max_conn, inner_value: val.max_connection);
+        override_opt_field!(upsd_cfg.namespace_type, inner_value: val.namespace_type);
         override_opt_field!(upsd_cfg.pass, val.password);
         override_opt_field!(upsd_cfg.poll_freq, inner_value: val.poll_freq);
         override_opt_field!(upsd_cfg.poll_interval, inner_value: val.poll_interval);
+        override_opt_field!(upsd_cfg.poll_overrides, inner_value: val.poll_overrides);
+        override_opt_field!(upsd_cfg.poll_rules, inner_value: val.poll_rules);
+        override_opt_field!(upsd_cfg.pool_idle_timeout, inner_value: val.pool_idle_timeout);
+        override_opt_field!(upsd_cfg.pool_max_lifetime, inner_value: val.pool_max_lifetime);
+        override_opt_field!(
+          upsd_cfg.pool_validation_window,
+          inner_value: val.pool_validation_window
+        );
         override_opt_field!(upsd_cfg.port, inner_value: val.port);
+        override_opt_field!(upsd_cfg.proxy, val.proxy);
+        override_opt_field!(
+          upsd_cfg.reconnect_max_backoff,
+          inner_value: val.reconnect_max_backoff
+        );
+        override_opt_field!(upsd_cfg.record_file, val.record_file);
+        override_opt_field!(upsd_cfg.request_timeout, inner_value: val.request_timeout);
+        override_opt_field!(upsd_cfg.stale_threshold, inner_value: val.stale_threshold);
         override_opt_field!(upsd_cfg.tls_mode, inner_value : val.tls_mode);
         override_opt_field!(upsd_cfg.user, val.username);
 
@@ -148,6 +225,24 @@ impl ConfigLayer for ServerTomlArgs {
       override_opt_field!(config.http_server.worker_count, http_server.worker_count);
     }
 
+    if let Some(nut_server) = self.nut_server {
+      override_opt_field!(config.nut_server.enabled, inner_value: nut_server.enabled);
+      override_opt_field!(config.nut_server.listen, inner_value: nut_server.listen);
+      override_opt_field!(config.nut_server.namespace, inner_value: nut_server.namespace);
+      override_opt_field!(config.nut_server.port, inner_value: nut_server.port);
+    }
+
+    if let Some(reverse_dns) = self.reverse_dns {
+      override_opt_field!(config.reverse_dns.enabled, inner_value: reverse_dns.enabled);
+      override_opt_field!(config.reverse_dns.negative_ttl, inner_value: reverse_dns.negative_ttl);
+      override_opt_field!(config.reverse_dns.positive_ttl, inner_value: reverse_dns.positive_ttl);
+      override_opt_field!(config.reverse_dns.timeout, inner_value: reverse_dns.timeout);
+    }
+
+    if let Some(client_aliases) = self.client_aliases {
+      config.client_aliases.extend(client_aliases);
+    }
+
     if let Some(auth_config) = self.auth {
       config.auth.users_file = Some(auth_config.users_file);
 
@@ -0,0 +1,81 @@
+use super::error::InvalidClientAliasError;
+use core::net::IpAddr;
+
+/// Client address and display name pair in `<ip>=<name>` format, e.g. `10.0.0.5=nas01`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientAlias {
+  pub addr: IpAddr,
+  pub name: Box<str>,
+}
+
+impl core::str::FromStr for ClientAlias {
+  type Err = InvalidClientAliasError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (addr, name) = s.split_once('=').ok_or(InvalidClientAliasError)?;
+    let addr = addr
+      .trim()
+      .parse::<IpAddr>()
+      .map_err(|_| InvalidClientAliasError)?;
+    let name = name.trim();
+
+    if name.is_empty() {
+      Err(InvalidClientAliasError)
+    } else {
+      Ok(Self {
+        addr,
+        name: Box::from(name),
+      })
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ClientAlias;
+  use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+  use std::{collections::HashMap, str::FromStr};
+
+  #[test]
+  fn parse_client_alias() {
+    let alias = ClientAlias::from_str(" 10.0.0.5 = nas01 ").unwrap();
+
+    assert_eq!(alias.addr, IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5)));
+    assert_eq!(alias.name.as_ref(), "nas01");
+
+    let alias = ClientAlias::from_str("::1=localhost").unwrap();
+
+    assert_eq!(alias.addr, IpAddr::V6(Ipv6Addr::LOCALHOST));
+    assert_eq!(alias.name.as_ref(), "localhost");
+  }
+
+  #[test]
+  fn deserialize_alias_table() {
+    #[derive(serde::Deserialize)]
+    struct Aliases {
+      client_aliases: HashMap<IpAddr, Box<str>>,
+    }
+
+    let aliases: Aliases = toml::from_str(
+      r#"
+      [client_aliases]
+      "10.0.0.5" = "nas01"
+      "fd00::12" = "backup-server"
+      "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+      aliases.client_aliases[&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5))].as_ref(),
+      "nas01"
+    );
+    assert_eq!(aliases.client_aliases.len(), 2);
+  }
+
+  #[test]
+  fn invalid_client_alias() {
+    assert!(ClientAlias::from_str("10.0.0.5").is_err());
+    assert!(ClientAlias::from_str("10.0.0.5=").is_err());
+    assert!(ClientAlias::from_str("nas01=10.0.0.5").is_err());
+  }
+}
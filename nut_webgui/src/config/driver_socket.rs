@@ -0,0 +1,52 @@
+use super::error::InvalidDriverSocketError;
+use nut_webgui_upsmc::UpsName;
+use std::path::PathBuf;
+
+/// UPS name and driver state socket pair in `<ups>=<socket path>` format, e.g.
+/// `myups=/var/run/nut/usbhid-ups-myups`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriverSocket {
+  pub ups: UpsName,
+  pub path: PathBuf,
+}
+
+impl core::str::FromStr for DriverSocket {
+  type Err = InvalidDriverSocketError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (ups, path) = s.split_once('=').ok_or(InvalidDriverSocketError)?;
+    let path = path.trim();
+    let ups = UpsName::try_from(ups.trim().to_owned()).map_err(|_| InvalidDriverSocketError)?;
+
+    if path.is_empty() {
+      Err(InvalidDriverSocketError)
+    } else {
+      Ok(Self {
+        ups,
+        path: PathBuf::from(path),
+      })
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::DriverSocket;
+  use nut_webgui_upsmc::UpsName;
+  use std::{path::Path, str::FromStr};
+
+  #[test]
+  fn parse_driver_socket() {
+    let socket = DriverSocket::from_str(" myups = /var/run/nut/usbhid-ups-myups ").unwrap();
+
+    assert_eq!(socket.ups, UpsName::new_unchecked("myups"));
+    assert_eq!(socket.path, Path::new("/var/run/nut/usbhid-ups-myups"));
+  }
+
+  #[test]
+  fn invalid_driver_socket() {
+    assert!(DriverSocket::from_str("/var/run/nut/usbhid-ups-myups").is_err());
+    assert!(DriverSocket::from_str("myups=").is_err());
+    assert!(DriverSocket::from_str("=/var/run/nut/usbhid-ups-myups").is_err());
+  }
+}
@@ -1,11 +1,19 @@
 use core::{net::AddrParseError, num::ParseIntError};
-use std::ffi::OsString;
+use nut_webgui_upsmc::error::UpsIdentifierParseError;
+use std::{ffi::OsString, path::PathBuf};
 
 #[derive(Debug)]
 pub enum ConfigError {
   File(TomlConfigError),
   Environment(EnvConfigError),
   Arguments(clap::Error),
+  Validation(ValidationError),
+}
+
+#[derive(Debug)]
+pub enum ValidationError {
+  DuplicateRecordFile { path: PathBuf },
+  ReservedNamespace { name: Box<str> },
 }
 
 #[derive(Debug)]
@@ -25,9 +33,15 @@ pub enum UserTomlError {
 pub enum EnvConfigError {
   IOError { inner: std::io::Error },
   InvalidAddrFormat { inner: core::net::AddrParseError },
+  InvalidCertFingerprint,
+  InvalidClientAlias,
+  InvalidDriverSocket,
   InvalidLogLevelFormat,
+  InvalidNamespaceType,
   InvalidNumericFormat,
+  InvalidProxyUrl,
   InvalidTlsMode,
+  InvalidUpsIdentifier { inner: UpsIdentifierParseError },
   InvalidUriPath,
   NonUnicodeVar { variable: OsString },
 }
@@ -38,6 +52,21 @@ pub struct InvalidTlsModeError;
 #[derive(Debug, Clone, Copy)]
 pub struct InvalidPathError;
 
+#[derive(Debug, Clone, Copy)]
+pub struct InvalidCertFingerprintError;
+
+#[derive(Debug, Clone, Copy)]
+pub struct InvalidNamespaceTypeError;
+
+#[derive(Debug, Clone, Copy)]
+pub struct InvalidDriverSocketError;
+
+#[derive(Debug, Clone, Copy)]
+pub struct InvalidProxyUrlError;
+
+#[derive(Debug, Clone, Copy)]
+pub struct InvalidClientAliasError;
+
 impl std::fmt::Display for InvalidPathError {
   #[inline]
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -51,6 +80,38 @@ impl core::fmt::Display for InvalidTlsModeError {
   }
 }
 
+impl core::fmt::Display for InvalidCertFingerprintError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str("not a valid SHA-256 certificate fingerprint")
+  }
+}
+
+impl core::fmt::Display for InvalidNamespaceTypeError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str("not a valid namespace type")
+  }
+}
+
+impl core::fmt::Display for InvalidDriverSocketError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str("driver socket must be in <ups>=<socket path> format")
+  }
+}
+
+impl core::fmt::Display for InvalidProxyUrlError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str(
+      "proxy must be in socks5://[user:pass@]host:port or http://[user:pass@]host:port format",
+    )
+  }
+}
+
+impl core::fmt::Display for InvalidClientAliasError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str("client alias must be in <ip>=<name> format")
+  }
+}
+
 impl std::fmt::Display for EnvConfigError {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
@@ -66,6 +127,21 @@ impl std::fmt::Display for EnvConfigError {
       }
       EnvConfigError::InvalidUriPath => f.write_str("env config: invalid uri path format"),
       EnvConfigError::InvalidTlsMode => f.write_str("env config: invalid tls mode option"),
+      EnvConfigError::InvalidCertFingerprint => {
+        f.write_str("env config: invalid SHA-256 certificate fingerprint")
+      }
+      EnvConfigError::InvalidNamespaceType => f.write_str("env config: invalid namespace type"),
+      EnvConfigError::InvalidUpsIdentifier { inner } => f.write_fmt(format_args!(
+        "env config: invalid ups identifier, {}",
+        inner
+      )),
+      EnvConfigError::InvalidProxyUrl => f.write_str("env config: invalid proxy url"),
+      EnvConfigError::InvalidClientAlias => {
+        f.write_str("env config: client aliases must be in <ip>=<name> format")
+      }
+      EnvConfigError::InvalidDriverSocket => {
+        f.write_str("env config: driver sockets must be in <ups>=<socket path> format")
+      }
     }
   }
 }
@@ -95,6 +171,21 @@ impl std::fmt::Display for ConfigError {
       ConfigError::File(e) => e.fmt(f),
       ConfigError::Environment(e) => e.fmt(f),
       ConfigError::Arguments(e) => e.fmt(f),
+      ConfigError::Validation(e) => e.fmt(f),
+    }
+  }
+}
+
+impl std::fmt::Display for ValidationError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::DuplicateRecordFile { path } => f.write_fmt(format_args!(
+        "config: record_file {} is used by multiple namespaces",
+        path.display()
+      )),
+      Self::ReservedNamespace { name } => {
+        f.write_fmt(format_args!("config: namespace name {} is reserved", name))
+      }
     }
   }
 }
@@ -137,6 +228,13 @@ impl From<TomlConfigError> for ConfigError {
   }
 }
 
+impl From<ValidationError> for ConfigError {
+  #[inline]
+  fn from(value: ValidationError) -> Self {
+    Self::Validation(value)
+  }
+}
+
 impl From<clap::Error> for ConfigError {
   #[inline]
   fn from(value: clap::Error) -> Self {
@@ -151,6 +249,13 @@ impl From<ParseIntError> for EnvConfigError {
   }
 }
 
+impl From<UpsIdentifierParseError> for EnvConfigError {
+  #[inline]
+  fn from(value: UpsIdentifierParseError) -> Self {
+    Self::InvalidUpsIdentifier { inner: value }
+  }
+}
+
 impl From<InvalidPathError> for EnvConfigError {
   #[inline]
   fn from(_: InvalidPathError) -> Self {
@@ -172,6 +277,41 @@ impl From<InvalidTlsModeError> for EnvConfigError {
   }
 }
 
+impl From<InvalidCertFingerprintError> for EnvConfigError {
+  #[inline]
+  fn from(_value: InvalidCertFingerprintError) -> Self {
+    Self::InvalidCertFingerprint
+  }
+}
+
+impl From<InvalidNamespaceTypeError> for EnvConfigError {
+  #[inline]
+  fn from(_value: InvalidNamespaceTypeError) -> Self {
+    Self::InvalidNamespaceType
+  }
+}
+
+impl From<InvalidDriverSocketError> for EnvConfigError {
+  #[inline]
+  fn from(_value: InvalidDriverSocketError) -> Self {
+    Self::InvalidDriverSocket
+  }
+}
+
+impl From<InvalidProxyUrlError> for EnvConfigError {
+  #[inline]
+  fn from(_value: InvalidProxyUrlError) -> Self {
+    Self::InvalidProxyUrl
+  }
+}
+
+impl From<InvalidClientAliasError> for EnvConfigError {
+  #[inline]
+  fn from(_value: InvalidClientAliasError) -> Self {
+    Self::InvalidClientAlias
+  }
+}
+
 impl From<tracing::metadata::ParseLevelFilterError> for EnvConfigError {
   fn from(_: tracing::metadata::ParseLevelFilterError) -> Self {
     Self::InvalidLogLevelFormat
@@ -187,6 +327,12 @@ impl From<std::io::Error> for EnvConfigError {
 impl core::error::Error for ConfigError {}
 impl core::error::Error for EnvConfigError {}
 impl core::error::Error for TomlConfigError {}
+impl core::error::Error for ValidationError {}
 impl core::error::Error for UserTomlError {}
 impl core::error::Error for InvalidTlsModeError {}
+impl core::error::Error for InvalidCertFingerprintError {}
+impl core::error::Error for InvalidNamespaceTypeError {}
+impl core::error::Error for InvalidDriverSocketError {}
+impl core::error::Error for InvalidProxyUrlError {}
+impl core::error::Error for InvalidClientAliasError {}
 impl std::error::Error for InvalidPathError {}
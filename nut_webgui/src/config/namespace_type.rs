@@ -0,0 +1,77 @@
+use super::error::InvalidNamespaceTypeError;
+use serde::{Deserialize, Serialize, de::Visitor};
+use std::str::FromStr;
+
+/// Transport used by a namespace to read UPS devices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NamespaceType {
+  /// Connects to upsd over TCP.
+  #[default]
+  Upsd,
+
+  /// Connects directly to NUT driver state sockets, upsd is not required.
+  Driver,
+}
+
+impl core::str::FromStr for NamespaceType {
+  type Err = InvalidNamespaceTypeError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_ascii_lowercase().as_str() {
+      "upsd" => Ok(Self::Upsd),
+      "driver" => Ok(Self::Driver),
+      _ => Err(InvalidNamespaceTypeError),
+    }
+  }
+}
+
+impl NamespaceType {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      NamespaceType::Upsd => "upsd",
+      NamespaceType::Driver => "driver",
+    }
+  }
+}
+
+impl core::fmt::Display for NamespaceType {
+  #[inline]
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+struct NamespaceTypeVisitor;
+
+impl<'de> Visitor<'de> for NamespaceTypeVisitor {
+  type Value = NamespaceType;
+
+  fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+    formatter.write_str("expecting namespace type, upsd or driver")
+  }
+
+  fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+  where
+    E: serde::de::Error,
+  {
+    NamespaceType::from_str(v).map_err(|err| E::custom(err))
+  }
+}
+
+impl<'de> Deserialize<'de> for NamespaceType {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    deserializer.deserialize_str(NamespaceTypeVisitor)
+  }
+}
+
+impl Serialize for NamespaceType {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    serializer.serialize_str(self.as_str())
+  }
+}
@@ -0,0 +1,105 @@
+use nut_webgui_upsmc::ups_status::UpsStatus;
+use serde::{Deserialize, de::Error};
+use std::time::Duration;
+
+/// Status driven poll interval rule. Applies while a device has all of the `status` flags, and
+/// its status has not changed for at least `stable_for` seconds.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PollRule {
+  #[serde(deserialize_with = "deserialize_status")]
+  pub status: UpsStatus,
+
+  #[serde(default)]
+  pub stable_for: u64,
+
+  pub poll_interval: Option<u64>,
+  pub poll_freq: Option<u64>,
+}
+
+/// Per-device poll interval override.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct DevicePollConfig {
+  pub poll_interval: Option<u64>,
+  pub poll_freq: Option<u64>,
+}
+
+/// Resolved poll intervals of a device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollSchedule {
+  /// `ups.status` sync interval
+  pub interval: Duration,
+
+  /// Full device sync period
+  pub freq: Duration,
+}
+
+impl PollSchedule {
+  /// Creates a schedule from seconds. Interval is kept between 1 second and `freq`.
+  pub fn from_secs(poll_interval: u64, poll_freq: u64) -> Self {
+    let freq = poll_freq.max(1);
+
+    Self {
+      interval: Duration::from_secs(poll_interval.clamp(1, freq)),
+      freq: Duration::from_secs(freq),
+    }
+  }
+}
+
+impl PollRule {
+  #[inline]
+  pub fn is_match(&self, status: UpsStatus, stable_for: Duration) -> bool {
+    status.has(self.status) && stable_for >= Duration::from_secs(self.stable_for)
+  }
+}
+
+fn deserialize_status<'de, D>(deserializer: D) -> Result<UpsStatus, D::Error>
+where
+  D: serde::Deserializer<'de>,
+{
+  let status = UpsStatus::deserialize(deserializer)?;
+
+  if status.is_empty() {
+    Err(D::Error::custom(
+      "poll rule status must contain at least one known status flag",
+    ))
+  } else {
+    Ok(status)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::PollRule;
+  use nut_webgui_upsmc::ups_status::UpsStatus;
+
+  #[derive(serde::Deserialize)]
+  struct Rules {
+    poll_rules: Vec<PollRule>,
+  }
+
+  #[test]
+  fn deserialize_rules() {
+    let rules: Rules = toml::from_str(
+      r#"
+      poll_rules = [
+        { status = "OB", poll_interval = 1, poll_freq = 5 },
+        { status = "OL", stable_for = 600, poll_freq = 120 },
+      ]
+      "#,
+    )
+    .unwrap();
+
+    assert_eq!(rules.poll_rules[0].status, UpsStatus::ON_BATTERY);
+    assert_eq!(rules.poll_rules[0].stable_for, 0);
+    assert_eq!(rules.poll_rules[1].status, UpsStatus::ONLINE);
+    assert_eq!(rules.poll_rules[1].stable_for, 600);
+    assert_eq!(rules.poll_rules[1].poll_interval, None);
+  }
+
+  #[test]
+  fn reject_unknown_status() {
+    let rules = toml::from_str::<Rules>(r#"poll_rules = [{ status = "", poll_freq = 5 }]"#);
+
+    assert!(rules.is_err());
+  }
+}
@@ -0,0 +1,155 @@
+use super::error::InvalidProxyUrlError;
+use nut_webgui_upsmc::client::{Proxy, ProxyAuth};
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, de::Visitor};
+use std::str::FromStr;
+
+/// Proxy used for upsd connections, in `socks5://[user:pass@]host:port` or
+/// `http://[user:pass@]host:port` form.
+#[derive(Debug, Clone)]
+pub struct ProxyUrl(Proxy);
+
+impl ProxyUrl {
+  #[inline]
+  pub fn as_proxy(&self) -> &Proxy {
+    &self.0
+  }
+}
+
+fn decode(value: &str) -> Result<Box<str>, InvalidProxyUrlError> {
+  percent_decode_str(value)
+    .decode_utf8()
+    .map(Box::from)
+    .map_err(|_| InvalidProxyUrlError)
+}
+
+/// Accepts `socks5`, `socks5h` and `http` schemes. Credentials may be percent-encoded.
+impl FromStr for ProxyUrl {
+  type Err = InvalidProxyUrlError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (scheme, rest) = s.trim().split_once("://").ok_or(InvalidProxyUrlError)?;
+    let rest = rest.strip_suffix('/').unwrap_or(rest);
+
+    let (credentials, addr) = match rest.rsplit_once('@') {
+      Some((credentials, addr)) => (Some(credentials), addr),
+      None => (None, rest),
+    };
+
+    let port = addr
+      .rsplit_once(':')
+      .filter(|(host, _)| !host.is_empty() && !host.ends_with(':'))
+      .and_then(|(_, port)| port.parse::<u16>().ok());
+
+    if addr.contains('/') || !matches!(port, Some(1..)) {
+      return Err(InvalidProxyUrlError);
+    }
+
+    let proxy = if scheme.eq_ignore_ascii_case("socks5") || scheme.eq_ignore_ascii_case("socks5h") {
+      Proxy::socks5(addr)
+    } else if scheme.eq_ignore_ascii_case("http") {
+      Proxy::http_connect(addr)
+    } else {
+      return Err(InvalidProxyUrlError);
+    };
+
+    match credentials {
+      Some(credentials) => {
+        let (username, password) = credentials.split_once(':').unwrap_or((credentials, ""));
+        Ok(Self(proxy.with_auth(decode(username)?, decode(password)?)))
+      }
+      None => Ok(Self(proxy)),
+    }
+  }
+}
+
+/// Password is never printed.
+impl core::fmt::Display for ProxyUrl {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let (scheme, addr, auth) = match &self.0 {
+      Proxy::Socks5 { addr, auth } => ("socks5", addr, auth),
+      Proxy::HttpConnect { addr, auth } => ("http", addr, auth),
+    };
+
+    match auth {
+      Some(ProxyAuth { username, .. }) => write!(f, "{scheme}://{username}:******@{addr}"),
+      None => write!(f, "{scheme}://{addr}"),
+    }
+  }
+}
+
+struct ProxyUrlVisitor;
+
+impl<'de> Visitor<'de> for ProxyUrlVisitor {
+  type Value = ProxyUrl;
+
+  fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+    formatter.write_str("expecting proxy url, e.g. socks5://host:1080 or http://host:3128")
+  }
+
+  fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+  where
+    E: serde::de::Error,
+  {
+    ProxyUrl::from_str(v).map_err(|err| E::custom(err))
+  }
+}
+
+impl<'de> Deserialize<'de> for ProxyUrl {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    deserializer.deserialize_str(ProxyUrlVisitor)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ProxyUrl;
+  use nut_webgui_upsmc::client::Proxy;
+  use std::str::FromStr;
+
+  #[test]
+  fn parse_socks5() {
+    let proxy = ProxyUrl::from_str("socks5://bastion:1080").unwrap();
+
+    match proxy.as_proxy() {
+      Proxy::Socks5 { addr, auth } => {
+        assert_eq!(addr.as_ref(), "bastion:1080");
+        assert!(auth.is_none());
+      }
+      proxy => panic!("unexpected proxy {proxy:?}"),
+    }
+
+    assert_eq!(proxy.to_string(), "socks5://bastion:1080");
+  }
+
+  #[test]
+  fn parse_http_with_auth() {
+    let proxy = ProxyUrl::from_str("http://nut%40home:p%3Ass@[::1]:3128/").unwrap();
+
+    match proxy.as_proxy() {
+      Proxy::HttpConnect {
+        addr,
+        auth: Some(auth),
+      } => {
+        assert_eq!(addr.as_ref(), "[::1]:3128");
+        assert_eq!(auth.username.as_ref(), "nut@home");
+        assert_eq!(auth.password.as_ref(), "p:ss");
+      }
+      proxy => panic!("unexpected proxy {proxy:?}"),
+    }
+
+    assert_eq!(proxy.to_string(), "http://nut@home:******@[::1]:3128");
+  }
+
+  #[test]
+  fn invalid_urls() {
+    assert!(ProxyUrl::from_str("bastion:1080").is_err());
+    assert!(ProxyUrl::from_str("https://bastion:3128").is_err());
+    assert!(ProxyUrl::from_str("socks5://bastion").is_err());
+    assert!(ProxyUrl::from_str("socks5://bastion:0").is_err());
+    assert!(ProxyUrl::from_str("http://bastion:3128/path").is_err());
+  }
+}
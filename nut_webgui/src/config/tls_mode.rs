@@ -12,6 +12,10 @@ pub enum TlsMode {
 
   /// Enables TLS, but skips all certificate validations.
   SkipVerify,
+
+  /// Enables TLS when upsd supports it, otherwise falls back to plaintext. Fallbacks are refused
+  /// once TLS is negotiated.
+  Prefer,
 }
 
 impl core::str::FromStr for TlsMode {
@@ -20,6 +24,7 @@ impl core::str::FromStr for TlsMode {
   fn from_str(s: &str) -> Result<Self, Self::Err> {
     match s.to_ascii_lowercase().as_str() {
       "disable" => Ok(Self::Disable),
+      "prefer" => Ok(Self::Prefer),
       "skip" => Ok(Self::SkipVerify),
       "strict" => Ok(Self::Strict),
       _ => Err(InvalidTlsModeError),
@@ -33,6 +38,7 @@ impl TlsMode {
       TlsMode::Disable => "disable",
       TlsMode::Strict => "strict",
       TlsMode::SkipVerify => "skip",
+      TlsMode::Prefer => "prefer",
     }
   }
 }
@@ -1,5 +1,8 @@
 use crate::state::{ConnectionStatus, UpsdNamespace};
-use nut_webgui_upsmc::{UpsName, ups_status::UpsStatus};
+use nut_webgui_upsmc::{
+  UpsName, Value, VarName, json, ups_status::UpsStatus, ups_variables::UpsVariables,
+};
+use serde::Serialize;
 use std::net::IpAddr;
 
 pub mod batch;
@@ -12,6 +15,24 @@ pub struct DeviceStatusChange {
   pub status_new: UpsStatus,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct VariableChange {
+  pub name: VarName,
+  #[serde(serialize_with = "json::serialize_option")]
+  pub value_old: Option<Value>,
+  #[serde(serialize_with = "json::serialize_option")]
+  pub value_new: Option<Value>,
+}
+
+/// Variable differences of a device between two full syncs.
+#[derive(Debug, Clone)]
+pub struct DeviceVariablesChange {
+  pub name: UpsName,
+  pub added: Vec<VariableChange>,
+  pub removed: Vec<VariableChange>,
+  pub changed: Vec<VariableChange>,
+}
+
 #[derive(Debug, Clone)]
 pub struct DeviceClientInfo {
   pub name: UpsName,
@@ -36,6 +57,10 @@ pub enum SystemEvent {
     changes: Vec<DeviceStatusChange>,
     namespace: UpsdNamespace,
   },
+  VariablesChange {
+    changes: Vec<DeviceVariablesChange>,
+    namespace: UpsdNamespace,
+  },
   DaemonStatusUpdate {
     status: ConnectionStatus,
     namespace: UpsdNamespace,
@@ -49,3 +74,160 @@ pub enum SystemEvent {
     namespace: UpsdNamespace,
   },
 }
+
+impl DeviceVariablesChange {
+  /// Compares old and new variables of a device. Returns [`None`] if there is no difference.
+  pub fn diff(name: UpsName, old: &UpsVariables, new: &UpsVariables) -> Option<Self> {
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+    let mut changed = Vec::new();
+
+    for (var_name, value_new) in new.iter() {
+      match old.get(var_name) {
+        Some(value_old) if value_old == value_new => {}
+        Some(value_old) => changed.push(VariableChange {
+          name: var_name.clone(),
+          value_old: Some(value_old.clone()),
+          value_new: Some(value_new.clone()),
+        }),
+        None => added.push(VariableChange {
+          name: var_name.clone(),
+          value_old: None,
+          value_new: Some(value_new.clone()),
+        }),
+      }
+    }
+
+    for (var_name, value_old) in old.iter() {
+      if !new.contains_key(var_name) {
+        removed.push(VariableChange {
+          name: var_name.clone(),
+          value_old: Some(value_old.clone()),
+          value_new: None,
+        });
+      }
+    }
+
+    if added.is_empty() && removed.is_empty() && changed.is_empty() {
+      return None;
+    }
+
+    added.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    removed.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    changed.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    Some(Self {
+      name,
+      added,
+      removed,
+      changed,
+    })
+  }
+
+  /// Compares old and new values of a single variable, [`None`] value means the variable does not
+  /// exist. Returns [`None`] if there is no difference.
+  pub fn diff_one(
+    name: UpsName,
+    var_name: &VarName,
+    old: Option<&Value>,
+    new: Option<&Value>,
+  ) -> Option<Self> {
+    let change = VariableChange {
+      name: var_name.clone(),
+      value_old: old.cloned(),
+      value_new: new.cloned(),
+    };
+
+    let mut diff = Self {
+      name,
+      added: Vec::new(),
+      removed: Vec::new(),
+      changed: Vec::new(),
+    };
+
+    match (old, new) {
+      (Some(old), Some(new)) if old == new => return None,
+      (None, None) => return None,
+      (Some(_), Some(_)) => diff.changed.push(change),
+      (None, Some(_)) => diff.added.push(change),
+      (Some(_), None) => diff.removed.push(change),
+    }
+
+    Some(diff)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::DeviceVariablesChange;
+  use nut_webgui_upsmc::{UpsName, Value, VarName, ups_variables::UpsVariables};
+
+  #[test]
+  fn variables_diff() {
+    let old = UpsVariables::from([
+      (VarName::UPS_STATUS, Value::from("OL")),
+      (VarName::BATTERY_CHARGE, Value::from(100)),
+      (
+        VarName::new_unchecked("ups.beeper.status"),
+        Value::from("enabled"),
+      ),
+    ]);
+    let new = UpsVariables::from([
+      (VarName::UPS_STATUS, Value::from("OB DISCHRG")),
+      (VarName::BATTERY_CHARGE, Value::from(100)),
+      (VarName::BATTERY_RUNTIME, Value::from(1200)),
+    ]);
+
+    let diff = DeviceVariablesChange::diff(UpsName::new_unchecked("ups"), &old, &new).unwrap();
+
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.added[0].name, VarName::BATTERY_RUNTIME);
+    assert_eq!(diff.added[0].value_old, None);
+    assert_eq!(diff.added[0].value_new, Some(Value::from(1200)));
+
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].name.as_str(), "ups.beeper.status");
+    assert_eq!(diff.removed[0].value_old, Some(Value::from("enabled")));
+    assert_eq!(diff.removed[0].value_new, None);
+
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].name, VarName::UPS_STATUS);
+    assert_eq!(diff.changed[0].value_old, Some(Value::from("OL")));
+    assert_eq!(diff.changed[0].value_new, Some(Value::from("OB DISCHRG")));
+  }
+
+  #[test]
+  fn variables_diff_without_changes() {
+    let vars = UpsVariables::from([(VarName::UPS_STATUS, Value::from("OL"))]);
+    let diff = DeviceVariablesChange::diff(UpsName::new_unchecked("ups"), &vars, &vars.clone());
+
+    assert!(diff.is_none());
+  }
+
+  #[test]
+  fn variable_diff_one() {
+    let ups = UpsName::new_unchecked("ups");
+    let ol = Value::from("OL");
+    let ob = Value::from("OB");
+
+    let diff =
+      DeviceVariablesChange::diff_one(ups.clone(), &VarName::UPS_STATUS, Some(&ol), Some(&ob))
+        .unwrap();
+    assert!(diff.added.is_empty() && diff.removed.is_empty());
+    assert_eq!(diff.changed[0].value_old, Some(ol.clone()));
+    assert_eq!(diff.changed[0].value_new, Some(ob));
+
+    let diff =
+      DeviceVariablesChange::diff_one(ups.clone(), &VarName::UPS_STATUS, None, Some(&ol)).unwrap();
+    assert_eq!(diff.added.len(), 1);
+
+    let diff =
+      DeviceVariablesChange::diff_one(ups.clone(), &VarName::UPS_STATUS, Some(&ol), None).unwrap();
+    assert_eq!(diff.removed.len(), 1);
+
+    assert!(
+      DeviceVariablesChange::diff_one(ups, &VarName::UPS_STATUS, Some(&ol), Some(&ol.clone()))
+        .is_none()
+    );
+  }
+}
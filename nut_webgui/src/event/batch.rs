@@ -1,9 +1,11 @@
 use super::{
-  DeviceClientInfo, DeviceStatusChange, SystemEvent,
+  DeviceClientInfo, DeviceStatusChange, DeviceVariablesChange, SystemEvent,
   channel::{ChannelSendError, EventChannel},
 };
 use crate::state::{ConnectionStatus, UpsdNamespace};
-use nut_webgui_upsmc::{UpsName, ups_status::UpsStatus};
+use nut_webgui_upsmc::{
+  UpsName, Value, VarName, ups_status::UpsStatus, ups_variables::UpsVariables,
+};
 use std::net::IpAddr;
 
 /// This struct does not exactly send all events as a single message, but simply aggregates all events in
@@ -15,6 +17,7 @@ pub struct EventBatch {
   removed: Vec<UpsName>,
   status_changes: Vec<DeviceStatusChange>,
   updated: Vec<UpsName>,
+  variable_changes: Vec<DeviceVariablesChange>,
   disconnections: Vec<DeviceClientInfo>,
   connections: Vec<DeviceClientInfo>,
 }
@@ -27,6 +30,7 @@ impl EventBatch {
       removed: Vec::new(),
       status_changes: Vec::new(),
       updated: Vec::new(),
+      variable_changes: Vec::new(),
       upsd_status: None,
       disconnections: Vec::new(),
       connections: Vec::new(),
@@ -57,6 +61,29 @@ impl EventBatch {
     });
   }
 
+  /// Records added, removed and changed variables. Nothing is recorded when variables are the
+  /// same.
+  #[inline]
+  pub fn variables_change(&mut self, name: UpsName, old: &UpsVariables, new: &UpsVariables) {
+    if let Some(change) = DeviceVariablesChange::diff(name, old, new) {
+      self.variable_changes.push(change);
+    }
+  }
+
+  /// Records a single variable update, [`None`] value means the variable does not exist.
+  #[inline]
+  pub fn variable_change(
+    &mut self,
+    name: UpsName,
+    var_name: &VarName,
+    old: Option<&Value>,
+    new: Option<&Value>,
+  ) {
+    if let Some(change) = DeviceVariablesChange::diff_one(name, var_name, old, new) {
+      self.variable_changes.push(change);
+    }
+  }
+
   #[inline]
   pub fn client_connection(&mut self, name: UpsName, connected: Vec<IpAddr>) {
     self.connections.push(DeviceClientInfo {
@@ -107,6 +134,13 @@ impl EventBatch {
       })?;
     }
 
+    if !self.variable_changes.is_empty() {
+      channel.send(SystemEvent::VariablesChange {
+        changes: self.variable_changes,
+        namespace: self.namespace.clone(),
+      })?;
+    }
+
     if !self.disconnections.is_empty() {
       channel.send(SystemEvent::ClientDisconnection {
         devices: self.disconnections,
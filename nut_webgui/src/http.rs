@@ -31,6 +31,7 @@ pub mod hypermedia;
 pub mod json_api;
 pub mod metric;
 pub mod probe;
+pub mod tracking;
 
 pub struct HttpServer {
   server_state: Arc<ServerState>,
@@ -222,6 +223,10 @@ fn create_hypermedia_routes(server_state: Arc<ServerState>) -> Router<Arc<Server
     .route("/topology", get(hypermedia::route::topology::get))
     .route("/connection", get(hypermedia::route::connection::get))
     .route("/system", get(hypermedia::route::system::get))
+    .route(
+      "/ups/by-id/{identifier}",
+      get(hypermedia::route::ups::by_id::get),
+    )
     .route(
       "/ups/{namespace}/{ups_name}",
       get(hypermedia::route::ups::get),
@@ -1,5 +1,5 @@
 use super::error::HandshakeError;
-use crate::state::ConnectionStatus;
+use crate::{event::VariableChange, state::ConnectionStatus};
 use axum::extract::ws::Message;
 use nut_webgui_upsmc::{UpsName, ups_event::UpsEvents, ups_status::UpsStatus};
 use serde::Serialize;
@@ -31,6 +31,14 @@ pub enum NutEventMessage<'a> {
     events: UpsEvents,
     timestamp: i64,
   },
+  VariablesChanged {
+    name: &'a UpsName,
+    namespace: &'a str,
+    added: &'a [VariableChange],
+    removed: &'a [VariableChange],
+    changed: &'a [VariableChange],
+    timestamp: i64,
+  },
   DaemonStatus {
     namespace: &'a str,
     status: ConnectionStatus,
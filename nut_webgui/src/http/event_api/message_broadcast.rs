@@ -1,7 +1,9 @@
 use super::message::NutEventMessage;
 use crate::{
   background_service::BackgroundService,
-  event::{DeviceClientInfo, DeviceStatusChange, SystemEvent, channel::EventChannel},
+  event::{
+    DeviceClientInfo, DeviceStatusChange, DeviceVariablesChange, SystemEvent, channel::EventChannel,
+  },
   state::ConnectionStatus,
 };
 use chrono::Utc;
@@ -93,6 +95,9 @@ impl SerializeTask {
       SystemEvent::DeviceStatusChange { changes, namespace } => {
         Self::process_device_status_update(changes, namespace, timestamp)
       }
+      SystemEvent::VariablesChange { changes, namespace } => {
+        Self::process_variables_change(changes, namespace, timestamp)
+      }
       SystemEvent::DaemonStatusUpdate { status, namespace } => {
         Self::process_daemon_state(*status, namespace, timestamp)
       }
@@ -205,6 +210,30 @@ impl SerializeTask {
     Ok(Arc::from(data))
   }
 
+  fn process_variables_change(
+    changes: &[DeviceVariablesChange],
+    namespace: &str,
+    timestamp: i64,
+  ) -> Result<MessagePayload, serde_json::error::Error> {
+    let mut data = Vec::with_capacity(changes.len());
+
+    for change in changes {
+      let message = NutEventMessage::VariablesChanged {
+        name: &change.name,
+        namespace,
+        added: &change.added,
+        removed: &change.removed,
+        changed: &change.changed,
+        timestamp,
+      };
+
+      let value = serde_json::to_string(&message)?;
+      data.push(value);
+    }
+
+    Ok(Arc::from(data))
+  }
+
   fn process_daemon_state(
     status: ConnectionStatus,
     namespace: &str,
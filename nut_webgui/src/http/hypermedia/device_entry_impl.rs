@@ -20,6 +20,14 @@ impl DeviceEntry {
     }
   }
 
+  /// Alarm details from `ups.alarm`, drivers report them while `ALARM` status is set.
+  pub fn get_alarm(&self) -> Option<&str> {
+    match self.variables.get(VarName::UPS_ALARM) {
+      Some(Value::String(v)) if !v.trim().is_empty() => Some(v.trim()),
+      _ => None,
+    }
+  }
+
   pub fn get_ups_temperature(&self) -> Option<Celcius> {
     let temp_var = self.variables.get(VarName::UPS_TEMPERATURE)?;
 
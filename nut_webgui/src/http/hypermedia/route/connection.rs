@@ -12,6 +12,7 @@ use axum::{
   extract::{Query, State},
   response::{Html, IntoResponse, Response},
 };
+use nut_webgui_upsmc::UpsName;
 use serde::Deserialize;
 
 #[derive(Deserialize)]
@@ -35,6 +36,7 @@ struct ConnectionPageTemplate<'a> {
 struct UpsdInfoTemplate<'a> {
   config: &'a UpsdConfig,
   state: &'a DaemonState,
+  logins: Vec<(&'a UpsName, Option<usize>)>,
   name: &'a str,
 }
 
@@ -48,8 +50,16 @@ pub async fn get(
 
   for upsd in state.upsd_servers.values() {
     let daemon_state = upsd.daemon_state.read().await;
+    let mut logins: Vec<_> = daemon_state
+      .devices
+      .values()
+      .map(|device| (&device.name, device.num_logins))
+      .collect();
+
+    logins.sort_unstable_by_key(|(name, _)| *name);
 
     let html = UpsdInfoTemplate {
+      logins,
       state: &daemon_state,
       config: &upsd.config,
       name: &upsd.namespace,
@@ -208,6 +208,10 @@ impl TopologyGraphBuilder {
             || device.status.has(UpsStatus::REPLACE_BATTERY)
             || device.status.has(UpsStatus::ALARM)
             || device.status.has(UpsStatus::OVERLOADED)
+            || device.status.has(UpsStatus::COMM_BAD)
+            || device.status.has(UpsStatus::FAN_FAILURE)
+            || device.status.has(UpsStatus::NO_BATTERY)
+            || device.status.has(UpsStatus::OVERHEAT)
           {
             SemanticType::Error
           } else if device.status.has(UpsStatus::ON_BATTERY)
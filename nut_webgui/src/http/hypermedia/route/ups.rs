@@ -24,6 +24,7 @@ use std::{
 };
 use tokio::sync::RwLockReadGuard;
 
+pub mod by_id;
 pub mod fsd;
 pub mod instcmd;
 pub mod rw;
@@ -0,0 +1,51 @@
+use crate::{
+  http::hypermedia::{error::ErrorPage, util::redirect_not_found},
+  state::ServerState,
+};
+use axum::{
+  extract::{Path, State},
+  response::{IntoResponse, Redirect, Response},
+};
+use nut_webgui_upsmc::UpsIdentifier;
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use std::sync::Arc;
+
+/// Resolves `upsname[@hostname[:port]]` identifiers to the device page. Namespaces are searched in
+/// name order, identifiers without a hostname redirect to the first namespace with the device.
+pub async fn get(
+  State(state): State<Arc<ServerState>>,
+  Path(identifier): Path<String>,
+) -> Result<Response, ErrorPage> {
+  let identifier: UpsIdentifier = identifier
+    .parse()
+    .map_err(|err| ErrorPage::new(format!("invalid ups identifier {identifier}, {err}")))?;
+
+  let mut namespaces: Vec<_> = state
+    .upsd_servers
+    .iter()
+    .filter(|(_, upsd)| upsd.config.is_identified_by(&identifier))
+    .collect();
+
+  namespaces.sort_unstable_by_key(|(namespace, _)| *namespace);
+
+  for (namespace, upsd) in namespaces {
+    if upsd
+      .daemon_state
+      .read()
+      .await
+      .devices
+      .contains_key(identifier.name())
+    {
+      let location = format!(
+        "{base_path}/ups/{namespace}/{ups_name}",
+        base_path = state.config.http_server.base_path,
+        namespace = utf8_percent_encode(namespace, NON_ALPHANUMERIC),
+        ups_name = utf8_percent_encode(identifier.name().as_str(), NON_ALPHANUMERIC),
+      );
+
+      return Ok(Redirect::to(&location).into_response());
+    }
+  }
+
+  Ok(redirect_not_found!(&state))
+}
@@ -2,12 +2,14 @@ use std::sync::Arc;
 
 use crate::{
   auth::user_session::UserSession,
-  config::UpsdConfig,
-  http::hypermedia::{
-    error::ErrorPage,
-    notification::NotificationTemplate,
-    semantic_type::SemanticType,
-    util::{RenderWithConfig, redirect_not_found},
+  http::{
+    hypermedia::{
+      error::ErrorPage,
+      notification::NotificationTemplate,
+      semantic_type::SemanticType,
+      util::{RenderWithConfig, redirect_not_found},
+    },
+    tracking::{WriteClient, WriteClientError},
   },
   state::ServerState,
 };
@@ -34,16 +36,12 @@ pub async fn post(
   }
 
   let session = session.map(|v| v.0);
-  let auth_client = match &upsd.config {
-    UpsdConfig {
-      pass: Some(pass),
-      user: Some(user),
-      ..
-    } => {
-      let client = upsd.connection_pool.get_client().await?;
-      client.authenticate(user, pass).await
-    }
-    _ => {
+  let auth_client = match WriteClient::connect(upsd, &ups_name).await {
+    Ok(client) => Ok(client),
+    Err(WriteClientError::Authentication(err)) => Err(err),
+    Err(WriteClientError::Connection(err)) => return Err(ErrorPage::from(err)),
+    Err(WriteClientError::UpsNotFound) => return Ok(redirect_not_found!(&state)),
+    Err(WriteClientError::MissingCredentials) => {
       return Ok(
         Html(
           NotificationTemplate::from(
@@ -54,6 +52,17 @@ pub async fn post(
         .into_response(),
       );
     }
+    Err(WriteClientError::DriverWritesDisabled) => {
+      return Ok(
+        Html(
+          NotificationTemplate::from(
+            "Driver writes are not allowed for this namespace. Server is in read-only mode.",
+          )
+          .render_with_config(&state.config, session.as_ref())?,
+        )
+        .into_response(),
+      );
+    }
   };
 
   let fsd_result = match auth_client {
@@ -1,11 +1,13 @@
 use crate::{
   auth::user_session::UserSession,
-  config::UpsdConfig,
-  http::hypermedia::{
-    error::ErrorPage,
-    notification::NotificationTemplate,
-    semantic_type::SemanticType,
-    util::{RenderWithConfig, redirect_not_found},
+  http::{
+    hypermedia::{
+      error::ErrorPage,
+      notification::NotificationTemplate,
+      semantic_type::SemanticType,
+      util::{RenderWithConfig, redirect_not_found},
+    },
+    tracking::{WriteClient, WriteClientError},
   },
   state::ServerState,
 };
@@ -14,7 +16,7 @@ use axum::{
   extract::{Path, State},
   response::{Html, IntoResponse, Response},
 };
-use nut_webgui_upsmc::{CmdName, UpsName};
+use nut_webgui_upsmc::{CmdName, UpsName, Value, response::TrackingStatus};
 use serde::Deserialize;
 use std::sync::Arc;
 use tracing::{error, info};
@@ -22,6 +24,19 @@ use tracing::{error, info};
 #[derive(Deserialize, Debug)]
 pub struct CommandRequest {
   command: CmdName,
+  value: Option<Box<str>>,
+}
+
+impl CommandRequest {
+  /// Command argument from the form, blank inputs are treated as no argument.
+  fn value(&self) -> Option<Value> {
+    self
+      .value
+      .as_deref()
+      .map(str::trim)
+      .filter(|v| !v.is_empty())
+      .map(Value::from)
+  }
 }
 
 pub async fn post(
@@ -40,16 +55,14 @@ pub async fn post(
   }
 
   let session = session.map(|v| v.0);
-  let auth_client = match &upsd.config {
-    UpsdConfig {
-      pass: Some(pass),
-      user: Some(user),
-      ..
-    } => {
-      let client = upsd.connection_pool.get_client().await?;
-      client.authenticate(user, pass).await
-    }
-    _ => {
+  let value = request.value();
+
+  let auth_client = match WriteClient::connect(upsd, &ups_name).await {
+    Ok(client) => Ok(client),
+    Err(WriteClientError::Authentication(err)) => Err(err),
+    Err(WriteClientError::Connection(err)) => return Err(ErrorPage::from(err)),
+    Err(WriteClientError::UpsNotFound) => return Ok(redirect_not_found!(&state)),
+    Err(WriteClientError::MissingCredentials) => {
       return Ok(
         Html(
           NotificationTemplate::from(
@@ -60,11 +73,24 @@ pub async fn post(
         .into_response(),
       );
     }
+    Err(WriteClientError::DriverWritesDisabled) => {
+      return Ok(
+        Html(
+          NotificationTemplate::from(
+            "Driver writes are not allowed for this namespace. Server is in read-only mode.",
+          )
+          .render_with_config(&state.config, session.as_ref())?,
+        )
+        .into_response(),
+      );
+    }
   };
 
   let cmd_result = match auth_client {
     Ok(mut client) => {
-      let result = client.instcmd(&ups_name, &request.command).await;
+      let result = client
+        .instcmd(&ups_name, &request.command, value.as_ref())
+        .await;
       _ = client.close().await;
       result
     }
@@ -72,12 +98,13 @@ pub async fn post(
   };
 
   let template = match cmd_result {
-    Ok(_) => {
+    Ok(Some(TrackingStatus::Success)) => {
       info!(
-        message = "instcmd called successfully",
+        message = "instcmd completed successfully",
         namespace = %namespace,
         device_name = %ups_name,
-        cmd = %request.command
+        cmd = %request.command,
+        value = ?value
       );
 
       NotificationTemplate::from(format!(
@@ -86,12 +113,44 @@ pub async fn post(
       ))
       .set_level(SemanticType::Success)
     }
+    Ok(Some(TrackingStatus::Failed(err))) => {
+      error!(
+        message = "instcmd rejected by device driver",
+        namespace = %namespace,
+        device_name = %ups_name,
+        cmd = %request.command,
+        value = ?value,
+        reason = %err
+      );
+
+      NotificationTemplate::from(format!(
+        "'{0}' failed on {1}, {2}",
+        &request.command, &ups_name, err
+      ))
+      .set_level(SemanticType::Error)
+    }
+    Ok(Some(TrackingStatus::Pending) | None) => {
+      info!(
+        message = "instcmd called successfully",
+        namespace = %namespace,
+        device_name = %ups_name,
+        cmd = %request.command,
+        value = ?value
+      );
+
+      NotificationTemplate::from(format!(
+        "'{0}' is accepted by {1}, but its result is not confirmed yet.",
+        &request.command, &ups_name
+      ))
+      .set_level(SemanticType::Info)
+    }
     Err(err) => {
       error!(
         message = "instcmd call failed",
         namespace = %namespace,
         device_name = %ups_name,
         cmd = %request.command,
+        value = ?value,
         reason = %err
       );
 
@@ -102,3 +161,42 @@ pub async fn post(
 
   Ok(Html(template.render_with_config(&state.config, session.as_ref())?).into_response())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::CommandRequest;
+  use axum::{
+    Form,
+    body::Body,
+    extract::FromRequest,
+    http::{Request, header},
+  };
+  use nut_webgui_upsmc::{CmdName, Value};
+
+  async fn parse_form(body: &'static str) -> CommandRequest {
+    let request = Request::post("/")
+      .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+      .body(Body::from(body))
+      .unwrap();
+
+    let Form(request) = Form::<CommandRequest>::from_request(request, &())
+      .await
+      .unwrap();
+
+    request
+  }
+
+  #[tokio::test]
+  async fn form_value() {
+    let request = parse_form("command=load.off.delay&value=+120+").await;
+
+    assert_eq!(request.command, CmdName::new_unchecked("load.off.delay"));
+    assert_eq!(request.value(), Some(Value::from("120")));
+  }
+
+  #[tokio::test]
+  async fn form_without_value() {
+    assert_eq!(parse_form("command=beeper.on").await.value(), None);
+    assert_eq!(parse_form("command=beeper.on&value=++").await.value(), None);
+  }
+}
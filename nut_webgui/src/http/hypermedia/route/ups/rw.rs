@@ -1,12 +1,14 @@
 use crate::{
   auth::user_session::UserSession,
-  config::UpsdConfig,
-  http::hypermedia::{
-    error::ErrorPage,
-    notification::NotificationTemplate,
-    route::ups::RwFormTemplate,
-    semantic_type::SemanticType,
-    util::{RenderWithConfig, htmx_swap, redirect_not_found},
+  http::{
+    hypermedia::{
+      error::ErrorPage,
+      notification::NotificationTemplate,
+      route::ups::RwFormTemplate,
+      semantic_type::SemanticType,
+      util::{RenderWithConfig, htmx_swap, redirect_not_found},
+    },
+    tracking::{WriteClient, WriteClientError},
   },
   state::{ServerState, VarDetail},
 };
@@ -15,7 +17,7 @@ use axum::{
   extract::{Path, State},
   response::{Html, IntoResponse, Response},
 };
-use nut_webgui_upsmc::{InferValueFrom, UpsName, Value, VarName};
+use nut_webgui_upsmc::{InferValueFrom, UpsName, Value, VarName, response::TrackingStatus};
 use serde::Deserialize;
 use std::{sync::Arc, time::Duration};
 use tracing::{error, info};
@@ -69,25 +71,28 @@ fn validate_request(request_value: Box<str>, detail: &VarDetail) -> ValidationRe
         }
       }
     }
-    VarDetail::Range { min, max } => match Value::infer_number_from(request_value.as_ref()) {
-      Ok(value) => match (min.as_lossy_f64(), max.as_lossy_f64()) {
-        (Some(min), Some(max)) => {
-          let valuef64 = value.as_lossy_f64().unwrap_or(0.0);
-
-          if min <= valuef64 && valuef64 <= max {
-            ValidationResult::Valid { value }
-          } else {
-            ValidationResult::Invalid {
-              value: Value::from(request_value),
-              message: "value is not in range",
-            }
+    VarDetail::Range { ranges } => match Value::infer_number_from(request_value.as_ref()) {
+      Ok(value) => {
+        let valuef64 = value.as_lossy_f64().unwrap_or(0.0);
+        let mut checks = ranges
+          .iter()
+          .filter_map(|range| range.contains(valuef64))
+          .peekable();
+
+        if checks.peek().is_none() {
+          ValidationResult::Invalid {
+            value: Value::from(request_value),
+            message: "driver reported min-max values are not numeric values",
+          }
+        } else if checks.any(|is_within| is_within) {
+          ValidationResult::Valid { value }
+        } else {
+          ValidationResult::Invalid {
+            value: Value::from(request_value),
+            message: "value is not in range",
           }
         }
-        _ => ValidationResult::Invalid {
-          value: Value::from(request_value),
-          message: "driver reported min-max values are not numeric values",
-        },
-      },
+      }
       Err(_) => ValidationResult::Invalid {
         value: Value::from(request_value),
         message: "input is not a number",
@@ -163,16 +168,12 @@ pub async fn patch(
     (value, var_detail.clone())
   };
 
-  let auth_client = match &upsd.config {
-    UpsdConfig {
-      pass: Some(pass),
-      user: Some(user),
-      ..
-    } => {
-      let client = upsd.connection_pool.get_client().await?;
-      client.authenticate(user, pass).await
-    }
-    _ => {
+  let auth_client = match WriteClient::connect(upsd, &ups_name).await {
+    Ok(client) => Ok(client),
+    Err(WriteClientError::Authentication(err)) => Err(err),
+    Err(WriteClientError::Connection(err)) => return Err(ErrorPage::from(err)),
+    Err(WriteClientError::UpsNotFound) => return Ok(redirect_not_found!(&state)),
+    Err(WriteClientError::MissingCredentials) => {
       return Ok(htmx_swap!(
         Html(
           NotificationTemplate::from(
@@ -183,6 +184,17 @@ pub async fn patch(
         "none"
       ));
     }
+    Err(WriteClientError::DriverWritesDisabled) => {
+      return Ok(htmx_swap!(
+        Html(
+          NotificationTemplate::from(
+            "Driver writes are not allowed for this namespace. Server is in read-only mode.",
+          )
+          .render_with_config(&state.config, session.as_ref())?
+        ),
+        "none"
+      ));
+    }
   };
 
   let response = match auth_client {
@@ -191,7 +203,44 @@ pub async fn patch(
       _ = auth_client.close().await;
 
       let (semantic, message, notification) = match result {
-        Ok(_) => {
+        Ok(Some(TrackingStatus::Success)) => {
+          info!(
+            message = "set var request completed",
+            namespace = %namespace,
+            device = %ups_name,
+            value = %value,
+            name = %request.name
+          );
+
+          (
+            SemanticType::Success,
+            None,
+            Some(
+              NotificationTemplate::from("Variable is updated").set_level(SemanticType::Success),
+            ),
+          )
+        }
+        Ok(Some(TrackingStatus::Failed(err))) => {
+          error!(
+            message = "set var request rejected by device driver",
+            namespace = %namespace,
+            device = %ups_name,
+            value = %value,
+            name = %request.name,
+            reason = %err
+          );
+
+          (
+            SemanticType::Error,
+            Some("value is rejected by device driver"),
+            Some(
+              NotificationTemplate::from(format!("Set var request failed, {}", err))
+                .set_level(SemanticType::Error)
+                .set_ttl(Duration::from_secs(15)),
+            ),
+          )
+        }
+        Ok(Some(TrackingStatus::Pending) | None) => {
           info!(
             message = "set var request accepted",
             namespace = %namespace,
@@ -268,3 +317,93 @@ pub async fn patch(
 
   Ok(response)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{ValidationResult, validate_request};
+  use crate::state::{VarDetail, VarRange};
+  use nut_webgui_upsmc::Value;
+
+  /// Two disjoint intervals, e.g. low and high transfer voltage options: `[90, 100]` and
+  /// `[120, 140]`.
+  fn multi_range() -> VarDetail {
+    VarDetail::Range {
+      ranges: vec![
+        VarRange {
+          min: Value::from(90),
+          max: Value::from(100),
+        },
+        VarRange {
+          min: Value::from(120),
+          max: Value::from(140),
+        },
+      ],
+    }
+  }
+
+  fn is_valid(input: &str, detail: &VarDetail) -> bool {
+    matches!(
+      validate_request(Box::from(input), detail),
+      ValidationResult::Valid { .. }
+    )
+  }
+
+  #[test]
+  fn value_inside_ranges() {
+    let detail = multi_range();
+
+    assert!(is_valid("95", &detail));
+    assert!(is_valid("130", &detail));
+    assert!(is_valid("97.5", &detail));
+  }
+
+  #[test]
+  fn value_on_range_edges() {
+    let detail = multi_range();
+
+    assert!(is_valid("90", &detail));
+    assert!(is_valid("100", &detail));
+    assert!(is_valid("120", &detail));
+    assert!(is_valid("140", &detail));
+    assert!(is_valid("100.0", &detail));
+  }
+
+  #[test]
+  fn value_between_ranges() {
+    let detail = multi_range();
+
+    assert!(!is_valid("110", &detail));
+    assert!(!is_valid("100.5", &detail));
+    assert!(!is_valid("119.9", &detail));
+    assert!(!is_valid("89", &detail));
+    assert!(!is_valid("141", &detail));
+  }
+
+  #[test]
+  fn invalid_range_input() {
+    let detail = multi_range();
+
+    assert!(matches!(
+      validate_request(Box::from("abc"), &detail),
+      ValidationResult::Invalid {
+        message: "input is not a number",
+        ..
+      }
+    ));
+
+    let non_numeric = VarDetail::Range {
+      ranges: vec![VarRange {
+        min: Value::from("low"),
+        max: Value::from("high"),
+      }],
+    };
+
+    assert!(matches!(
+      validate_request(Box::from("95"), &non_numeric),
+      ValidationResult::Invalid {
+        message: "driver reported min-max values are not numeric values",
+        ..
+      }
+    ));
+  }
+}
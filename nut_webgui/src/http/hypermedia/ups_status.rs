@@ -123,6 +123,48 @@ fn get_status_detail(status: UpsStatus) -> Option<StatusDetail> {
       desc: "Battery is disconnected for maintenance",
       name: "BYPASS",
     }),
+    UpsStatus::COMM_BAD => Some(StatusDetail {
+      icon_name: "wifi-off",
+      class: SemanticType::Error,
+      desc: "Communication with UPS is lost",
+      name: "COMMBAD",
+    }),
+    UpsStatus::ECO => Some(StatusDetail {
+      icon_name: "feather",
+      class: SemanticType::Info,
+      desc: "UPS is in ECO mode",
+      name: "ECO",
+    }),
+    UpsStatus::FAN_FAILURE => Some(StatusDetail {
+      icon_name: "wind",
+      class: SemanticType::Error,
+      desc: "Fan failure",
+      name: "FAN-FAILURE",
+    }),
+    UpsStatus::HIGH_BATTERY => Some(StatusDetail {
+      icon_name: "battery",
+      class: SemanticType::Info,
+      desc: "Battery level is high",
+      name: "HIGH-BATTERY",
+    }),
+    UpsStatus::HIGH_EFFICIENCY => Some(StatusDetail {
+      icon_name: "trending-up",
+      class: SemanticType::Info,
+      desc: "UPS is in high efficiency mode",
+      name: "HIGH-EFFICIENCY",
+    }),
+    UpsStatus::NO_BATTERY => Some(StatusDetail {
+      icon_name: "battery",
+      class: SemanticType::Error,
+      desc: "Battery is not connected",
+      name: "NO-BATTERY",
+    }),
+    UpsStatus::OVERHEAT => Some(StatusDetail {
+      icon_name: "thermometer",
+      class: SemanticType::Error,
+      desc: "UPS is overheated",
+      name: "OVERHEAT",
+    }),
     _ => None,
   }
 }
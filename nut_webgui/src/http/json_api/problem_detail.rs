@@ -83,6 +83,11 @@ impl From<Error> for ProblemDetail {
         status: StatusCode::INTERNAL_SERVER_ERROR,
         detail: Some(err.to_string()),
       },
+      ErrorKind::ProxyError { .. } => ProblemDetail {
+        title: "UPSD proxy error",
+        status: StatusCode::INTERNAL_SERVER_ERROR,
+        detail: Some(err.to_string()),
+      },
       ErrorKind::ConnectionPoolClosed => ProblemDetail {
         title: "UPSD connection pool error",
         status: StatusCode::INTERNAL_SERVER_ERROR,
@@ -93,6 +98,16 @@ impl From<Error> for ProblemDetail {
         status: StatusCode::INTERNAL_SERVER_ERROR,
         detail: Some(err.to_string()),
       },
+      ErrorKind::TlsDowngradeRefused => ProblemDetail {
+        title: "UPSD TLS error",
+        status: StatusCode::INTERNAL_SERVER_ERROR,
+        detail: Some(err.to_string()),
+      },
+      ErrorKind::Unimplemented => ProblemDetail {
+        title: "Command not implemented",
+        status: StatusCode::NOT_IMPLEMENTED,
+        detail: Some(err.to_string()),
+      },
     }
   }
 }
@@ -6,39 +6,44 @@ pub mod rw;
 pub mod ups;
 pub mod ups_list;
 
-macro_rules! request_auth_client {
-  ($upsd_state:expr) => {
-    match &$upsd_state.config {
-      $crate::config::UpsdConfig {
-        pass: Some(pass),
-        user: Some(user),
-        ..
-      } => match $upsd_state.connection_pool.get_client().await {
-        Ok(client) => client
-          .authenticate(user.as_ref(), pass.as_ref())
-          .await
-          .map_err(|err| {
-            $crate::http::json_api::problem_detail::ProblemDetail::new(
-              "Unable to authenticate",
-              axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            )
-            .with_detail(err.to_string())
-          }),
-        Err(e) => Err(
-          $crate::http::json_api::problem_detail::ProblemDetail::new(
-            "Unable to get UPSD client",
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-          )
-          .with_detail(e.to_string()),
-        ),
-      },
-      _ => Err(
+macro_rules! request_write_client {
+  ($upsd_state:expr, $ups_name:expr) => {
+    match $crate::http::tracking::WriteClient::connect($upsd_state, $ups_name).await {
+      Ok(client) => Ok(client),
+      Err($crate::http::tracking::WriteClientError::MissingCredentials) => Err(
         $crate::http::json_api::problem_detail::ProblemDetail::new(
           "Insufficient UPSD configuration",
           axum::http::StatusCode::FORBIDDEN,
         )
         .with_detail("Operation requires valid username and password to be configured for the UPSD connection.".into()),
       ),
+      Err($crate::http::tracking::WriteClientError::DriverWritesDisabled) => Err(
+        $crate::http::json_api::problem_detail::ProblemDetail::new(
+          "Driver writes disabled",
+          axum::http::StatusCode::FORBIDDEN,
+        )
+        .with_detail("Operation requires allow_driver_writes to be enabled for the driver namespace.".into()),
+      ),
+      Err($crate::http::tracking::WriteClientError::UpsNotFound) => Err(
+        $crate::http::json_api::problem_detail::ProblemDetail::new(
+          "Device not found",
+          axum::http::StatusCode::NOT_FOUND,
+        ),
+      ),
+      Err($crate::http::tracking::WriteClientError::Authentication(err)) => Err(
+        $crate::http::json_api::problem_detail::ProblemDetail::new(
+          "Unable to authenticate",
+          axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )
+        .with_detail(err.to_string()),
+      ),
+      Err($crate::http::tracking::WriteClientError::Connection(err)) => Err(
+        $crate::http::json_api::problem_detail::ProblemDetail::new(
+          "Unable to get UPSD client",
+          axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )
+        .with_detail(err.to_string()),
+      ),
     }
   };
 }
@@ -56,4 +61,4 @@ macro_rules! extract_upsd {
 }
 
 pub(super) use extract_upsd;
-pub(super) use request_auth_client;
+pub(super) use request_write_client;
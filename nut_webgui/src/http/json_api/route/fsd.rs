@@ -1,7 +1,7 @@
 use crate::{
   http::json_api::{
     problem_detail::ProblemDetail,
-    route::{extract_upsd, request_auth_client},
+    route::{extract_upsd, request_write_client},
   },
   state::ServerState,
 };
@@ -37,7 +37,7 @@ pub async fn post(
     }
   }?;
 
-  let mut client = request_auth_client!(upsd)?;
+  let mut client = request_write_client!(upsd, &ups_name)?;
 
   {
     let response = client.fsd(&ups_name).await;
@@ -1,7 +1,7 @@
 use crate::{
   http::json_api::{
     problem_detail::ProblemDetail,
-    route::{extract_upsd, request_auth_client},
+    route::{extract_upsd, request_write_client},
   },
   state::ServerState,
 };
@@ -13,7 +13,7 @@ use axum::{
   },
   http::StatusCode,
 };
-use nut_webgui_upsmc::{CmdName, UpsName};
+use nut_webgui_upsmc::{CmdName, UpsName, Value, response::TrackingStatus};
 use serde::Deserialize;
 use std::sync::Arc;
 use tracing::info;
@@ -21,6 +21,8 @@ use tracing::info;
 #[derive(Debug, Deserialize)]
 pub struct InstcmdRequest {
   instcmd: CmdName,
+  #[serde(default)]
+  value: Option<Value>,
 }
 
 pub async fn post(
@@ -53,21 +55,72 @@ pub async fn post(
     }
   }?;
 
-  let mut client = request_auth_client!(upsd)?;
+  let mut client = request_write_client!(upsd, &ups_name)?;
 
-  {
-    let response = client.instcmd(&ups_name, &body.instcmd).await;
+  let status = {
+    let response = client
+      .instcmd(&ups_name, &body.instcmd, body.value.as_ref())
+      .await;
     _ = client.close().await;
 
     response
   }?;
 
-  info!(
-    message = "instcmd called",
-    namespace = %namespace,
-    device = %ups_name,
-    instcmd = %&body.instcmd
-  );
+  match status {
+    Some(TrackingStatus::Success) => {
+      info!(
+        message = "instcmd completed",
+        namespace = %namespace,
+        device = %ups_name,
+        instcmd = %&body.instcmd,
+        value = ?body.value
+      );
+
+      Ok(StatusCode::OK)
+    }
+    Some(TrackingStatus::Failed(err)) => Err(
+      ProblemDetail::new("INSTCMD failed", StatusCode::INTERNAL_SERVER_ERROR).with_detail(format!(
+        "'{cmd_name}' is rejected by device driver. error={err}",
+        cmd_name = &body.instcmd
+      )),
+    ),
+    Some(TrackingStatus::Pending) | None => {
+      info!(
+        message = "instcmd called",
+        namespace = %namespace,
+        device = %ups_name,
+        instcmd = %&body.instcmd,
+        value = ?body.value
+      );
+
+      Ok(StatusCode::ACCEPTED)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::InstcmdRequest;
+  use nut_webgui_upsmc::{CmdName, Value};
+
+  #[test]
+  fn request_value() {
+    let request: InstcmdRequest =
+      serde_json::from_str(r#"{"instcmd":"load.off.delay","value":120}"#).unwrap();
+
+    assert_eq!(request.instcmd, CmdName::new_unchecked("load.off.delay"));
+    assert_eq!(request.value, Some(Value::from(120)));
+
+    let request: InstcmdRequest =
+      serde_json::from_str(r#"{"instcmd":"load.off.delay","value":"120"}"#).unwrap();
+
+    assert_eq!(request.value, Some(Value::from("120")));
+  }
+
+  #[test]
+  fn request_without_value() {
+    let request: InstcmdRequest = serde_json::from_str(r#"{"instcmd":"beeper.on"}"#).unwrap();
 
-  Ok(StatusCode::ACCEPTED)
+    assert_eq!(request.value, None);
+  }
 }
@@ -1,5 +1,5 @@
 use crate::{
-  config::tls_mode::TlsMode,
+  config::{namespace_type::NamespaceType, tls_mode::TlsMode},
   http::json_api::{problem_detail::ProblemDetail, route::extract_upsd},
   state::{ConnectionStatus, ServerState},
 };
@@ -11,6 +11,7 @@ use axum::{
   response::{IntoResponse, Response},
 };
 use chrono::{DateTime, Utc};
+use nut_webgui_upsmc::client::NegotiatedTls;
 use serde::{
   Serialize,
   ser::{SerializeSeq, Serializer},
@@ -19,10 +20,15 @@ use std::sync::Arc;
 
 #[derive(Serialize)]
 struct UpsdEntry<'a> {
+  pub active_endpoint: Option<&'a str>,
   pub address: &'a str,
   pub device_count: usize,
+  pub fallback_addresses: &'a [Box<str>],
+  pub last_failover_time: Option<&'a DateTime<Utc>>,
   pub last_sync_time: Option<&'a DateTime<Utc>>,
   pub namespace: &'a str,
+  pub namespace_type: NamespaceType,
+  pub negotiated_tls: NegotiatedTls,
   pub poll_freq: u64,
   pub poll_interval: u64,
   pub port: u16,
@@ -40,10 +46,15 @@ pub async fn get(
   let daemon_state = upsd.daemon_state.read().await;
 
   let upsd_entry = UpsdEntry {
+    active_endpoint: daemon_state.active_endpoint.as_deref(),
     address: &upsd.config.addr,
     device_count: daemon_state.devices.len(),
+    fallback_addresses: &upsd.config.fallback_addrs,
+    last_failover_time: daemon_state.last_failover.as_ref(),
     last_sync_time: daemon_state.last_device_sync.as_ref(),
     namespace: &upsd.namespace,
+    namespace_type: upsd.config.namespace_type,
+    negotiated_tls: daemon_state.negotiated_tls,
     poll_freq: upsd.config.poll_freq,
     poll_interval: upsd.config.poll_interval,
     port: upsd.config.port,
@@ -64,10 +75,15 @@ pub async fn get_list(State(state): State<Arc<ServerState>>) -> Result<Response,
     let daemon_state = upsd.daemon_state.read().await;
 
     let upsd_entry = UpsdEntry {
+      active_endpoint: daemon_state.active_endpoint.as_deref(),
       address: &upsd.config.addr,
       device_count: daemon_state.devices.len(),
+      fallback_addresses: &upsd.config.fallback_addrs,
+      last_failover_time: daemon_state.last_failover.as_ref(),
       last_sync_time: daemon_state.last_device_sync.as_ref(),
       namespace: &upsd.namespace,
+      namespace_type: upsd.config.namespace_type,
+      negotiated_tls: daemon_state.negotiated_tls,
       poll_freq: upsd.config.poll_freq,
       poll_interval: upsd.config.poll_interval,
       port: upsd.config.port,
@@ -1,9 +1,9 @@
 use crate::{
   http::json_api::{
     problem_detail::ProblemDetail,
-    route::{extract_upsd, request_auth_client},
+    route::{extract_upsd, request_write_client},
   },
-  state::{ServerState, VarDetail},
+  state::{ServerState, VarDetail, VarRange},
 };
 use axum::{
   Json,
@@ -13,7 +13,7 @@ use axum::{
   },
   http::StatusCode,
 };
-use nut_webgui_upsmc::{UpsName, Value, VarName};
+use nut_webgui_upsmc::{UpsName, Value, VarName, response::TrackingStatus};
 use serde::Deserialize;
 use std::sync::Arc;
 use tracing::info;
@@ -95,40 +95,36 @@ pub async fn patch(
             )
           }
         }
-        Some(VarDetail::Range { min, max }) => {
+        Some(VarDetail::Range { ranges }) => {
           if body.value.is_numeric() {
-            match (min.as_lossy_f64(), max.as_lossy_f64()) {
-              (Some(min), Some(max)) => {
-                let valuef64 = body.value.as_lossy_f64().unwrap_or(0.0);
+            let valuef64 = body.value.as_lossy_f64().unwrap_or(0.0);
+            let mut checks = ranges
+              .iter()
+              .filter_map(|range| range.contains(valuef64))
+              .peekable();
 
-                if min <= valuef64 && valuef64 <= max {
-                  Ok(())
-                } else {
-                  Err(
-                    ProblemDetail::new("Out of range", StatusCode::BAD_REQUEST).with_detail(
-                      format!(
-                        "'{var_name}' is not within the acceptable range [{min}, {max}]",
-                        var_name = &body.variable,
-                        min = min,
-                        max = max,
-                      ),
-                    ),
-                  )
-                }
-              }
-              _ => Err(ProblemDetail::new("Malformed driver response", StatusCode::INTERNAL_SERVER_ERROR).with_detail(
+            if checks.peek().is_none() {
+              Err(ProblemDetail::new("Malformed driver response", StatusCode::INTERNAL_SERVER_ERROR).with_detail(
                 "Cannot process request since the reported min-max values by ups device are not number.".to_owned(),
-              ),
-            ),
+              ))
+            } else if checks.any(|is_within| is_within) {
+              Ok(())
+            } else {
+              Err(
+                ProblemDetail::new("Out of range", StatusCode::BAD_REQUEST).with_detail(format!(
+                  "'{var_name}' is not within any of the acceptable ranges {ranges}",
+                  var_name = &body.variable,
+                  ranges = format_ranges(ranges),
+                )),
+              )
             }
           } else {
             Err(
               ProblemDetail::new("Invalid value type", StatusCode::BAD_REQUEST).with_detail(
                 format!(
-                  "'{var_name}' expects a numeric value between {min} and {max}, but the provided value is not a number.",
+                  "'{var_name}' expects a numeric value within {ranges}, but the provided value is not a number.",
                   var_name = &body.variable,
-                  min = min,
-                  max = max,
+                  ranges = format_ranges(ranges),
                 ),
               ),
             )
@@ -148,22 +144,51 @@ pub async fn patch(
     }
   }?;
 
-  let mut client = request_auth_client!(upsd)?;
+  let mut client = request_write_client!(upsd, &ups_name)?;
 
-  {
+  let status = {
     let response = client.set_var(&ups_name, &body.variable, &body.value).await;
     _ = client.close().await;
 
     response
   }?;
 
-  info!(
-    message = "set var request accepted",
-    namespace = %namespace,
-    device = %ups_name,
-    variable = %body.variable,
-    value = %body.value,
-  );
+  match status {
+    Some(TrackingStatus::Success) => {
+      info!(
+        message = "set var request completed",
+        namespace = %namespace,
+        device = %ups_name,
+        variable = %body.variable,
+        value = %body.value,
+      );
+
+      Ok(StatusCode::OK)
+    }
+    Some(TrackingStatus::Failed(err)) => Err(
+      ProblemDetail::new("SET VAR failed", StatusCode::INTERNAL_SERVER_ERROR).with_detail(format!(
+        "'{var_name}' value is rejected by device driver. error={err}",
+        var_name = &body.variable
+      )),
+    ),
+    Some(TrackingStatus::Pending) | None => {
+      info!(
+        message = "set var request accepted",
+        namespace = %namespace,
+        device = %ups_name,
+        variable = %body.variable,
+        value = %body.value,
+      );
+
+      Ok(StatusCode::ACCEPTED)
+    }
+  }
+}
 
-  Ok(StatusCode::ACCEPTED)
+fn format_ranges(ranges: &[VarRange]) -> String {
+  ranges
+    .iter()
+    .map(|range| range.to_string())
+    .collect::<Vec<_>>()
+    .join(", ")
 }
@@ -1,6 +1,6 @@
 use crate::{
   http::json_api::{problem_detail::ProblemDetail, route::extract_upsd},
-  state::ServerState,
+  state::{DeviceEntry, ServerState, StaleGroups},
 };
 use axum::{
   Json,
@@ -9,7 +9,26 @@ use axum::{
   response::{IntoResponse, Response},
 };
 use nut_webgui_upsmc::UpsName;
-use std::sync::Arc;
+use serde::Serialize;
+use std::{sync::Arc, time::Duration};
+
+/// Device entry with stale data groups, see [crate::config::UpsdConfig::stale_threshold].
+#[derive(Serialize)]
+pub struct DeviceResponse<'a> {
+  #[serde(flatten)]
+  pub device: &'a DeviceEntry,
+  pub stale: StaleGroups,
+}
+
+impl<'a> DeviceResponse<'a> {
+  #[inline]
+  pub fn new(device: &'a DeviceEntry, stale_threshold: Duration) -> Self {
+    Self {
+      device,
+      stale: device.stale_groups(stale_threshold),
+    }
+  }
+}
 
 pub async fn get(
   State(state): State<Arc<ServerState>>,
@@ -19,7 +38,10 @@ pub async fn get(
   let upsd = extract_upsd!(state, namespace.as_ref())?;
 
   match upsd.daemon_state.read().await.devices.get(&ups_name) {
-    Some(ups) => Ok(Json(ups).into_response()),
+    Some(ups) => {
+      let stale_threshold = upsd.config.get_stale_threshold();
+      Ok(Json(DeviceResponse::new(ups, stale_threshold)).into_response())
+    }
     None => Err(ProblemDetail::new(
       "Device not found",
       StatusCode::NOT_FOUND,
@@ -1,6 +1,9 @@
 use crate::{
-  http::json_api::{problem_detail::ProblemDetail, route::extract_upsd},
-  state::{DeviceEntry, ServerState},
+  http::json_api::{
+    problem_detail::ProblemDetail,
+    route::{extract_upsd, ups::DeviceResponse},
+  },
+  state::ServerState,
 };
 use axum::{
   Json,
@@ -16,8 +19,13 @@ pub async fn get(
   let upsd = extract_upsd!(state, namespace.as_ref())?;
   let daemon_state = upsd.daemon_state.read().await;
 
-  let mut device_refs: Vec<&DeviceEntry> = daemon_state.devices.values().collect();
-  device_refs.sort_by(|r, l| r.name.cmp(&l.name));
+  let stale_threshold = upsd.config.get_stale_threshold();
+  let mut device_refs: Vec<DeviceResponse> = daemon_state
+    .devices
+    .values()
+    .map(|device| DeviceResponse::new(device, stale_threshold))
+    .collect();
+  device_refs.sort_by(|r, l| r.device.name.cmp(&l.device.name));
 
   Ok(Json(device_refs).into_response())
 }
@@ -1,4 +1,4 @@
-use crate::state::{ConnectionStatus, ServerState};
+use crate::state::{ConnectionStatus, DaemonState, ServerState};
 use axum::{
   extract::{Path, State},
   http::StatusCode,
@@ -65,7 +65,13 @@ pub async fn get_namespace_health(
         HealthStatus::Dead
       };
 
-      status.into_response()
+      match format_diagnostics(&daemon_state) {
+        Some(diagnostics) => {
+          let (status_code, text) = status.as_parts();
+          (status_code, format!("{text}\n{diagnostics}")).into_response()
+        }
+        None => status.into_response(),
+      }
     }
     None => (StatusCode::NOT_FOUND, "UPSD NAMESPACE DOES NOT EXIST").into_response(),
   }
@@ -91,14 +97,43 @@ pub async fn get_namespace_readiness(
   }
 }
 
-impl IntoResponse for HealthStatus {
-  fn into_response(self) -> Response {
+/// Reconnect diagnostics as `key: value` lines, `None` unless the last device sync has failed.
+fn format_diagnostics(daemon_state: &DaemonState) -> Option<String> {
+  if daemon_state.consecutive_failures == 0 {
+    return None;
+  }
+
+  let last_error = daemon_state.last_error.as_deref()?;
+  let mut diagnostics = format!(
+    "consecutive_failures: {}\nlast_error: {}",
+    daemon_state.consecutive_failures, last_error
+  );
+
+  if let Some(time) = daemon_state.last_error_time {
+    diagnostics.push_str(&format!("\nlast_error_time: {}", time.to_rfc3339()));
+  }
+
+  if let Some(time) = daemon_state.next_retry {
+    diagnostics.push_str(&format!("\nnext_retry: {}", time.to_rfc3339()));
+  }
+
+  Some(diagnostics)
+}
+
+impl HealthStatus {
+  #[inline]
+  const fn as_parts(self) -> (StatusCode, &'static str) {
     match self {
       HealthStatus::Degraded => (StatusCode::OK, "DEGRADED"),
       HealthStatus::Ok => (StatusCode::OK, "OK"),
       HealthStatus::Dead => (StatusCode::INTERNAL_SERVER_ERROR, "DEAD"),
     }
-    .into_response()
+  }
+}
+
+impl IntoResponse for HealthStatus {
+  fn into_response(self) -> Response {
+    self.as_parts().into_response()
   }
 }
 
@@ -0,0 +1,340 @@
+use crate::{config::UpsdConfig, state::UpsdState};
+use nut_webgui_upsmc::{
+  CmdName, UpsName, Value, VarName,
+  client::{ClientStream, NutAuthClient},
+  driver::DriverClient,
+  error::{Error, ErrorKind, ProtocolError},
+  response::TrackingStatus,
+};
+use std::time::Duration;
+use tokio::{
+  io::{AsyncRead, AsyncWrite},
+  net::UnixStream,
+};
+use tracing::debug;
+
+/// Maximum wait time for a tracked INSTCMD or SET VAR request to be resolved by the driver.
+pub const TRACKING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Calls INSTCMD and waits for its result if upsd supports command tracking.
+///
+/// Returns `None` when upsd does not support tracking, the command is only accepted in that case.
+pub async fn instcmd<S>(
+  client: &mut NutAuthClient<S>,
+  ups: &UpsName,
+  cmd: &CmdName,
+  value: Option<&Value>,
+) -> Result<Option<TrackingStatus>, Error>
+where
+  S: AsyncRead + AsyncWrite + Unpin + Send + Sync,
+{
+  if enable_tracking(client).await? {
+    let id = client.instcmd_tracked(ups, cmd, value).await?;
+    client.wait_tracking(&id, TRACKING_TIMEOUT).await.map(Some)
+  } else {
+    match value {
+      Some(value) => client.instcmd_with_arg(ups, cmd, value).await?,
+      None => client.instcmd(ups, cmd).await?,
+    }
+
+    Ok(None)
+  }
+}
+
+/// Calls SET VAR and waits for its result if upsd supports command tracking.
+///
+/// Returns `None` when upsd does not support tracking, the request is only accepted in that case.
+pub async fn set_var<S>(
+  client: &mut NutAuthClient<S>,
+  ups: &UpsName,
+  var: &VarName,
+  value: &Value,
+) -> Result<Option<TrackingStatus>, Error>
+where
+  S: AsyncRead + AsyncWrite + Unpin + Send + Sync,
+{
+  if enable_tracking(client).await? {
+    let id = client.set_var_tracked(ups, var, value).await?;
+    client.wait_tracking(&id, TRACKING_TIMEOUT).await.map(Some)
+  } else {
+    client.set_var(ups, var, value).await.map(|_| None)
+  }
+}
+
+/// Enables command tracking on the connection. Only protocol error replies (e.g. `UNKNOWN-COMMAND`
+/// from older upsd versions) are treated as missing tracking support, IO and parse errors are
+/// returned to the caller.
+async fn enable_tracking<S>(client: &mut NutAuthClient<S>) -> Result<bool, Error>
+where
+  S: AsyncRead + AsyncWrite + Unpin + Send + Sync,
+{
+  match client.set_tracking(true).await {
+    Ok(_) => Ok(true),
+    Err(err) if matches!(err.kind(), ErrorKind::ProtocolError { .. }) => {
+      debug!(
+        message = "upsd rejected command tracking, falling back to untracked request",
+        reason = %err
+      );
+
+      Ok(false)
+    }
+    Err(err) => Err(err),
+  }
+}
+
+/// Client for INSTCMD, SET VAR and FSD requests of a namespace.
+///
+/// Upsd namespaces use an authenticated upsd connection, driver namespaces write directly to the
+/// device's driver socket.
+pub enum WriteClient {
+  Upsd(NutAuthClient<Box<dyn ClientStream>>),
+  Driver(DriverClient<UnixStream>),
+}
+
+#[derive(Debug)]
+pub enum WriteClientError {
+  /// Upsd namespace does not have username and password configured.
+  MissingCredentials,
+
+  /// Driver namespace does not have `allow_driver_writes` enabled.
+  DriverWritesDisabled,
+
+  /// Driver namespace does not have a socket configured for the UPS.
+  UpsNotFound,
+
+  /// Unable to get a connection from the pool or unable to connect to the driver socket.
+  Connection(Error),
+
+  /// Upsd rejected configured credentials.
+  Authentication(Error),
+}
+
+impl WriteClient {
+  pub async fn connect(upsd: &UpsdState, ups: &UpsName) -> Result<Self, WriteClientError> {
+    match (&upsd.connection_pool, &upsd.config) {
+      (
+        Some(pool),
+        UpsdConfig {
+          pass: Some(pass),
+          user: Some(user),
+          ..
+        },
+      ) => {
+        let client = pool
+          .get_client()
+          .await
+          .map_err(|err| WriteClientError::Connection(err.into()))?;
+
+        client
+          .authenticate(user, pass)
+          .await
+          .map(Self::Upsd)
+          .map_err(WriteClientError::Authentication)
+      }
+      (Some(_), _) => Err(WriteClientError::MissingCredentials),
+      (None, config) if !config.allow_driver_writes => Err(WriteClientError::DriverWritesDisabled),
+      (None, config) => {
+        let path = config
+          .drivers
+          .get(ups)
+          .ok_or(WriteClientError::UpsNotFound)?;
+
+        let mut client = DriverClient::connect(path)
+          .await
+          .map_err(WriteClientError::Connection)?;
+
+        client.set_timeout(TRACKING_TIMEOUT);
+
+        Ok(Self::Driver(client))
+      }
+    }
+  }
+
+  /// See [`instcmd`], driver sockets always track requests.
+  pub async fn instcmd(
+    &mut self,
+    ups: &UpsName,
+    cmd: &CmdName,
+    value: Option<&Value>,
+  ) -> Result<Option<TrackingStatus>, Error> {
+    match self {
+      Self::Upsd(client) => instcmd(client, ups, cmd, value).await,
+      Self::Driver(client) => client.instcmd(cmd, value).await.map(Some),
+    }
+  }
+
+  /// See [`set_var`], driver sockets always track requests.
+  pub async fn set_var(
+    &mut self,
+    ups: &UpsName,
+    var: &VarName,
+    value: &Value,
+  ) -> Result<Option<TrackingStatus>, Error> {
+    match self {
+      Self::Upsd(client) => set_var(client, ups, var, value).await,
+      Self::Driver(client) => client.set_var(var, value).await.map(Some),
+    }
+  }
+
+  /// Sets the forced shutdown flag. FSD is an upsd feature and it's not available on driver
+  /// sockets.
+  pub async fn fsd(&mut self, ups: &UpsName) -> Result<(), Error> {
+    match self {
+      Self::Upsd(client) => client.fsd(ups).await,
+      Self::Driver(_) => Err(ProtocolError::FeatureNotSupported.into()),
+    }
+  }
+
+  pub async fn close(self) -> Result<(), Error> {
+    match self {
+      Self::Upsd(client) => client.close().await,
+      Self::Driver(client) => client.close().await,
+    }
+  }
+}
+
+impl core::fmt::Display for WriteClientError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::MissingCredentials => f.write_str(
+        "no username or password configured for UPS daemon, server is in read-only mode",
+      ),
+      Self::DriverWritesDisabled => {
+        f.write_str("driver writes are not allowed for the namespace, server is in read-only mode")
+      }
+      Self::UpsNotFound => f.write_str("no driver socket configured for the UPS"),
+      Self::Connection(err) => write!(f, "unable to connect, {err}"),
+      Self::Authentication(err) => write!(f, "client authentication failed, {err}"),
+    }
+  }
+}
+
+impl core::error::Error for WriteClientError {}
+
+#[cfg(test)]
+mod tests {
+  use super::{WriteClient, WriteClientError, instcmd};
+  use crate::{
+    config::{UpsdConfig, namespace_type::NamespaceType},
+    state::{DaemonState, UpsdState},
+  };
+  use nut_webgui_upsmc::{CmdName, UpsName, Value, client::NutClient};
+  use std::collections::HashMap;
+  use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, duplex},
+    sync::RwLock,
+    task::JoinHandle,
+  };
+
+  fn driver_namespace(allow_driver_writes: bool) -> UpsdState {
+    UpsdState {
+      daemon_state: RwLock::new(DaemonState::new()),
+      connection_pool: None,
+      config: UpsdConfig {
+        namespace_type: NamespaceType::Driver,
+        drivers: HashMap::from([(
+          UpsName::new_unchecked("bx1600mi"),
+          "/var/run/nut/usbhid-ups-bx1600mi".into(),
+        )]),
+        allow_driver_writes,
+        ..Default::default()
+      },
+      namespace: "driver".into(),
+    }
+  }
+
+  /// Answers each request line with the paired reply and asserts the requests are received in
+  /// order.
+  fn scripted_upsd(
+    script: &'static [(&'static str, &'static str)],
+  ) -> (DuplexStream, JoinHandle<()>) {
+    let (client, server) = duplex(1024);
+
+    let handle = tokio::spawn(async move {
+      let mut server = BufReader::new(server);
+
+      for (request, reply) in script {
+        let mut line = String::new();
+        server.read_line(&mut line).await.unwrap();
+
+        assert_eq!(line.trim_end(), *request);
+        server.write_all(reply.as_bytes()).await.unwrap();
+      }
+    });
+
+    (client, handle)
+  }
+
+  #[tokio::test]
+  async fn instcmd_forwards_value() {
+    let (stream, upsd) = scripted_upsd(&[
+      ("USERNAME user", "OK\n"),
+      ("PASSWORD pass", "OK\n"),
+      ("SET TRACKING ON", "ERR UNKNOWN-COMMAND\n"),
+      ("INSTCMD bx1600mi load.off.delay \"120\"", "OK\n"),
+    ]);
+
+    let mut client = NutClient::from(stream)
+      .authenticate("user", "pass")
+      .await
+      .unwrap();
+
+    let status = instcmd(
+      &mut client,
+      &UpsName::new_unchecked("bx1600mi"),
+      &CmdName::new_unchecked("load.off.delay"),
+      Some(&Value::from("120")),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(status, None);
+    upsd.await.unwrap();
+  }
+
+  #[tokio::test]
+  async fn instcmd_without_value() {
+    let (stream, upsd) = scripted_upsd(&[
+      ("USERNAME user", "OK\n"),
+      ("PASSWORD pass", "OK\n"),
+      ("SET TRACKING ON", "ERR UNKNOWN-COMMAND\n"),
+      ("INSTCMD bx1600mi beeper.on", "OK\n"),
+    ]);
+
+    let mut client = NutClient::from(stream)
+      .authenticate("user", "pass")
+      .await
+      .unwrap();
+
+    let status = instcmd(
+      &mut client,
+      &UpsName::new_unchecked("bx1600mi"),
+      &CmdName::new_unchecked("beeper.on"),
+      None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(status, None);
+    upsd.await.unwrap();
+  }
+
+  #[tokio::test]
+  async fn driver_writes_require_opt_in() {
+    let upsd = driver_namespace(false);
+    let result = WriteClient::connect(&upsd, &UpsName::new_unchecked("bx1600mi")).await;
+
+    assert!(matches!(
+      result,
+      Err(WriteClientError::DriverWritesDisabled)
+    ));
+  }
+
+  #[tokio::test]
+  async fn driver_writes_unknown_ups() {
+    let upsd = driver_namespace(true);
+    let result = WriteClient::connect(&upsd, &UpsName::new_unchecked("unknown")).await;
+
+    assert!(matches!(result, Err(WriteClientError::UpsNotFound)));
+  }
+}
@@ -1,4 +1,8 @@
-use self::openmetric::collector::UpsdStatCollector;
+use self::openmetric::{
+  collector::UpsdStatCollector,
+  command_observer::{CommandDurationObserver, new_command_duration_family},
+  pool_collector::UpsdPoolCollector,
+};
 use crate::{
   auth::{
     AUTH_COOKIE_DURATION,
@@ -9,23 +13,30 @@ use crate::{
   config::{
     ServerConfig, UpsdConfig, cfg_arg::ServerCliArgs, cfg_env::ServerEnvArgs,
     cfg_fallback::FallbackArgs, cfg_toml::ServerTomlArgs, cfg_user::UsersConfigFile,
-    error::ConfigError,
+    error::ConfigError, namespace_type::NamespaceType, tls_mode::TlsMode,
   },
   event::channel::EventChannel,
   http::{
     HttpServer,
     event_api::message_broadcast::{MessageBroadcast, MessageBroadcastService},
   },
+  nut_server::NutServerService,
+  pinned_cert_verifier::PinnedCertVerifier,
   skip_tls_verifier::SkipTlsVerifier,
   state::{DaemonState, ServerState, UpsdNamespace, UpsdState},
   sync::{
-    sync_desc::DescriptionSyncService, sync_device::DeviceSyncService,
-    sync_status::StatusSyncService,
+    client_resolver::ClientResolver, sync_desc::DescriptionSyncService,
+    sync_device::DeviceSyncService, sync_driver::DriverSyncService, sync_status::StatusSyncService,
   },
 };
 use nut_webgui_upsmc::{
-  client::{NutPoolClient, NutPoolClientBuilder},
-  rustls::{ClientConfig, pki_types::ServerName},
+  client::{
+    CommandObserver, Endpoint, NutPoolClient, NutPoolClientBuilder, ServerAddr, SessionRecorder,
+  },
+  rustls::{
+    ClientConfig, RootCertStore,
+    pki_types::{CertificateDer, PrivateKeyDer, ServerName, pem::PemObject},
+  },
 };
 use rustls_platform_verifier::BuilderVerifierExt;
 use std::{collections::HashMap, panic, process::ExitCode, sync::Arc, time::Duration};
@@ -46,7 +57,9 @@ mod background_service;
 mod config;
 mod event;
 mod http;
+mod nut_server;
 mod openmetric;
+mod pinned_cert_verifier;
 mod skip_tls_verifier;
 mod state;
 mod sync;
@@ -135,13 +148,33 @@ async fn start_server(config: ServerConfig) -> Result<(), Box<dyn core::error::E
   let auth_user_store = create_user_store(&config)?;
   let mut upsd_servers = HashMap::new();
   let mut openmetrics = prometheus_client::registry::Registry::with_prefix("nutwg");
+  let command_durations = new_command_duration_family();
+  let mut pools = Vec::with_capacity(config.upsd.len());
+
+  openmetrics.register_with_unit(
+    "upsd_command_duration",
+    "Duration of upsd commands",
+    prometheus_client::registry::Unit::Seconds,
+    command_durations.clone(),
+  );
 
   for (name, upsd_cfg) in config.upsd.iter() {
     let namespace = UpsdNamespace::from(name.as_ref());
+    let connection_pool = match upsd_cfg.namespace_type {
+      NamespaceType::Upsd => {
+        let observer = CommandDurationObserver::new(namespace.clone(), command_durations.clone());
+        let pool = create_pool(upsd_cfg, Arc::new(observer))?;
+        pools.push((namespace.clone(), pool.clone()));
+
+        Some(pool)
+      }
+      NamespaceType::Driver => None,
+    };
+
     let upsd_state = Arc::new(UpsdState {
       config: upsd_cfg.clone(),
       daemon_state: RwLock::new(DaemonState::new()),
-      connection_pool: create_pool(upsd_cfg)?,
+      connection_pool,
       namespace: namespace.clone(),
     });
 
@@ -149,6 +182,8 @@ async fn start_server(config: ServerConfig) -> Result<(), Box<dyn core::error::E
     upsd_servers.insert(namespace, upsd_state);
   }
 
+  openmetrics.register_collector(Box::new(UpsdPoolCollector::new(pools)));
+
   let server_state = Arc::new(ServerState {
     auth_user_store,
     config,
@@ -169,18 +204,71 @@ async fn start_server(config: ServerConfig) -> Result<(), Box<dyn core::error::E
       message_broadcast,
     ));
 
+  let client_resolver = Arc::new(ClientResolver::new(
+    &server_state.config.reverse_dns,
+    server_state.config.client_aliases.clone(),
+  ));
+
   for (name, upsd_state) in server_state.upsd_servers.iter() {
     debug!(
       message = "adding background services for upsd config",
       namespace = name.as_ref()
     );
 
-    let device_sync = DeviceSyncService::new(event_channel.clone(), upsd_state.clone());
-    let status_sync = StatusSyncService::new(event_channel.clone(), upsd_state.clone());
+    bg_services = match upsd_state.connection_pool.clone() {
+      Some(pool) => {
+        let device_sync = DeviceSyncService::new(
+          event_channel.clone(),
+          upsd_state.clone(),
+          pool.clone(),
+          client_resolver.clone(),
+        );
+        let status_sync = StatusSyncService::new(
+          event_channel.clone(),
+          upsd_state.clone(),
+          pool,
+          client_resolver.clone(),
+        );
+
+        bg_services
+          .add_service(device_sync)
+          .add_service(status_sync)
+      }
+      None => bg_services.add_service(DriverSyncService::new(
+        event_channel.clone(),
+        upsd_state.clone(),
+      )),
+    };
+  }
+
+  if server_state.config.nut_server.enabled {
+    let nut_server_cfg = &server_state.config.nut_server;
+    let upsd_state = server_state
+      .upsd_servers
+      .get(nut_server_cfg.namespace.as_ref())
+      .cloned()
+      .ok_or_else(|| {
+        format!(
+          "nut server namespace '{}' is not configured",
+          nut_server_cfg.namespace
+        )
+      })?;
+
+    let nut_listener = TcpListener::bind(nut_server_cfg.get_listen_addr())
+      .await
+      .inspect_err(|err| {
+        error!(
+          message = "cannot bind tcp socket for nut server",
+          reason = %err,
+          listen_port = nut_server_cfg.port
+        );
+      })?;
 
-    bg_services = bg_services
-      .add_service(device_sync)
-      .add_service(status_sync);
+    bg_services = bg_services.add_service(NutServerService::new(
+      nut_listener,
+      server_state.clone(),
+      upsd_state,
+    ));
   }
 
   debug!(message = "starting background services");
@@ -208,8 +296,8 @@ async fn start_server(config: ServerConfig) -> Result<(), Box<dyn core::error::E
 
   if let Some(state) = Arc::into_inner(server_state) {
     for upsd in state.upsd_servers.into_values() {
-      if let Some(upsd_state) = Arc::into_inner(upsd) {
-        upsd_state.connection_pool.close().await;
+      if let Some(pool) = Arc::into_inner(upsd).and_then(|v| v.connection_pool) {
+        pool.close().await;
       }
     }
   }
@@ -243,44 +331,169 @@ fn load_configs() -> Result<ServerConfig, ConfigError> {
     .layer(cli_args)
     .layer(FallbackArgs);
 
+  config.validate()?;
+
   Ok(config)
 }
 
 fn create_pool(
   config: &UpsdConfig,
+  observer: Arc<dyn CommandObserver>,
 ) -> Result<NutPoolClient, Box<dyn core::error::Error + 'static>> {
-  let tls_client_conf = match config.tls_mode {
-    config::tls_mode::TlsMode::Disable => None,
-    config::tls_mode::TlsMode::Strict => Some(
-      ClientConfig::builder()
-        .with_platform_verifier()?
-        .with_no_client_auth(),
-    ),
-    config::tls_mode::TlsMode::SkipVerify => {
-      let mut config = ClientConfig::builder()
-        .with_platform_verifier()?
-        .with_no_client_auth();
-
-      config
-        .dangerous()
-        .set_certificate_verifier(Arc::new(SkipTlsVerifier));
+  if let Some(identifier) = config.monitor.iter().find(|v| !config.is_identified_by(v)) {
+    return Err(
+      format!(
+        "monitor entry {} does not match the upsd address {}",
+        identifier,
+        config.get_socket_addr()
+      )
+      .into(),
+    );
+  }
 
-      Some(config)
-    }
-  };
+  let tls_client_conf = create_tls_config(config)?;
 
   let mut builder = NutPoolClientBuilder::new(config.get_socket_addr().into())
-    .with_timeout(Duration::from_secs(config.poll_freq))
-    .with_limit(config.max_conn);
+    .with_timeout(Duration::from_secs(config.request_timeout.max(1)))
+    .with_connect_timeout(Duration::from_secs(config.connect_timeout.max(1)))
+    .with_limit(config.max_conn)
+    .with_observer(observer)
+    .with_validation_window(Duration::from_secs(config.pool_validation_window));
+
+  if config.pool_max_lifetime > 0 {
+    builder = builder.with_max_lifetime(Duration::from_secs(config.pool_max_lifetime));
+  }
+
+  if config.pool_idle_timeout > 0 {
+    builder = builder.with_idle_timeout(Duration::from_secs(config.pool_idle_timeout));
+  }
+
+  for (host, socket_addr) in config.get_fallback_socket_addrs() {
+    let mut endpoint = Endpoint::new(ServerAddr::from(socket_addr));
+
+    if tls_client_conf.is_some() {
+      endpoint = endpoint.with_server_name(ServerName::try_from(host.to_owned())?);
+    }
+
+    builder = builder.with_fallback(endpoint);
+  }
+
+  if let Some(record_file) = config.record_file.as_ref() {
+    let recorder = SessionRecorder::create(record_file).map_err(|err| {
+      format!(
+        "unable to create record_file {}, {}",
+        record_file.display(),
+        err
+      )
+    })?;
+
+    warn!(
+      message = "upsd traffic is being recorded",
+      record_file = %record_file.display()
+    );
+
+    builder = builder.with_recorder(recorder);
+  }
+
+  if let Some(proxy) = config.proxy.as_ref() {
+    info!(message = "upsd connections are tunneled through proxy", proxy = %proxy);
+    builder = builder.with_proxy(proxy.as_proxy().clone());
+  }
 
   if let Some(tls_config) = tls_client_conf {
-    let server_name = ServerName::try_from(config.addr.as_ref().to_owned())?;
-    builder = builder.with_tls(server_name, Arc::new(tls_config));
+    let server_name = ServerName::try_from(config.get_host().to_owned())?;
+
+    builder = if config.tls_mode == TlsMode::Prefer {
+      builder.with_prefer_tls(server_name, Arc::new(tls_config))
+    } else {
+      builder.with_tls(server_name, Arc::new(tls_config))
+    };
   }
 
   Ok(builder.build())
 }
 
+fn create_tls_config(
+  config: &UpsdConfig,
+) -> Result<Option<ClientConfig>, Box<dyn core::error::Error + 'static>> {
+  if config.tls_mode == TlsMode::Disable {
+    if !config.cert_fingerprints.is_empty()
+      || config.ca_file.is_some()
+      || config.client_cert.is_some()
+      || config.client_key.is_some()
+    {
+      return Err(
+        "cert_fingerprints, ca_file, client_cert and client_key require tls_mode other than disable"
+          .into(),
+      );
+    }
+
+    return Ok(None);
+  }
+
+  if config.ca_file.is_some() {
+    if !config.cert_fingerprints.is_empty() {
+      return Err("ca_file cannot be combined with cert_fingerprints".into());
+    }
+
+    if config.tls_mode == TlsMode::SkipVerify {
+      return Err("ca_file requires tls_mode other than skip".into());
+    }
+  }
+
+  let builder = ClientConfig::builder();
+
+  let builder = if !config.cert_fingerprints.is_empty() {
+    let verifier =
+      PinnedCertVerifier::new(&config.cert_fingerprints, builder.crypto_provider().clone());
+
+    builder
+      .dangerous()
+      .with_custom_certificate_verifier(Arc::new(verifier))
+  } else {
+    match (config.tls_mode, config.ca_file.as_ref()) {
+      (TlsMode::SkipVerify, _) => builder
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(SkipTlsVerifier)),
+      (_, Some(ca_file)) => {
+        let mut root_store = RootCertStore::empty();
+
+        for cert in CertificateDer::pem_file_iter(ca_file)
+          .map_err(|err| format!("unable to read ca_file {}, {}", ca_file.display(), err))?
+        {
+          root_store.add(cert?)?;
+        }
+
+        builder.with_root_certificates(root_store)
+      }
+      (_, None) => builder.with_platform_verifier()?,
+    }
+  };
+
+  let tls_config = match (config.client_cert.as_ref(), config.client_key.as_ref()) {
+    (Some(cert_file), Some(key_file)) => {
+      let cert_chain = CertificateDer::pem_file_iter(cert_file)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|err| {
+          format!(
+            "unable to read client_cert {}, {}",
+            cert_file.display(),
+            err
+          )
+        })?;
+
+      let key = PrivateKeyDer::from_pem_file(key_file)
+        .map_err(|err| format!("unable to read client_key {}, {}", key_file.display(), err))?;
+
+      builder.with_client_auth_cert(cert_chain, key)?
+    }
+    (None, None) => builder.with_no_client_auth(),
+    _ => return Err("client_cert and client_key must be configured together".into()),
+  };
+
+  Ok(Some(tls_config))
+}
+
 fn create_user_store(
   config: &ServerConfig,
 ) -> Result<Option<Arc<UserStore>>, Box<dyn core::error::Error + 'static>> {
@@ -0,0 +1,90 @@
+use crate::{
+  background_service::BackgroundService,
+  state::{ServerState, UpsdState},
+};
+use std::sync::Arc;
+use tokio::{net::TcpListener, select};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info};
+
+mod session;
+
+use session::Session;
+
+/// Serves a namespace's cached device state over the NUT network protocol, so additional NUT
+/// clients (e.g. `upsmon` secondaries) can attach without opening new connections to upsd.
+///
+/// Read requests are answered from [`crate::state::DaemonState`]. `SET VAR`, `INSTCMD` and `FSD`
+/// requests are forwarded to upsd with the client's own `USERNAME` and `PASSWORD`.
+pub struct NutServerService {
+  listener: Arc<TcpListener>,
+  server_state: Arc<ServerState>,
+  upsd_state: Arc<UpsdState>,
+}
+
+impl NutServerService {
+  pub fn new(
+    listener: TcpListener,
+    server_state: Arc<ServerState>,
+    upsd_state: Arc<UpsdState>,
+  ) -> Self {
+    Self {
+      listener: Arc::new(listener),
+      server_state,
+      upsd_state,
+    }
+  }
+}
+
+impl BackgroundService for NutServerService {
+  fn run(
+    &self,
+    token: CancellationToken,
+  ) -> core::pin::Pin<Box<dyn core::future::Future<Output = ()> + Send>> {
+    let listener = self.listener.clone();
+    let server_state = self.server_state.clone();
+    let upsd_state = self.upsd_state.clone();
+
+    Box::pin(async move {
+      info!(
+        message = "nut server is listening",
+        namespace = %upsd_state.namespace,
+        listen = ?listener.local_addr().ok()
+      );
+
+      loop {
+        let accepted = select! {
+          accepted = listener.accept() => accepted,
+          _ = token.cancelled() => { break; }
+        };
+
+        match accepted {
+          Ok((stream, peer)) => {
+            debug!(message = "nut client connected", peer = %peer);
+
+            let session = Session::new(server_state.clone(), upsd_state.clone(), peer);
+            let session_token = token.child_token();
+
+            tokio::spawn(async move {
+              select! {
+                result = session.serve(stream) => {
+                  if let Err(err) = result {
+                    debug!(message = "nut client connection closed", peer = %peer, reason = %err);
+                  } else {
+                    debug!(message = "nut client disconnected", peer = %peer);
+                  }
+                }
+                _ = session_token.cancelled() => {}
+              }
+            });
+          }
+          Err(err) => {
+            error!(message = "unable to accept nut client connection", reason = %err);
+          }
+        }
+      }
+
+      debug!(message = "nut server stopped");
+    })
+  }
+}
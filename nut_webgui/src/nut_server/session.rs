@@ -0,0 +1,757 @@
+use crate::{
+  http::tracking,
+  state::{DeviceEntry, ServerState, UpsdState, VarDetail},
+};
+use core::net::SocketAddr;
+use nut_webgui_upsmc::{
+  CmdName, UpsName, VarName, VarType,
+  client::{ClientStream, NutAuthClient},
+  error::{Error, ErrorKind, ProtocolError},
+  response::TrackingStatus,
+  server::{Request, ResponseEncoder},
+  ups_status::UpsStatus,
+};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tracing::{info, warn};
+
+/// Maximum accepted request line length. Longer lines are rejected with `ERR TOO-LONG`.
+const MAX_LINE_LENGTH: u64 = 1024;
+
+const HELP_TEXT: &str = "Commands: HELP VER GET LIST SET INSTCMD LOGIN LOGOUT USERNAME PASSWORD";
+const NET_VER: &str = "1.3";
+const VER_TEXT: &str = concat!(
+  "Network UPS Tools upsd compatible proxy - nut_webgui ",
+  env!("CARGO_PKG_VERSION")
+);
+
+/// A single NUT client connection.
+pub struct Session {
+  server_state: Arc<ServerState>,
+  upsd_state: Arc<UpsdState>,
+  peer: SocketAddr,
+  username: Option<Box<str>>,
+  password: Option<Box<str>>,
+  login: Option<UpsName>,
+  encoder: ResponseEncoder,
+}
+
+impl Session {
+  pub fn new(server_state: Arc<ServerState>, upsd_state: Arc<UpsdState>, peer: SocketAddr) -> Self {
+    Self {
+      server_state,
+      upsd_state,
+      peer,
+      username: None,
+      password: None,
+      login: None,
+      encoder: ResponseEncoder::new(),
+    }
+  }
+
+  /// Answers client requests until the client disconnects or sends `LOGOUT`.
+  pub async fn serve<S>(mut self, stream: S) -> Result<(), std::io::Error>
+  where
+    S: AsyncRead + AsyncWrite + Unpin,
+  {
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+    let mut line = String::new();
+
+    loop {
+      line.clear();
+
+      let read = (&mut reader)
+        .take(MAX_LINE_LENGTH)
+        .read_line(&mut line)
+        .await?;
+
+      if read == 0 {
+        return Ok(());
+      }
+
+      if !line.ends_with('\n') {
+        self.encoder.err(&ProtocolError::TooLong);
+        writer.write_all(self.encoder.as_bytes()).await?;
+
+        return Ok(());
+      }
+
+      if line.trim().is_empty() {
+        continue;
+      }
+
+      let close = match line.parse::<Request>() {
+        Ok(request) => self.handle(request).await,
+        Err(err) => {
+          self.encoder.err(&err);
+          false
+        }
+      };
+
+      writer.write_all(self.encoder.as_bytes()).await?;
+      self.encoder.clear();
+
+      if close {
+        return Ok(());
+      }
+    }
+  }
+
+  /// Writes the reply into the encoder. Returns `true` when the connection should be closed.
+  async fn handle(&mut self, request: Request) -> bool {
+    match request {
+      Request::Help => self.encoder.text(HELP_TEXT),
+      Request::Ver => self.encoder.text(VER_TEXT),
+      Request::NetVer => self.encoder.text(NET_VER),
+      Request::Logout => {
+        self.encoder.ok_goodbye();
+        return true;
+      }
+      Request::Username { username } => {
+        if self.username.is_some() {
+          self.encoder.err(&ProtocolError::AlreadySetUsername);
+        } else {
+          self.username = Some(username);
+          self.encoder.ok();
+        }
+      }
+      Request::Password { password } => {
+        if self.password.is_some() {
+          self.encoder.err(&ProtocolError::AlreadySetPassword);
+        } else {
+          self.password = Some(password);
+          self.encoder.ok();
+        }
+      }
+      Request::Login { ups } => self.attach(ups).await,
+      Request::ListUps => self.list_ups().await,
+      Request::ListVar { ups } => {
+        self
+          .with_device(&ups, true, |device, encoder| {
+            let mut variables: Vec<_> = device.variables.iter().collect();
+            variables.sort_unstable_by_key(|(name, _)| *name);
+
+            encoder.list_var(&ups, variables);
+          })
+          .await
+      }
+      Request::ListRw { ups } => {
+        self
+          .with_device(&ups, true, |device, encoder| {
+            let mut variables: Vec<_> = device
+              .rw_variables
+              .keys()
+              .filter_map(|name| device.variables.get(name).map(|value| (name, value)))
+              .collect();
+            variables.sort_unstable_by_key(|(name, _)| *name);
+
+            encoder.list_rw(&ups, variables);
+          })
+          .await
+      }
+      Request::ListCmd { ups } => {
+        self
+          .with_device(&ups, false, |device, encoder| {
+            encoder.list_cmd(&ups, &device.commands)
+          })
+          .await
+      }
+      Request::ListEnum { ups, var } => {
+        self
+          .with_device(&ups, true, |device, encoder| {
+            match (device.variables.get(&var), device.rw_variables.get(&var)) {
+              (None, _) => encoder.err(&ProtocolError::VarNotSupported),
+              (_, Some(VarDetail::Enum { options })) => encoder.list_enum(&ups, &var, options),
+              _ => encoder.list_enum(&ups, &var, []),
+            }
+          })
+          .await
+      }
+      Request::ListRange { ups, var } => {
+        self
+          .with_device(&ups, true, |device, encoder| {
+            match (device.variables.get(&var), device.rw_variables.get(&var)) {
+              (None, _) => encoder.err(&ProtocolError::VarNotSupported),
+              (_, Some(VarDetail::Range { ranges })) => encoder.list_range(
+                &ups,
+                &var,
+                ranges.iter().map(|range| (&range.min, &range.max)),
+              ),
+              _ => encoder.list_range(&ups, &var, []),
+            }
+          })
+          .await
+      }
+      Request::ListClient { ups } => {
+        self
+          .with_device(&ups, false, |device, encoder| {
+            encoder.list_client(&ups, device.attached.iter().map(|client| &client.addr))
+          })
+          .await
+      }
+      Request::GetVar { ups, var } => {
+        self
+          .with_device(&ups, true, |device, encoder| {
+            match device.variables.get(&var) {
+              Some(value) => encoder.var(&ups, &var, value),
+              None => encoder.err(&ProtocolError::VarNotSupported),
+            }
+          })
+          .await
+      }
+      Request::GetType { ups, var } => {
+        self
+          .with_device(&ups, true, |device, encoder| {
+            match var_types(device, &var) {
+              Some(var_types) => encoder.var_type(&ups, &var, &var_types),
+              None => encoder.err(&ProtocolError::VarNotSupported),
+            }
+          })
+          .await
+      }
+      Request::GetUpsDesc { ups } => {
+        self
+          .with_device(&ups, false, |device, encoder| {
+            let desc = if device.desc.is_empty() {
+              "Unavailable"
+            } else {
+              device.desc.as_ref()
+            };
+
+            encoder.ups_desc(&ups, desc);
+          })
+          .await
+      }
+      Request::GetNumLogins { ups } => {
+        self
+          .with_device(&ups, false, |device, encoder| {
+            encoder.num_logins(&ups, device.num_logins.unwrap_or(device.attached.len()))
+          })
+          .await
+      }
+      Request::GetDesc { ups, var } => self.var_desc(ups, var).await,
+      Request::GetCmdDesc { ups, cmd } => self.cmd_desc(ups, cmd).await,
+      Request::SetVar { .. } | Request::InstCmd { .. } | Request::Fsd { .. } => {
+        self.forward(request).await
+      }
+      Request::StartTls => self.encoder.err(&ProtocolError::FeatureNotConfigured),
+      Request::Primary { .. } => self.encoder.err(&ProtocolError::AccessDenied),
+      Request::SetTracking { .. } | Request::GetTracking { .. } => {
+        self.encoder.err(&ProtocolError::FeatureNotSupported)
+      }
+    }
+
+    false
+  }
+
+  /// Looks up the device and calls `reply`. Devices without any data are reported as
+  /// `DATA-STALE` when `require_data` is set.
+  async fn with_device<F>(&mut self, ups: &UpsName, require_data: bool, reply: F)
+  where
+    F: FnOnce(&DeviceEntry, &mut ResponseEncoder),
+  {
+    let daemon_state = self.upsd_state.daemon_state.read().await;
+
+    match daemon_state.devices.get(ups) {
+      Some(device)
+        if require_data && device.status.has(UpsStatus::NOCOMM) && device.variables.is_empty() =>
+      {
+        self.encoder.err(&ProtocolError::DateStale)
+      }
+      Some(device) => reply(device, &mut self.encoder),
+      None => self.encoder.err(&ProtocolError::UnknownUps),
+    }
+  }
+
+  async fn list_ups(&mut self) {
+    let daemon_state = self.upsd_state.daemon_state.read().await;
+    let mut devices: Vec<_> = daemon_state
+      .devices
+      .values()
+      .map(|device| (&device.name, device.desc.as_ref()))
+      .collect();
+
+    devices.sort_unstable_by_key(|(name, _)| *name);
+
+    self.encoder.list_ups(devices);
+  }
+
+  async fn var_desc(&mut self, ups: UpsName, var: VarName) {
+    if !self.device_exists(&ups).await {
+      self.encoder.err(&ProtocolError::UnknownUps);
+      return;
+    }
+
+    let descriptions = self.server_state.shared_desc.read().await;
+    let desc = descriptions
+      .get(var.as_str())
+      .map_or("Description unavailable", |v| v.as_ref());
+
+    self.encoder.var_desc(&ups, &var, desc);
+  }
+
+  async fn cmd_desc(&mut self, ups: UpsName, cmd: CmdName) {
+    if !self.device_exists(&ups).await {
+      self.encoder.err(&ProtocolError::UnknownUps);
+      return;
+    }
+
+    let descriptions = self.server_state.shared_desc.read().await;
+    let desc = descriptions
+      .get(cmd.as_str())
+      .map_or("Description unavailable", |v| v.as_ref());
+
+    self.encoder.cmd_desc(&ups, &cmd, desc);
+  }
+
+  /// `LOGIN` is only tracked by the proxy itself, upsd does not see proxied clients. upsd accepts
+  /// any `PASSWORD` and only checks credentials on write requests, so credentials are checked when
+  /// `SET VAR`, `INSTCMD` or `FSD` are forwarded, not here.
+  async fn attach(&mut self, ups: UpsName) {
+    if self.username.is_none() {
+      self.encoder.err(&ProtocolError::UsernameRequired);
+    } else if self.password.is_none() {
+      self.encoder.err(&ProtocolError::PasswordRequired);
+    } else if self.login.is_some() {
+      self.encoder.err(&ProtocolError::AlreadyAttached);
+    } else if !self.device_exists(&ups).await {
+      self.encoder.err(&ProtocolError::UnknownUps);
+    } else {
+      info!(
+        message = "nut client logged in",
+        namespace = %self.upsd_state.namespace,
+        device = %ups,
+        peer = %self.peer
+      );
+
+      self.login = Some(ups);
+      self.encoder.ok();
+    }
+  }
+
+  async fn authenticate(
+    &self,
+    username: &str,
+    password: &str,
+  ) -> Result<NutAuthClient<Box<dyn ClientStream>>, ProtocolError> {
+    let Some(pool) = self.upsd_state.connection_pool.as_ref() else {
+      return Err(ProtocolError::FeatureNotSupported);
+    };
+
+    let client = pool
+      .get_client()
+      .await
+      .map_err(|err| protocol_error(&err.into()))?;
+
+    client
+      .authenticate(username, password)
+      .await
+      .map_err(|err| protocol_error(&err))
+  }
+
+  async fn device_exists(&self, ups: &UpsName) -> bool {
+    self
+      .upsd_state
+      .daemon_state
+      .read()
+      .await
+      .devices
+      .contains_key(ups)
+  }
+
+  /// Forwards write requests to upsd with the client's credentials.
+  async fn forward(&mut self, request: Request) {
+    let is_fsd = matches!(request, Request::Fsd { .. });
+
+    let result = match (&self.username, &self.password) {
+      (None, _) => Err(ProtocolError::UsernameRequired),
+      (_, None) => Err(ProtocolError::PasswordRequired),
+      (Some(username), Some(password)) => self.forward_as(username, password, request).await,
+    };
+
+    match result {
+      Ok(_) if is_fsd => self.encoder.ok_fsd(),
+      Ok(_) => self.encoder.ok(),
+      Err(err) => self.encoder.err(&err),
+    }
+  }
+
+  async fn forward_as(
+    &self,
+    username: &str,
+    password: &str,
+    request: Request,
+  ) -> Result<(), ProtocolError> {
+    if self.upsd_state.connection_pool.is_none() {
+      return Err(ProtocolError::FeatureNotSupported);
+    }
+
+    info!(
+      message = "forwarding nut client request to upsd",
+      namespace = %self.upsd_state.namespace,
+      peer = %self.peer,
+      request = ?request
+    );
+
+    let mut client = self.authenticate(username, password).await?;
+
+    let result = match request {
+      Request::SetVar { ups, var, value } => {
+        tracking::set_var(&mut client, &ups, &var, &value).await
+      }
+      Request::InstCmd { ups, cmd, value } => {
+        tracking::instcmd(&mut client, &ups, &cmd, value.as_ref()).await
+      }
+      Request::Fsd { ups } => client.fsd(&ups).await.map(|_| None),
+      _ => Ok(None),
+    };
+
+    _ = client.close().await;
+
+    match result {
+      Ok(Some(TrackingStatus::Failed(err))) => Err(err),
+      Ok(_) => Ok(()),
+      Err(err) => {
+        warn!(
+          message = "forwarded nut client request failed",
+          namespace = %self.upsd_state.namespace,
+          peer = %self.peer,
+          reason = %err
+        );
+
+        Err(protocol_error(&err))
+      }
+    }
+  }
+}
+
+/// Returns upsd's protocol error as is, connection failures are reported as
+/// `DRIVER-NOT-CONNECTED`.
+fn protocol_error(err: &Error) -> ProtocolError {
+  match err.kind() {
+    ErrorKind::ProtocolError { inner } => inner.clone(),
+    _ => ProtocolError::DriverNotConnected,
+  }
+}
+
+/// Builds `GET TYPE` reply from the cached variable details. Read-only variables are reported as
+/// `NUMBER` or `STRING` based on their values.
+fn var_types(device: &DeviceEntry, var: &VarName) -> Option<Vec<VarType>> {
+  let value = device.variables.get(var)?;
+
+  let var_types = match device.rw_variables.get(var) {
+    Some(VarDetail::String { max_len }) => {
+      vec![VarType::ReadWrite, VarType::String { max_len: *max_len }]
+    }
+    Some(VarDetail::Number) => vec![VarType::ReadWrite, VarType::Number],
+    Some(VarDetail::Enum { .. }) => vec![VarType::ReadWrite, VarType::Enum],
+    Some(VarDetail::Range { .. }) => vec![VarType::ReadWrite, VarType::Range],
+    None if value.is_numeric() => vec![VarType::Number],
+    None => vec![VarType::String {
+      max_len: value.as_str().len(),
+    }],
+  };
+
+  Some(var_types)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Session, var_types};
+  use crate::{
+    config::{ServerConfig, UpsdConfig},
+    state::{DaemonState, DeviceEntry, DeviceFreshness, ServerState, UpsdState, VarDetail},
+  };
+  use chrono::Utc;
+  use nut_webgui_upsmc::{
+    UpsName, Value, VarName, VarType,
+    client::{NutPoolClient, NutPoolClientBuilder, ServerAddr},
+    ups_status::UpsStatus,
+    ups_variables::UpsVariables,
+  };
+  use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+  use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, duplex},
+    net::TcpListener,
+    sync::RwLock,
+    task::JoinHandle,
+  };
+
+  fn device(name: &str, status: UpsStatus, variables: UpsVariables) -> DeviceEntry {
+    DeviceEntry {
+      attached: Vec::new(),
+      commands: Vec::new(),
+      desc: Box::from(""),
+      last_modified: Utc::now(),
+      last_updated: DeviceFreshness::new(Utc::now()),
+      name: UpsName::new_unchecked(name),
+      num_logins: None,
+      rw_variables: HashMap::new(),
+      status,
+      variables,
+    }
+  }
+
+  /// Starts a session with `ups` (online) and `stale` (no data) devices. Returns the client end of
+  /// the connection and the session task.
+  fn start_session(
+    pool: Option<NutPoolClient>,
+  ) -> (
+    BufReader<DuplexStream>,
+    JoinHandle<Result<(), std::io::Error>>,
+  ) {
+    let mut daemon_state = DaemonState::new();
+
+    for device in [
+      device(
+        "ups",
+        UpsStatus::ONLINE,
+        UpsVariables::from([(VarName::UPS_STATUS, Value::from("OL"))]),
+      ),
+      device("stale", UpsStatus::NOCOMM, UpsVariables::new()),
+    ] {
+      daemon_state.devices.insert(device.name.clone(), device);
+    }
+
+    let upsd_state = Arc::new(UpsdState {
+      daemon_state: RwLock::new(daemon_state),
+      connection_pool: pool,
+      config: UpsdConfig::default(),
+      namespace: "default".into(),
+    });
+
+    let server_state = Arc::new(ServerState {
+      upsd_servers: HashMap::new(),
+      shared_desc: RwLock::new(HashMap::new()),
+      config: Arc::new(ServerConfig::default()),
+      auth_user_store: None,
+      message_broadcast: tokio::sync::broadcast::Sender::new(1),
+      openmetrics: prometheus_client::registry::Registry::default(),
+    });
+
+    let (client, server) = duplex(4096);
+    let peer = SocketAddr::from(([127, 0, 0, 1], 50000));
+    let session = Session::new(server_state, upsd_state, peer);
+
+    (BufReader::new(client), tokio::spawn(session.serve(server)))
+  }
+
+  async fn request(client: &mut BufReader<DuplexStream>, line: &str) -> String {
+    client.get_mut().write_all(line.as_bytes()).await.unwrap();
+    client.get_mut().write_all(b"\n").await.unwrap();
+
+    let mut reply = String::new();
+    client.read_line(&mut reply).await.unwrap();
+    reply.trim_end().to_owned()
+  }
+
+  /// Upsd stub accepting `PASSWORD secret` only. `INSTCMD ups beeper.off` fails with
+  /// `CMD-NOT-SUPPORTED`, other commands succeed.
+  fn is_write(line: &str) -> bool {
+    ["SET VAR ", "INSTCMD ", "FSD "]
+      .iter()
+      .any(|prefix| line.starts_with(prefix))
+  }
+
+  async fn spawn_upsd() -> (NutPoolClient, JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let pool = NutPoolClientBuilder::new(ServerAddr::from(listener.local_addr().unwrap())).build();
+
+    let task = tokio::spawn(async move {
+      while let Ok((stream, _)) = listener.accept().await {
+        tokio::spawn(async move {
+          let (reader, mut writer) = stream.into_split();
+          let mut lines = BufReader::new(reader).lines();
+          let mut authorized = false;
+
+          while let Ok(Some(line)) = lines.next_line().await {
+            // NOTE: Like upsd, any password is accepted and only checked on write requests.
+            if let Some(value) = line.strip_prefix("PASSWORD ") {
+              authorized = value == "secret";
+            }
+
+            let reply = match line.as_str() {
+              "LOGOUT" => "OK Goodbye\n",
+              "SET TRACKING ON" => "ERR UNKNOWN-COMMAND\n",
+              line if !authorized && is_write(line) => "ERR ACCESS-DENIED\n",
+              "INSTCMD ups beeper.off" => "ERR CMD-NOT-SUPPORTED\n",
+              "FSD ups" => "OK FSD-SET\n",
+              _ => "OK\n",
+            };
+
+            if writer.write_all(reply.as_bytes()).await.is_err() {
+              break;
+            }
+          }
+        });
+      }
+    });
+
+    (pool, task)
+  }
+
+  #[tokio::test]
+  async fn login_requires_username_and_password() {
+    let (mut client, session) = start_session(None);
+
+    assert_eq!(
+      request(&mut client, "LOGIN ups").await,
+      "ERR USERNAME-REQUIRED"
+    );
+    assert_eq!(request(&mut client, "USERNAME monuser").await, "OK");
+    assert_eq!(
+      request(&mut client, "USERNAME other").await,
+      "ERR ALREADY-SET-USERNAME"
+    );
+    assert_eq!(
+      request(&mut client, "LOGIN ups").await,
+      "ERR PASSWORD-REQUIRED"
+    );
+    assert_eq!(request(&mut client, "PASSWORD secret").await, "OK");
+    assert_eq!(
+      request(&mut client, "PASSWORD other").await,
+      "ERR ALREADY-SET-PASSWORD"
+    );
+    assert_eq!(
+      request(&mut client, "LOGIN unknown").await,
+      "ERR UNKNOWN-UPS"
+    );
+    assert_eq!(request(&mut client, "LOGIN ups").await, "OK");
+    assert_eq!(
+      request(&mut client, "LOGIN ups").await,
+      "ERR ALREADY-ATTACHED"
+    );
+    assert_eq!(request(&mut client, "LOGOUT").await, "OK Goodbye");
+
+    session.await.unwrap().unwrap();
+  }
+
+  #[tokio::test]
+  async fn login_does_not_check_credentials() {
+    let (pool, upsd) = spawn_upsd().await;
+
+    let (mut client, _) = start_session(Some(pool.clone()));
+    assert_eq!(request(&mut client, "USERNAME monuser").await, "OK");
+    assert_eq!(request(&mut client, "PASSWORD wrong").await, "OK");
+    assert_eq!(request(&mut client, "LOGIN ups").await, "OK");
+    assert_eq!(
+      request(&mut client, "INSTCMD ups beeper.on").await,
+      "ERR ACCESS-DENIED"
+    );
+
+    pool.close().await;
+    upsd.abort();
+  }
+
+  #[tokio::test]
+  async fn stale_device_has_no_data() {
+    let (mut client, _) = start_session(None);
+
+    assert_eq!(
+      request(&mut client, "GET VAR ups ups.status").await,
+      "VAR ups ups.status \"OL\""
+    );
+    assert_eq!(
+      request(&mut client, "GET VAR stale ups.status").await,
+      "ERR DATA-STALE"
+    );
+    assert_eq!(
+      request(&mut client, "GET UPSDESC stale").await,
+      "UPSDESC stale \"Unavailable\""
+    );
+  }
+
+  #[tokio::test]
+  async fn rejects_long_lines() {
+    let (mut client, session) = start_session(None);
+    let line = format!("GET VAR ups {}", "x".repeat(2048));
+
+    assert_eq!(request(&mut client, &line).await, "ERR TOO-LONG");
+
+    session.await.unwrap().unwrap();
+  }
+
+  #[tokio::test]
+  async fn forwards_write_requests() {
+    let (pool, upsd) = spawn_upsd().await;
+    let (mut client, _) = start_session(Some(pool.clone()));
+
+    assert_eq!(
+      request(&mut client, "INSTCMD ups beeper.on").await,
+      "ERR USERNAME-REQUIRED"
+    );
+    assert_eq!(request(&mut client, "USERNAME admin").await, "OK");
+    assert_eq!(
+      request(&mut client, "INSTCMD ups beeper.on").await,
+      "ERR PASSWORD-REQUIRED"
+    );
+    assert_eq!(request(&mut client, "PASSWORD secret").await, "OK");
+    assert_eq!(request(&mut client, "INSTCMD ups beeper.on").await, "OK");
+    assert_eq!(
+      request(&mut client, "INSTCMD ups beeper.off").await,
+      "ERR CMD-NOT-SUPPORTED"
+    );
+    assert_eq!(request(&mut client, "FSD ups").await, "OK FSD-SET");
+
+    let (mut client, _) = start_session(Some(pool.clone()));
+    assert_eq!(request(&mut client, "USERNAME admin").await, "OK");
+    assert_eq!(request(&mut client, "PASSWORD wrong").await, "OK");
+    assert_eq!(
+      request(&mut client, "INSTCMD ups beeper.on").await,
+      "ERR ACCESS-DENIED"
+    );
+
+    pool.close().await;
+    upsd.abort();
+  }
+
+  #[tokio::test]
+  async fn unreachable_upsd_is_driver_not_connected() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let pool = NutPoolClientBuilder::new(ServerAddr::from(addr)).build();
+    let (mut client, _) = start_session(Some(pool.clone()));
+
+    assert_eq!(request(&mut client, "USERNAME admin").await, "OK");
+    assert_eq!(request(&mut client, "PASSWORD secret").await, "OK");
+    assert_eq!(
+      request(&mut client, "INSTCMD ups beeper.on").await,
+      "ERR DRIVER-NOT-CONNECTED"
+    );
+    assert_eq!(request(&mut client, "LOGIN ups").await, "OK");
+
+    pool.close().await;
+  }
+
+  #[test]
+  fn cached_var_types() {
+    let delay = VarName::new_unchecked("ups.delay.shutdown");
+    let mut device = device(
+      "ups",
+      UpsStatus::ONLINE,
+      UpsVariables::from([
+        (VarName::UPS_STATUS, Value::from("OL")),
+        (VarName::BATTERY_CHARGE, Value::from(100)),
+        (delay.clone(), Value::from(20)),
+      ]),
+    );
+    device.rw_variables = HashMap::from([(delay.clone(), VarDetail::Number)]);
+
+    assert_eq!(
+      var_types(&device, &delay),
+      Some(vec![VarType::ReadWrite, VarType::Number])
+    );
+    assert_eq!(
+      var_types(&device, &VarName::UPS_STATUS),
+      Some(vec![VarType::String { max_len: 2 }])
+    );
+    assert_eq!(
+      var_types(&device, &VarName::BATTERY_CHARGE),
+      Some(vec![VarType::Number])
+    );
+    assert_eq!(var_types(&device, &VarName::UPS_MODEL), None);
+  }
+}
@@ -1,2 +1,4 @@
 pub mod collector;
+pub mod command_observer;
 mod known_metric;
+pub mod pool_collector;
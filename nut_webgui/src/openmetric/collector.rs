@@ -1,4 +1,7 @@
-use super::known_metric::{KNOWN_DESCRIPTORS, METRIC_UPS_STATUS, METRIC_UPS_STATUS_HELP};
+use super::known_metric::{
+  KNOWN_DESCRIPTORS, METRIC_UPS_STALE, METRIC_UPS_STALE_HELP, METRIC_UPS_STATUS,
+  METRIC_UPS_STATUS_HELP,
+};
 use crate::state::UpsdState;
 use prometheus_client::{
   collector::Collector,
@@ -24,6 +27,13 @@ struct UpsStatusLabelSet {
   device: Rc<str>,
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct UpsStaleLabelSet {
+  group: &'static str,
+  namespace: Arc<str>,
+  device: Rc<str>,
+}
+
 impl UpsdStatCollector {
   #[inline]
   pub const fn new(upsd_state: Arc<UpsdState>) -> Self {
@@ -34,6 +44,7 @@ impl UpsdStatCollector {
 impl Collector for UpsdStatCollector {
   fn encode(&self, mut encoder: encoding::DescriptorEncoder) -> Result<(), std::fmt::Error> {
     let state = self.inner.daemon_state.blocking_read();
+    let stale_threshold = self.inner.config.get_stale_threshold();
 
     for (device_name, entry) in state.devices.iter() {
       let mut status_encoder = encoder.encode_descriptor(
@@ -68,6 +79,48 @@ impl Collector for UpsdStatCollector {
           .encode_gauge(&0)?;
       }
 
+      for status in entry.unknown_status() {
+        let status_label = UpsStatusLabelSet {
+          status: status.to_owned(),
+          namespace: self.inner.namespace.clone(),
+          device: name.clone(),
+        };
+
+        status_encoder
+          .encode_family(&status_label)?
+          .encode_gauge(&1)?;
+      }
+
+      let stale = entry.stale_groups(stale_threshold);
+      let mut stale_encoder = encoder.encode_descriptor(
+        METRIC_UPS_STALE,
+        METRIC_UPS_STALE_HELP,
+        None,
+        MetricType::Gauge,
+      )?;
+
+      for (group, is_stale) in [
+        ("status", stale.status),
+        ("variables", stale.variables),
+        ("clients", stale.clients),
+        ("commands", stale.commands),
+      ] {
+        let stale_label = UpsStaleLabelSet {
+          group,
+          namespace: self.inner.namespace.clone(),
+          device: name.clone(),
+        };
+
+        stale_encoder
+          .encode_family(&stale_label)?
+          .encode_gauge(&u32::from(is_stale))?;
+      }
+
+      // Stale values are omitted instead of being reported as current readings.
+      if stale.variables {
+        continue;
+      }
+
       for descriptor in KNOWN_DESCRIPTORS {
         match descriptor.value(&entry.variables) {
           Some(metric_value) => {
@@ -0,0 +1,65 @@
+use crate::state::UpsdNamespace;
+use nut_webgui_upsmc::{
+  client::{CommandEvent, CommandObserver},
+  error::ErrorKind,
+};
+use prometheus_client::{
+  encoding::EncodeLabelSet,
+  metrics::{
+    family::Family,
+    histogram::{Histogram, exponential_buckets},
+  },
+};
+
+pub type CommandDurationFamily = Family<CommandLabelSet, Histogram, fn() -> Histogram>;
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct CommandLabelSet {
+  namespace: UpsdNamespace,
+  command: &'static str,
+  result: &'static str,
+}
+
+/// Records upsd command durations per namespace.
+pub struct CommandDurationObserver {
+  namespace: UpsdNamespace,
+  family: CommandDurationFamily,
+}
+
+/// Creates an empty command duration family. Buckets start from 1ms up to ~16s.
+pub fn new_command_duration_family() -> CommandDurationFamily {
+  Family::new_with_constructor(|| Histogram::new(exponential_buckets(0.001, 2.0, 15)))
+}
+
+impl CommandDurationObserver {
+  #[inline]
+  pub const fn new(namespace: UpsdNamespace, family: CommandDurationFamily) -> Self {
+    Self { namespace, family }
+  }
+}
+
+impl CommandObserver for CommandDurationObserver {
+  fn on_command(&self, event: CommandEvent<'_>) {
+    let result = match event.error {
+      None => "ok",
+      Some(ErrorKind::IOError { .. }) => "io_error",
+      Some(ErrorKind::ParseError { .. }) => "parse_error",
+      Some(ErrorKind::ProtocolError { .. }) => "protocol_error",
+      Some(ErrorKind::ProxyError { .. }) => "proxy_error",
+      Some(ErrorKind::ConnectionPoolClosed) => "pool_closed",
+      Some(ErrorKind::EmptyResponse) => "empty_response",
+      Some(ErrorKind::RequestTimeout) => "timeout",
+      Some(ErrorKind::TlsDowngradeRefused) => "tls_downgrade",
+      Some(ErrorKind::Unimplemented) => "unimplemented",
+    };
+
+    self
+      .family
+      .get_or_create(&CommandLabelSet {
+        namespace: self.namespace.clone(),
+        command: event.command,
+        result,
+      })
+      .observe(event.duration.as_secs_f64());
+  }
+}
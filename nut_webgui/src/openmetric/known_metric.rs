@@ -4,6 +4,9 @@ use std::sync::LazyLock;
 
 pub const METRIC_UPS_STATUS: &str = "ups_status";
 pub const METRIC_UPS_STATUS_HELP: &str = "UPS status";
+pub const METRIC_UPS_STALE: &str = "ups_stale";
+pub const METRIC_UPS_STALE_HELP: &str =
+  "Whether device data group is older than the staleness threshold";
 static UNIT_WATT: LazyLock<Unit> = LazyLock::new(|| Unit::Other("watts".to_owned()));
 static UNIT_VA: LazyLock<Unit> = LazyLock::new(|| Unit::Other("voltamps".to_owned()));
 static UNIT_HZ: LazyLock<Unit> = LazyLock::new(|| Unit::Other("hertzs".to_owned()));
@@ -0,0 +1,142 @@
+use crate::state::UpsdNamespace;
+use nut_webgui_upsmc::client::NutPoolClient;
+use prometheus_client::{
+  collector::Collector,
+  encoding::{DescriptorEncoder, EncodeLabelSet, EncodeMetric},
+  metrics::{MetricType, counter::ConstCounter, gauge::ConstGauge},
+  registry::Unit,
+};
+
+/// Exports connection pool statistics for all upsd namespaces.
+pub struct UpsdPoolCollector {
+  pools: Vec<(UpsdNamespace, NutPoolClient)>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct PoolLabelSet {
+  namespace: UpsdNamespace,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct PoolStateLabelSet {
+  namespace: UpsdNamespace,
+  state: &'static str,
+}
+
+impl UpsdPoolCollector {
+  #[inline]
+  pub const fn new(pools: Vec<(UpsdNamespace, NutPoolClient)>) -> Self {
+    Self { pools }
+  }
+}
+
+macro_rules! encode_pool_metric {
+  ($encoder:expr, $pools:expr, $name:literal, $help:literal, $unit:expr, $metric_type:expr, |$stats:ident| $metric:expr) => {{
+    let mut metric_encoder = $encoder.encode_descriptor($name, $help, $unit, $metric_type)?;
+
+    for (namespace, stats) in $pools.iter() {
+      let $stats = stats;
+      $metric.encode(metric_encoder.encode_family(&PoolLabelSet {
+        namespace: namespace.clone(),
+      })?)?;
+    }
+  }};
+}
+
+impl Collector for UpsdPoolCollector {
+  fn encode(&self, mut encoder: DescriptorEncoder) -> Result<(), std::fmt::Error> {
+    let pools: Vec<_> = self
+      .pools
+      .iter()
+      .map(|(namespace, pool)| (namespace.clone(), pool.stats()))
+      .collect();
+
+    {
+      let mut metric_encoder = encoder.encode_descriptor(
+        "upsd_pool_connections",
+        "Number of pooled upsd connections",
+        None,
+        MetricType::Gauge,
+      )?;
+
+      for (namespace, stats) in pools.iter() {
+        for (state, value) in [("idle", stats.idle), ("active", stats.active)] {
+          ConstGauge::new(value as i64).encode(metric_encoder.encode_family(
+            &PoolStateLabelSet {
+              namespace: namespace.clone(),
+              state,
+            },
+          )?)?;
+        }
+      }
+    }
+
+    encode_pool_metric!(
+      encoder,
+      pools,
+      "upsd_pool_max_connections",
+      "Maximum number of pooled upsd connections",
+      None,
+      MetricType::Gauge,
+      |stats| ConstGauge::new(stats.limit as i64)
+    );
+
+    encode_pool_metric!(
+      encoder,
+      pools,
+      "upsd_pool_acquired",
+      "Total number of connection checkouts",
+      None,
+      MetricType::Counter,
+      |stats| ConstCounter::new(stats.acquired)
+    );
+
+    encode_pool_metric!(
+      encoder,
+      pools,
+      "upsd_pool_wait",
+      "Total time spent on waiting for an available connection",
+      Some(&Unit::Seconds),
+      MetricType::Counter,
+      |stats| ConstCounter::new(stats.wait_time.as_secs_f64())
+    );
+
+    encode_pool_metric!(
+      encoder,
+      pools,
+      "upsd_pool_allocations",
+      "Total number of opened connections",
+      None,
+      MetricType::Counter,
+      |stats| ConstCounter::new(stats.allocated)
+    );
+
+    encode_pool_metric!(
+      encoder,
+      pools,
+      "upsd_pool_allocation_failures",
+      "Total number of failed connection attempts",
+      None,
+      MetricType::Counter,
+      |stats| ConstCounter::new(stats.allocation_failures)
+    );
+
+    encode_pool_metric!(
+      encoder,
+      pools,
+      "upsd_pool_deallocations",
+      "Total number of closed connections",
+      None,
+      MetricType::Counter,
+      |stats| ConstCounter::new(stats.deallocated)
+    );
+
+    Ok(())
+  }
+}
+
+impl std::fmt::Debug for UpsdPoolCollector {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("UpsdPoolCollector").finish()
+  }
+}
@@ -0,0 +1,142 @@
+use crate::config::cert_fingerprint::CertFingerprint;
+use nut_webgui_upsmc::rustls::{
+  CertificateError, DigitallySignedStruct, Error, SignatureScheme,
+  client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+  crypto::{CryptoProvider, verify_tls12_signature, verify_tls13_signature},
+  pki_types::{CertificateDer, ServerName, UnixTime},
+};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// Accepts server certificates only by their SHA-256 fingerprint. Certificate chain, hostname and
+/// expiry checks are skipped, but handshake signatures are still verified.
+#[derive(Debug)]
+pub struct PinnedCertVerifier {
+  fingerprints: Box<[CertFingerprint]>,
+  provider: Arc<CryptoProvider>,
+}
+
+impl PinnedCertVerifier {
+  pub fn new(fingerprints: &[CertFingerprint], provider: Arc<CryptoProvider>) -> Self {
+    Self {
+      fingerprints: Box::from(fingerprints),
+      provider,
+    }
+  }
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+  fn verify_server_cert(
+    &self,
+    end_entity: &CertificateDer<'_>,
+    _intermediates: &[CertificateDer<'_>],
+    _server_name: &ServerName<'_>,
+    _ocsp: &[u8],
+    _now: UnixTime,
+  ) -> Result<ServerCertVerified, Error> {
+    let digest: [u8; 32] = Sha256::digest(end_entity.as_ref()).into();
+    let fingerprint = CertFingerprint::from(digest);
+
+    if self.fingerprints.contains(&fingerprint) {
+      Ok(ServerCertVerified::assertion())
+    } else {
+      Err(Error::InvalidCertificate(
+        CertificateError::ApplicationVerificationFailure,
+      ))
+    }
+  }
+
+  fn verify_tls12_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, Error> {
+    verify_tls12_signature(
+      message,
+      cert,
+      dss,
+      &self.provider.signature_verification_algorithms,
+    )
+  }
+
+  fn verify_tls13_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, Error> {
+    verify_tls13_signature(
+      message,
+      cert,
+      dss,
+      &self.provider.signature_verification_algorithms,
+    )
+  }
+
+  fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+    self
+      .provider
+      .signature_verification_algorithms
+      .supported_schemes()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::PinnedCertVerifier;
+  use crate::config::cert_fingerprint::CertFingerprint;
+  use nut_webgui_upsmc::rustls::{
+    CertificateError, Error,
+    client::danger::ServerCertVerifier,
+    crypto::ring::default_provider,
+    pki_types::{CertificateDer, ServerName, UnixTime},
+  };
+  use sha2::{Digest, Sha256};
+  use std::sync::Arc;
+
+  const CERT: &[u8] = b"not a real certificate, only its digest matters";
+
+  fn verify(fingerprints: &[CertFingerprint]) -> Result<(), Error> {
+    let verifier = PinnedCertVerifier::new(fingerprints, Arc::new(default_provider()));
+    let server_name = ServerName::try_from("localhost").unwrap();
+
+    verifier
+      .verify_server_cert(
+        &CertificateDer::from(CERT),
+        &[],
+        &server_name,
+        &[],
+        UnixTime::now(),
+      )
+      .map(|_| ())
+  }
+
+  #[test]
+  fn accepts_matching_fingerprint() {
+    let digest: [u8; 32] = Sha256::digest(CERT).into();
+    let fingerprints = [
+      CertFingerprint::from([0u8; 32]),
+      CertFingerprint::from(digest),
+    ];
+
+    assert!(verify(&fingerprints).is_ok());
+  }
+
+  #[test]
+  fn rejects_mismatched_fingerprint() {
+    let fingerprints = [CertFingerprint::from([0u8; 32])];
+
+    assert!(matches!(
+      verify(&fingerprints),
+      Err(Error::InvalidCertificate(
+        CertificateError::ApplicationVerificationFailure
+      ))
+    ));
+  }
+
+  #[test]
+  fn rejects_without_fingerprints() {
+    assert!(verify(&[]).is_err());
+  }
+}
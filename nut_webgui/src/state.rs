@@ -6,11 +6,14 @@ use crate::{
 use chrono::{DateTime, Utc};
 use core::net::IpAddr;
 use nut_webgui_upsmc::{
-  CmdName, UpsName, Value, VarName, client::NutPoolClient, ups_status::UpsStatus,
+  CmdName, UpsName, Value, VarName,
+  client::{NegotiatedTls, NutPoolClient},
+  json::{self, JsonValue, JsonValues},
+  ups_status::UpsStatus,
   ups_variables::UpsVariables,
 };
 use serde::{Serialize, ser::SerializeStruct};
-use std::{borrow::Borrow, collections::HashMap, sync::Arc};
+use std::{borrow::Borrow, cmp::Ordering, collections::HashMap, sync::Arc, time::Duration};
 use tokio::sync::RwLock;
 
 pub type UpsdNamespace = Arc<str>;
@@ -41,8 +44,9 @@ pub struct UpsdState {
   /// Daemon state
   pub daemon_state: RwLock<DaemonState>,
 
-  /// Daemon connection pool
-  pub connection_pool: NutPoolClient,
+  /// Daemon connection pool. It's `None` for driver namespaces, which read driver sockets
+  /// directly.
+  pub connection_pool: Option<NutPoolClient>,
 
   /// Daemon config
   pub config: UpsdConfig,
@@ -66,6 +70,27 @@ pub struct DaemonState {
 
   /// Daemon server version
   pub ver: Option<Box<str>>,
+
+  /// Active upsd endpoint address
+  pub active_endpoint: Option<Box<str>>,
+
+  /// Last time the connection pool switched to another endpoint
+  pub last_failover: Option<DateTime<Utc>>,
+
+  /// Transport security negotiated with upsd
+  pub negotiated_tls: NegotiatedTls,
+
+  /// Last device sync error, cleared after a successful sync
+  pub last_error: Option<Box<str>>,
+
+  /// Timestamp of [DaemonState::last_error]
+  pub last_error_time: Option<DateTime<Utc>>,
+
+  /// Number of device sync failures since the last successful sync
+  pub consecutive_failures: u32,
+
+  /// Next device sync attempt while upsd is failing
+  pub next_retry: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -82,23 +107,57 @@ pub struct DeviceEntry {
   /// Last modification time of the device.
   pub last_modified: DateTime<Utc>,
 
+  /// Last successful update times of the device data groups.
+  pub last_updated: DeviceFreshness,
+
   /// Device name.
   pub name: UpsName,
 
+  /// Number of clients logged into the device, reported by `GET NUMLOGINS`. It's `None` when
+  /// upsd does not support the command.
+  pub num_logins: Option<usize>,
+
   /// Information about writeable variables supported by the device.
   pub rw_variables: HashMap<VarName, VarDetail>,
 
   /// Device status.
   ///
   /// ## Remark
-  /// This field only contains well-known UPS statuses from RFC-9271. Some UPS drivers may have
-  /// additional status names. For those status names, check the `ups.status` in the variable map.
+  /// This field only contains well-known UPS statuses. Some UPS drivers may have additional status
+  /// names. For those status names, see [`DeviceEntry::unknown_status`].
   pub status: UpsStatus,
 
   /// Device variables.
+  #[serde(serialize_with = "json::serialize_variables")]
   pub variables: UpsVariables,
 }
 
+/// Last successful update times of device data groups. Groups keep their previous values when a
+/// sync fails, so these timestamps tell how old the values are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DeviceFreshness {
+  /// `ups.status`
+  pub status: DateTime<Utc>,
+
+  /// Device variables
+  pub variables: DateTime<Utc>,
+
+  /// Attached clients
+  pub clients: DateTime<Utc>,
+
+  /// Supported commands
+  pub commands: DateTime<Utc>,
+}
+
+/// Device data groups with values older than the staleness threshold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StaleGroups {
+  pub status: bool,
+  pub variables: bool,
+  pub clients: bool,
+  pub commands: bool,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ClientInfo {
   pub addr: IpAddr,
@@ -110,7 +169,16 @@ pub enum VarDetail {
   String { max_len: usize },
   Number,
   Enum { options: Vec<Value> },
-  Range { min: Value, max: Value },
+  Range { ranges: Vec<VarRange> },
+}
+
+/// Inclusive value range reported by `LIST RANGE`.
+#[derive(Debug, Clone, Serialize)]
+pub struct VarRange {
+  #[serde(serialize_with = "json::serialize_value")]
+  pub min: Value,
+  #[serde(serialize_with = "json::serialize_value")]
+  pub max: Value,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
@@ -145,20 +213,73 @@ impl Serialize for VarDetail {
       VarDetail::Enum { options } => {
         let mut ser = serializer.serialize_struct("VarDetail", 2)?;
         ser.serialize_field("type", "enum")?;
-        ser.serialize_field("options", options)?;
+        ser.serialize_field("options", &JsonValues(options))?;
         ser.end()
       }
-      VarDetail::Range { min, max } => {
-        let mut ser = serializer.serialize_struct("VarDetail", 3)?;
+      VarDetail::Range { ranges } => {
+        let mut ser = serializer.serialize_struct("VarDetail", 4)?;
         ser.serialize_field("type", "range")?;
-        ser.serialize_field("min", min)?;
-        ser.serialize_field("max", max)?;
+
+        // min and max fields are kept for backward compatibility, they are the outer bounds of all
+        // ranges.
+        match self.range_bounds() {
+          Some((min, max)) => {
+            ser.serialize_field("min", &JsonValue(min))?;
+            ser.serialize_field("max", &JsonValue(max))?;
+          }
+          None => {
+            ser.skip_field("min")?;
+            ser.skip_field("max")?;
+          }
+        }
+
+        ser.serialize_field("ranges", ranges)?;
         ser.end()
       }
     }
   }
 }
 
+impl VarDetail {
+  /// Returns the lowest and the highest bounds across all ranges.
+  pub fn range_bounds(&self) -> Option<(&Value, &Value)> {
+    match self {
+      VarDetail::Range { ranges } => {
+        let min = ranges.iter().map(|r| &r.min).min_by(cmp_lossy)?;
+        let max = ranges.iter().map(|r| &r.max).max_by(cmp_lossy)?;
+
+        Some((min, max))
+      }
+      _ => None,
+    }
+  }
+}
+
+#[inline]
+fn cmp_lossy(lhs: &&Value, rhs: &&Value) -> Ordering {
+  lhs
+    .as_lossy_f64()
+    .partial_cmp(&rhs.as_lossy_f64())
+    .unwrap_or(Ordering::Equal)
+}
+
+impl VarRange {
+  /// Checks if the value is within the range. Returns `None` if driver reported bounds are not
+  /// numeric values.
+  pub fn contains(&self, value: f64) -> Option<bool> {
+    match (self.min.as_lossy_f64(), self.max.as_lossy_f64()) {
+      (Some(min), Some(max)) => Some(min <= value && value <= max),
+      _ => None,
+    }
+  }
+}
+
+impl std::fmt::Display for VarRange {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_fmt(format_args!("[{}, {}]", self.min, self.max))
+  }
+}
+
 impl std::fmt::Display for ConnectionStatus {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
@@ -170,15 +291,67 @@ impl std::fmt::Display for ConnectionStatus {
 }
 
 impl DeviceEntry {
+  /// Status names from `ups.status` that are not represented by [`DeviceEntry::status`].
+  pub fn unknown_status(&self) -> Vec<&str> {
+    match self.variables.get(VarName::UPS_STATUS) {
+      Some(Value::String(text)) => UpsStatus::unknown_tokens(text).collect(),
+      _ => Vec::new(),
+    }
+  }
+
+  /// Returns data groups older than `threshold`.
+  #[inline]
+  pub fn stale_groups(&self, threshold: Duration) -> StaleGroups {
+    self.last_updated.stale_groups(Utc::now(), threshold)
+  }
+
   pub fn mark_as_dead_with(&mut self, status: UpsStatus) {
     self.status = status;
     self.commands.clear();
+    self.num_logins = None;
     self.rw_variables.clear();
     self.variables.clear();
     self.last_modified = Utc::now();
   }
 }
 
+impl DeviceFreshness {
+  /// All groups are updated at `time`.
+  #[inline]
+  pub const fn new(time: DateTime<Utc>) -> Self {
+    Self {
+      status: time,
+      variables: time,
+      clients: time,
+      commands: time,
+    }
+  }
+
+  /// Returns groups older than `threshold` at `now`.
+  pub fn stale_groups(&self, now: DateTime<Utc>, threshold: Duration) -> StaleGroups {
+    let is_stale = |time: DateTime<Utc>| {
+      now
+        .signed_duration_since(time)
+        .to_std()
+        .is_ok_and(|age| age > threshold)
+    };
+
+    StaleGroups {
+      status: is_stale(self.status),
+      variables: is_stale(self.variables),
+      clients: is_stale(self.clients),
+      commands: is_stale(self.commands),
+    }
+  }
+}
+
+impl StaleGroups {
+  #[inline]
+  pub const fn any(&self) -> bool {
+    self.status || self.variables || self.clients || self.commands
+  }
+}
+
 impl DaemonState {
   #[inline]
   pub fn new() -> DaemonState {
@@ -188,8 +361,44 @@ impl DaemonState {
       prot_ver: None,
       status: ConnectionStatus::NotReady,
       ver: None,
+      active_endpoint: None,
+      last_failover: None,
+      negotiated_tls: NegotiatedTls::Pending,
+      last_error: None,
+      last_error_time: None,
+      consecutive_failures: 0,
+      next_retry: None,
     }
   }
+
+  /// Resets reconnect diagnostics after a successful device sync.
+  #[inline]
+  pub fn record_success(&mut self) {
+    self.consecutive_failures = 0;
+    self.last_error = None;
+    self.last_error_time = None;
+    self.next_retry = None;
+  }
+
+  /// Records a failed device sync, and returns the number of consecutive failures.
+  pub fn record_failure<E>(&mut self, err: &E) -> u32
+  where
+    E: core::fmt::Display,
+  {
+    self.last_error = Some(err.to_string().into_boxed_str());
+    self.last_error_time = Some(Utc::now());
+    self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+    self.consecutive_failures
+  }
+
+  /// Updates active endpoint and negotiated TLS details from the connection pool.
+  pub fn update_connection(&mut self, pool: &NutPoolClient) {
+    let endpoint = pool.active_endpoint();
+
+    self.active_endpoint = Some(endpoint.addr);
+    self.last_failover = endpoint.last_failover.map(DateTime::<Utc>::from);
+    self.negotiated_tls = pool.negotiated_tls();
+  }
 }
 
 impl From<CmdName> for DescriptionKey {
@@ -222,3 +431,89 @@ impl Borrow<str> for DescriptionKey {
     &self.inner
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{DaemonState, DeviceFreshness, VarDetail, VarRange};
+  use chrono::{TimeDelta, Utc};
+  use nut_webgui_upsmc::{Decimal, Value};
+  use std::{str::FromStr, time::Duration};
+
+  fn range_of(min: Value, max: Value) -> VarRange {
+    VarRange { min, max }
+  }
+
+  #[test]
+  fn stale_groups() {
+    let now = Utc::now();
+    let mut freshness = DeviceFreshness::new(now);
+    freshness.variables = now - TimeDelta::seconds(120);
+
+    let stale = freshness.stale_groups(now, Duration::from_secs(90));
+
+    assert!(stale.any());
+    assert!(stale.variables);
+    assert!(!stale.status);
+    assert!(!stale.clients);
+    assert!(!stale.commands);
+    assert!(!freshness.stale_groups(now, Duration::from_secs(180)).any());
+  }
+
+  #[test]
+  fn success_clears_diagnostics() {
+    let mut state = DaemonState::new();
+
+    assert_eq!(state.record_failure(&"connection refused"), 1);
+    assert_eq!(state.record_failure(&"connection refused"), 2);
+    assert_eq!(state.last_error.as_deref(), Some("connection refused"));
+    assert!(state.last_error_time.is_some());
+
+    state.record_success();
+
+    assert_eq!(state.consecutive_failures, 0);
+    assert!(state.last_error.is_none());
+    assert!(state.last_error_time.is_none());
+    assert!(state.next_retry.is_none());
+  }
+
+  #[test]
+  fn range_contains() {
+    let range = range_of(Value::from(10), Value::from(20));
+
+    assert_eq!(range.contains(9.9), Some(false));
+    assert_eq!(range.contains(10.0), Some(true));
+    assert_eq!(range.contains(15.0), Some(true));
+    assert_eq!(range.contains(20.0), Some(true));
+    assert_eq!(range.contains(20.1), Some(false));
+
+    assert_eq!(
+      range_of(Value::from("low"), Value::from(20)).contains(15.0),
+      None
+    );
+  }
+
+  #[test]
+  fn multi_range_bounds() {
+    let detail = VarDetail::Range {
+      ranges: vec![
+        range_of(Value::from(60), Value::from(120)),
+        range_of(Value::from(0), Value::from(30)),
+        range_of(
+          Value::from(Decimal::from_str("180.5").unwrap()),
+          Value::from(300),
+        ),
+      ],
+    };
+
+    let (min, max) = detail.range_bounds().unwrap();
+
+    assert_eq!(min, &Value::from(0));
+    assert_eq!(max, &Value::from(300));
+    assert!(
+      VarDetail::Range { ranges: Vec::new() }
+        .range_bounds()
+        .is_none()
+    );
+    assert!(VarDetail::Number.range_bounds().is_none());
+  }
+}
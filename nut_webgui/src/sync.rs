@@ -1,6 +1,9 @@
+mod backoff;
 mod reverse_dns;
 
+pub mod client_resolver;
 pub mod error;
 pub mod sync_desc;
 pub mod sync_device;
+pub mod sync_driver;
 pub mod sync_status;
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+/// Exponential reconnect backoff. The delay doubles with each consecutive failure, starting
+/// from `base` and capped at `max`. A random jitter of up to `base` is added on top, so the delay
+/// is never shorter than `base`.
+#[derive(Debug, Clone, Copy)]
+pub struct Backoff {
+  base: Duration,
+  max: Duration,
+}
+
+impl Backoff {
+  #[inline]
+  pub fn new(base: Duration, max: Duration) -> Self {
+    Self {
+      base,
+      max: max.max(base),
+    }
+  }
+
+  /// Returns the delay before the next attempt. `jitter` is expected to be in `[0, 1)`, and
+  /// extends the delay so multiple namespaces do not retry in lockstep.
+  pub fn delay(&self, failures: u32, jitter: f64) -> Duration {
+    if failures == 0 {
+      return self.base;
+    }
+
+    let multiplier = 2u32.saturating_pow(failures - 1);
+    let delay = self.base.saturating_mul(multiplier).min(self.max);
+
+    delay.saturating_add(self.base.mul_f64(jitter.clamp(0.0, 1.0)))
+  }
+}
+
+/// Random jitter factor in `[0, 1)`.
+pub fn jitter() -> f64 {
+  match getrandom::u64() {
+    Ok(value) => (value >> 11) as f64 / (1u64 << 53) as f64,
+    Err(_) => 0.5,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Backoff, jitter};
+  use std::time::Duration;
+
+  #[test]
+  fn exponential_delay() {
+    let backoff = Backoff::new(Duration::from_secs(10), Duration::from_secs(60));
+
+    assert_eq!(backoff.delay(0, 0.0), Duration::from_secs(10));
+    assert_eq!(backoff.delay(1, 0.0), Duration::from_secs(10));
+    assert_eq!(backoff.delay(1, 0.5), Duration::from_secs(15));
+    assert_eq!(backoff.delay(2, 0.0), Duration::from_secs(20));
+    assert_eq!(backoff.delay(3, 0.0), Duration::from_secs(40));
+    assert_eq!(backoff.delay(4, 0.0), Duration::from_secs(60));
+    assert_eq!(backoff.delay(4, 1.0), Duration::from_secs(70));
+    assert_eq!(backoff.delay(u32::MAX, 0.0), Duration::from_secs(60));
+  }
+
+  #[test]
+  fn never_below_base() {
+    let backoff = Backoff::new(Duration::from_secs(10), Duration::from_secs(60));
+
+    for failures in 0..8 {
+      assert!(backoff.delay(failures, 0.0) >= Duration::from_secs(10));
+    }
+  }
+
+  #[test]
+  fn max_below_base() {
+    let backoff = Backoff::new(Duration::from_secs(30), Duration::from_secs(10));
+
+    assert_eq!(backoff.delay(5, 0.0), Duration::from_secs(30));
+  }
+
+  #[test]
+  fn jitter_range() {
+    for _ in 0..64 {
+      let value = jitter();
+      assert!((0.0..1.0).contains(&value));
+    }
+  }
+}
@@ -0,0 +1,319 @@
+use crate::{
+  config::ReverseDnsConfig,
+  sync::reverse_dns::{Error, lookup_ip},
+};
+use futures::{
+  FutureExt,
+  future::{BoxFuture, Shared, join_all},
+};
+use std::{
+  collections::HashMap,
+  net::IpAddr,
+  sync::{Arc, Mutex},
+  time::{Duration, Instant},
+};
+use tokio::sync::Semaphore;
+use tracing::{debug, warn};
+
+/// Expired cache entries are pruned once the cache grows past this size.
+const CACHE_PRUNE_THRESHOLD: usize = 1024;
+
+/// Maximum number of reverse DNS lookups running on the blocking thread pool at once.
+const MAX_CONCURRENT_LOOKUPS: usize = 8;
+
+type Lookup = Shared<BoxFuture<'static, Option<Box<str>>>>;
+
+type LookupFn = fn(IpAddr) -> Result<Box<str>, Error>;
+
+/// Resolves attached client names. Static aliases take precedence, other addresses are looked up
+/// with reverse DNS on the blocking thread pool, and the results are cached with separate TTLs for
+/// resolved and failed lookups.
+///
+/// Concurrent lookups of the same address share a single in-flight lookup. `getnameinfo` cannot be
+/// cancelled, so timed out lookups keep running in the background and their results are still
+/// cached, while the number of blocking lookups is limited to [MAX_CONCURRENT_LOOKUPS].
+#[derive(Debug)]
+pub struct ClientResolver {
+  aliases: HashMap<IpAddr, Box<str>>,
+  lookups: Arc<LookupState>,
+  enabled: bool,
+  timeout: Duration,
+}
+
+#[derive(Debug)]
+struct LookupState {
+  cache: Mutex<HashMap<IpAddr, CacheEntry>>,
+  in_flight: Mutex<HashMap<IpAddr, Lookup>>,
+  permits: Arc<Semaphore>,
+  lookup_fn: LookupFn,
+  positive_ttl: Duration,
+  negative_ttl: Duration,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+  name: Option<Box<str>>,
+  expires_at: Instant,
+}
+
+impl ClientResolver {
+  pub fn new(config: &ReverseDnsConfig, aliases: HashMap<IpAddr, Box<str>>) -> Self {
+    Self::with_lookup(config, aliases, lookup_ip)
+  }
+
+  fn with_lookup(
+    config: &ReverseDnsConfig,
+    aliases: HashMap<IpAddr, Box<str>>,
+    lookup_fn: LookupFn,
+  ) -> Self {
+    Self {
+      aliases,
+      lookups: Arc::new(LookupState {
+        cache: Mutex::new(HashMap::new()),
+        in_flight: Mutex::new(HashMap::new()),
+        permits: Arc::new(Semaphore::new(MAX_CONCURRENT_LOOKUPS)),
+        lookup_fn,
+        positive_ttl: Duration::from_secs(config.positive_ttl),
+        negative_ttl: Duration::from_secs(config.negative_ttl),
+      }),
+      enabled: config.enabled,
+      timeout: Duration::from_secs(config.timeout.max(1)),
+    }
+  }
+
+  /// Resolves multiple addresses concurrently. Addresses with timed out lookups are not included,
+  /// so callers can keep previously resolved names.
+  pub async fn resolve_all<I>(&self, addrs: I) -> HashMap<IpAddr, Option<Box<str>>>
+  where
+    I: IntoIterator<Item = IpAddr>,
+  {
+    join_all(
+      addrs
+        .into_iter()
+        .map(|addr| async move { Some((addr, self.try_resolve(addr).await?)) }),
+    )
+    .await
+    .into_iter()
+    .flatten()
+    .collect()
+  }
+
+  /// Returns [`None`] if the lookup times out.
+  async fn try_resolve(&self, addr: IpAddr) -> Option<Option<Box<str>>> {
+    if let Some(alias) = self.aliases.get(&addr) {
+      return Some(Some(alias.clone()));
+    }
+
+    if !self.enabled || addr.is_loopback() {
+      return Some(None);
+    }
+
+    let lookup = match self.lookups.cached_or_lookup(addr, Instant::now()) {
+      Ok(name) => return Some(name),
+      Err(lookup) => lookup,
+    };
+
+    match tokio::time::timeout(self.timeout, lookup).await {
+      Ok(name) => Some(name),
+      Err(_) => {
+        debug!(message = "reverse dns lookup timed out", client = %addr);
+        None
+      }
+    }
+  }
+}
+
+impl LookupState {
+  /// Returns the cached name, or joins the in-flight lookup of the address, starting one if
+  /// needed.
+  fn cached_or_lookup(
+    self: &Arc<Self>,
+    addr: IpAddr,
+    now: Instant,
+  ) -> Result<Option<Box<str>>, Lookup> {
+    // NOTE: Lookups update the cache and the in-flight map under the in-flight lock, so a
+    // finished lookup is never started again before its result is cached.
+    let mut in_flight = self.in_flight.lock().unwrap_or_else(|err| err.into_inner());
+
+    if let Some(name) = self.cached(addr, now) {
+      return Ok(name);
+    }
+
+    let lookup = in_flight.entry(addr).or_insert_with(|| self.lookup(addr));
+
+    Err(lookup.clone())
+  }
+
+  /// Starts a lookup on the blocking thread pool. The lookup runs to completion even if all
+  /// callers stop waiting for it.
+  fn lookup(self: &Arc<Self>, addr: IpAddr) -> Lookup {
+    let state = self.clone();
+    let lookup_fn = self.lookup_fn;
+
+    tokio::spawn(async move {
+      // NOTE: Permits are held until the blocking lookup returns, timed out lookups included.
+      let _permit = state.permits.clone().acquire_owned().await;
+      let name = match tokio::task::spawn_blocking(move || lookup_fn(addr)).await {
+        Ok(Ok(name)) => Some(name),
+        Ok(Err(err)) => {
+          debug!(message = "reverse dns lookup failed", client = %addr, reason = %err);
+          None
+        }
+        Err(err) => {
+          warn!(message = "reverse dns lookup task failed", client = %addr, reason = %err);
+          None
+        }
+      };
+
+      let mut in_flight = state
+        .in_flight
+        .lock()
+        .unwrap_or_else(|err| err.into_inner());
+      state.insert(addr, name.clone(), Instant::now());
+      _ = in_flight.remove(&addr);
+
+      name
+    })
+    .map(|result| result.ok().flatten())
+    .boxed()
+    .shared()
+  }
+
+  fn cached(&self, addr: IpAddr, now: Instant) -> Option<Option<Box<str>>> {
+    let cache = self.cache.lock().unwrap_or_else(|err| err.into_inner());
+
+    cache
+      .get(&addr)
+      .filter(|entry| entry.expires_at > now)
+      .map(|entry| entry.name.clone())
+  }
+
+  fn insert(&self, addr: IpAddr, name: Option<Box<str>>, now: Instant) {
+    let ttl = if name.is_some() {
+      self.positive_ttl
+    } else {
+      self.negative_ttl
+    };
+
+    let mut cache = self.cache.lock().unwrap_or_else(|err| err.into_inner());
+
+    if cache.len() >= CACHE_PRUNE_THRESHOLD {
+      cache.retain(|_, entry| entry.expires_at > now);
+    }
+
+    cache.insert(
+      addr,
+      CacheEntry {
+        name,
+        expires_at: now + ttl,
+      },
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ClientResolver;
+  use crate::{config::ReverseDnsConfig, sync::reverse_dns::Error};
+  use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+  };
+
+  const CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 10));
+
+  static SLOW_LOOKUPS: AtomicUsize = AtomicUsize::new(0);
+
+  fn config(enabled: bool) -> ReverseDnsConfig {
+    ReverseDnsConfig {
+      enabled,
+      positive_ttl: 60,
+      negative_ttl: 10,
+      timeout: 1,
+    }
+  }
+
+  fn resolver(enabled: bool) -> ClientResolver {
+    ClientResolver::new(
+      &config(enabled),
+      HashMap::from([(CLIENT, Box::from("nas01"))]),
+    )
+  }
+
+  fn slow_lookup(_: IpAddr) -> Result<Box<str>, Error> {
+    SLOW_LOOKUPS.fetch_add(1, Ordering::Relaxed);
+    std::thread::sleep(Duration::from_millis(100));
+
+    Ok(Box::from("slow-host"))
+  }
+
+  #[tokio::test]
+  async fn alias_precedence() {
+    let resolver = resolver(false);
+
+    assert_eq!(
+      resolver.try_resolve(CLIENT).await,
+      Some(Some(Box::from("nas01")))
+    );
+    assert_eq!(
+      resolver
+        .try_resolve(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 11)))
+        .await,
+      Some(None)
+    );
+  }
+
+  #[tokio::test]
+  async fn concurrent_lookups_are_shared() {
+    let resolver = ClientResolver::with_lookup(&config(true), HashMap::new(), slow_lookup);
+    let client = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 30));
+
+    let names = resolver.resolve_all([client, client, client]).await;
+    let (first, second) = tokio::join!(resolver.try_resolve(client), resolver.try_resolve(client));
+
+    assert_eq!(names.get(&client), Some(&Some(Box::from("slow-host"))));
+    assert_eq!(first, Some(Some(Box::from("slow-host"))));
+    assert_eq!(second, first);
+    assert_eq!(SLOW_LOOKUPS.load(Ordering::Relaxed), 1);
+  }
+
+  #[test]
+  fn cache_ttl() {
+    let resolver = resolver(true);
+    let now = Instant::now();
+    let resolved = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 20));
+    let failed = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 21));
+
+    resolver
+      .lookups
+      .insert(resolved, Some(Box::from("ups-host")), now);
+    resolver.lookups.insert(failed, None, now);
+
+    assert_eq!(
+      resolver
+        .lookups
+        .cached(resolved, now + Duration::from_secs(30)),
+      Some(Some(Box::from("ups-host")))
+    );
+    assert_eq!(
+      resolver
+        .lookups
+        .cached(failed, now + Duration::from_secs(5)),
+      Some(None)
+    );
+    assert_eq!(
+      resolver
+        .lookups
+        .cached(failed, now + Duration::from_secs(10)),
+      None
+    );
+    assert_eq!(
+      resolver
+        .lookups
+        .cached(resolved, now + Duration::from_secs(60)),
+      None
+    );
+  }
+}
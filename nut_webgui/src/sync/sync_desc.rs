@@ -91,6 +91,11 @@ impl DescriptionTask {
       }
     };
 
+    // Driver sockets do not provide any description, only upsd namespaces are synced.
+    let Some(connection_pool) = upsd_state.connection_pool.as_ref() else {
+      return;
+    };
+
     let task_ctx: Vec<TaskContext> = {
       let mut tmp_lookup = HashSet::new();
       let mut ctxs = Vec::new();
@@ -146,7 +151,7 @@ impl DescriptionTask {
       let mut task_set = JoinSet::new();
 
       for ctx in task_ctx {
-        let nut_client = upsd_state.connection_pool.clone();
+        let nut_client = connection_pool.clone();
         task_set.spawn(Self::load_descs(nut_client, ctx));
       }
 
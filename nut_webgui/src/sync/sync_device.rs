@@ -1,23 +1,32 @@
 use crate::{
   background_service::BackgroundService,
   event::{batch::EventBatch, channel::EventChannel},
-  state::{ClientInfo, ConnectionStatus, DeviceEntry, UpsdState, VarDetail},
+  state::{
+    ClientInfo, ConnectionStatus, DeviceEntry, DeviceFreshness, UpsdState, VarDetail, VarRange,
+  },
   sync::{
+    backoff::{Backoff, jitter},
+    client_resolver::ClientResolver,
     error::{DeviceLoadError, SyncTaskError},
-    reverse_dns::lookup_ip,
+    sync_status::take_batch_result,
   },
 };
 use chrono::Utc;
 use futures::{future::join_all, join};
 use nut_webgui_upsmc::{
   UpsName, Value, VarName, VarType,
-  client::{AsyncNutClient, NutPoolClient},
+  client::{AsyncNutClient, Batch, NutPoolClient},
   response::{DaemonVer, ProtVer, UpsDevice},
   ups_status::UpsStatus,
   ups_variables::UpsVariables,
 };
 use std::{collections::HashMap, sync::Arc, time::Duration};
-use tokio::{select, task::JoinSet, time::interval, try_join};
+use tokio::{
+  select,
+  task::JoinSet,
+  time::{Instant, sleep_until},
+  try_join,
+};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
@@ -25,26 +34,38 @@ use tracing::{debug, error, info, warn};
 pub struct DeviceSyncService {
   event_channel: EventChannel,
   state: Arc<UpsdState>,
+  connection_pool: NutPoolClient,
+  client_resolver: Arc<ClientResolver>,
 }
 
 struct DeviceSyncTask {
   state: Arc<UpsdState>,
   event_channel: EventChannel,
+  connection_pool: NutPoolClient,
+  client_resolver: Arc<ClientResolver>,
 }
 
 struct DeviceDiffPatch {
   added: Vec<DeviceEntry>,
   deleted: Vec<UpsName>,
   updated: Vec<UpsDevice>,
+  num_logins: Vec<(UpsName, Option<usize>)>,
   prot_ver: ProtVer,
   upsd_ver: DaemonVer,
 }
 
 impl DeviceSyncService {
-  pub fn new(event_channel: EventChannel, state: Arc<UpsdState>) -> Self {
+  pub fn new(
+    event_channel: EventChannel,
+    state: Arc<UpsdState>,
+    connection_pool: NutPoolClient,
+    client_resolver: Arc<ClientResolver>,
+  ) -> Self {
     Self {
       event_channel,
       state,
+      connection_pool,
+      client_resolver,
     }
   }
 }
@@ -56,41 +77,62 @@ impl BackgroundService for DeviceSyncService {
   ) -> core::pin::Pin<Box<dyn core::future::Future<Output = ()> + Send>> {
     let event_channel = self.event_channel.clone();
     let state = self.state.clone();
+    let connection_pool = self.connection_pool.clone();
+    let client_resolver = self.client_resolver.clone();
 
     Box::pin(async move {
       let namespace = state.namespace.clone();
-      let mut interval = interval(Duration::from_secs(state.config.poll_freq));
-      interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+      let max_backoff = Duration::from_secs(state.config.reconnect_max_backoff);
 
       let task = DeviceSyncTask {
         state,
         event_channel,
+        connection_pool,
+        client_resolver,
       };
 
       'MAIN: loop {
-        select! {
-          _ = interval.tick() => { },
+        let started = Instant::now();
+
+        let result = select! {
+          v = task.next() => v,
           _ = token.cancelled() =>  { break 'MAIN; }
         };
 
-        select! {
-          v = task.next() => {
-            match v {
-              Ok(_) => {
-                debug!(
-                  message = "ups device sync completed",
-                  namespace = %namespace
-                );
-              },
-              Err(err) => {
-                error!(
-                  message = "ups device sync failed",
-                  namespace = %namespace,
-                  reason = %err
-                );
-              }
-            }
+        let sync_period = task.sync_period();
+        let delay = match result {
+          Ok(_) => {
+            debug!(
+              message = "ups device sync completed",
+              namespace = %namespace
+            );
+
+            task.state.daemon_state.write().await.record_success();
+            sync_period
           }
+          Err(err) => {
+            let mut write_lock = task.state.daemon_state.write().await;
+            let failures = write_lock.record_failure(&err);
+            let delay = Backoff::new(sync_period, max_backoff).delay(failures, jitter());
+
+            write_lock.next_retry = chrono::Duration::from_std(delay)
+              .ok()
+              .map(|delay| Utc::now() + delay);
+
+            error!(
+              message = "ups device sync failed",
+              namespace = %namespace,
+              reason = %err,
+              consecutive_failures = failures,
+              retry_in_secs = delay.as_secs()
+            );
+
+            delay
+          }
+        };
+
+        select! {
+          _ = sleep_until(started + delay) => { },
           _ = token.cancelled() =>  { break 'MAIN; }
         };
       }
@@ -104,6 +146,12 @@ impl BackgroundService for DeviceSyncService {
 }
 
 impl DeviceSyncTask {
+  /// Device list is synced with the namespace `poll_freq`. Poll rules and device overrides only
+  /// apply to the status sync.
+  fn sync_period(&self) -> Duration {
+    Duration::from_secs(self.state.config.poll_freq.max(1))
+  }
+
   pub async fn next(&self) -> Result<(), SyncTaskError> {
     let mut events = EventBatch::new(self.state.namespace.clone());
 
@@ -135,6 +183,12 @@ impl DeviceSyncTask {
           }
         }
 
+        for (device_name, num_logins) in patch.num_logins.into_iter() {
+          if let Some(device) = write_lock.devices.get_mut(&device_name) {
+            device.num_logins = num_logins;
+          }
+        }
+
         for device_name in patch.deleted.into_iter() {
           info!(
             message = "device disconnected",
@@ -157,6 +211,7 @@ impl DeviceSyncTask {
         }
 
         write_lock.last_device_sync = Some(Utc::now());
+        write_lock.update_connection(&self.connection_pool);
         write_lock.prot_ver = Some(patch.prot_ver.value.into_boxed_str());
         write_lock.ver = Some(patch.upsd_ver.value.into_boxed_str());
 
@@ -198,6 +253,7 @@ impl DeviceSyncTask {
 
         write_lock.devices.clear();
         write_lock.last_device_sync = Some(Utc::now());
+        write_lock.update_connection(&self.connection_pool);
 
         _ = self
           .event_channel
@@ -217,10 +273,14 @@ impl DeviceSyncTask {
 
   /// Diffs remote UPSD's state against local in-memory state, and creates a diff patch.
   async fn diff_upsd(&self) -> Result<DeviceDiffPatch, SyncTaskError> {
-    let client = &self.state.connection_pool;
-    let (remote, prot_ver, upsd_ver) =
+    let client = &self.connection_pool;
+    let (mut remote, prot_ver, upsd_ver) =
       try_join!(client.list_ups(), client.get_protver(), client.get_ver())?;
 
+    remote
+      .devices
+      .retain(|device| self.state.config.is_monitored(&device.ups_name));
+
     let total_device_count = remote.devices.len();
     let mut new_devices = Vec::new();
     let mut recheck_devices = Vec::new();
@@ -230,7 +290,9 @@ impl DeviceSyncTask {
       added: Vec::new(),
       updated: Vec::new(),
       deleted: Vec::new(),
+      num_logins: Vec::new(),
     };
+    let mut known_devices = Vec::new();
 
     {
       let state_lock = self.state.daemon_state.read().await;
@@ -251,8 +313,12 @@ impl DeviceSyncTask {
           Some(local_device) => {
             if local_device.status.has(UpsStatus::NOCOMM) {
               recheck_devices.push(remote_device);
-            } else if local_device.desc != remote_device.desc {
-              patch.updated.push(remote_device);
+            } else {
+              known_devices.push(remote_device.ups_name.clone());
+
+              if local_device.desc != remote_device.desc {
+                patch.updated.push(remote_device);
+              }
             }
           }
           None => {
@@ -262,14 +328,16 @@ impl DeviceSyncTask {
       }
     };
 
+    patch.num_logins = self.load_num_logins(&known_devices).await;
+
     let mut failure_count = 0;
     let mut new_devices_task = JoinSet::from_iter(new_devices.into_iter().map(|dev| {
-      let client = self.state.connection_pool.clone();
-      Self::load_device_entry(client, dev)
+      let client = self.connection_pool.clone();
+      Self::load_device_entry(client, self.client_resolver.clone(), dev)
     }));
     let mut recheck_task = JoinSet::from_iter(recheck_devices.into_iter().map(|dev| {
-      let client = self.state.connection_pool.clone();
-      Self::load_device_entry(client, dev)
+      let client = self.connection_pool.clone();
+      Self::load_device_entry(client, self.client_resolver.clone(), dev)
     }));
 
     while let Some(result) = new_devices_task.join_next().await {
@@ -289,8 +357,10 @@ impl DeviceSyncTask {
           let entry = DeviceEntry {
             status: UpsStatus::NOCOMM,
             name: err.name,
+            num_logins: None,
             desc: String::new().into_boxed_str(),
             last_modified: Utc::now(),
+            last_updated: DeviceFreshness::new(Utc::now()),
             attached: Vec::new(),
             commands: Vec::new(),
             rw_variables: HashMap::new(),
@@ -340,16 +410,53 @@ impl DeviceSyncTask {
     }
   }
 
+  /// `GET NUMLOGINS` is only refreshed with the device list, since it changes rarely and it's not
+  /// worth a request on every status sync.
+  async fn load_num_logins(&self, devices: &[UpsName]) -> Vec<(UpsName, Option<usize>)> {
+    if devices.is_empty() {
+      return Vec::new();
+    }
+
+    let mut batch = Batch::with_capacity(devices.len());
+    let handles: Vec<_> = devices
+      .iter()
+      .map(|device| (device, batch.get_num_logins(device)))
+      .collect();
+
+    let mut results = self.connection_pool.send_batch(&batch).await;
+
+    handles
+      .into_iter()
+      .map(|(device, handle)| {
+        let num_logins = take_batch_result(&mut results, handle)
+          .map(|v| v.value)
+          .inspect_err(|err| {
+            debug!(
+              message = "unable to get number of logins from upsd",
+              namespace = %self.state.namespace,
+              device = %device,
+              reason = %err
+            )
+          })
+          .ok();
+
+        (device.clone(), num_logins)
+      })
+      .collect()
+  }
+
   async fn load_device_entry(
     client: NutPoolClient,
+    client_resolver: Arc<ClientResolver>,
     device: UpsDevice,
   ) -> Result<DeviceEntry, DeviceLoadError> {
     let UpsDevice { ups_name, desc } = device;
-    let (vars, commands, clients, rw_vars) = join!(
+    let (vars, commands, clients, rw_vars, num_logins) = join!(
       client.list_var(&ups_name),
       client.list_cmd(&ups_name),
-      Self::load_clients(client.clone(), &ups_name),
-      Self::load_rw_vars(client.clone(), &ups_name)
+      Self::load_clients(client.clone(), &client_resolver, &ups_name),
+      Self::load_rw_vars(client.clone(), &ups_name),
+      client.get_num_logins(&ups_name)
     );
 
     let vars = vars.map_err(|err| DeviceLoadError {
@@ -392,12 +499,25 @@ impl DeviceSyncTask {
       })
       .unwrap_or_default();
 
+    let num_logins = num_logins
+      .inspect_err(|err| {
+        debug!(
+          message = "unable to get number of logins from upsd",
+          reason = %err,
+          device = %&ups_name
+        )
+      })
+      .map(|v| v.value)
+      .ok();
+
     Ok(DeviceEntry {
       attached,
       commands,
       desc,
       last_modified: Utc::now(),
+      last_updated: DeviceFreshness::new(Utc::now()),
       name: ups_name,
+      num_logins,
       rw_variables,
       status,
       variables: vars.variables,
@@ -406,20 +526,22 @@ impl DeviceSyncTask {
 
   async fn load_clients(
     client: NutPoolClient,
+    client_resolver: &ClientResolver,
     ups_name: &UpsName,
   ) -> Result<Vec<ClientInfo>, nut_webgui_upsmc::error::Error> {
     let client_list = client.list_client(ups_name).await?;
-    let mut clients: Vec<ClientInfo> = Vec::with_capacity(client_list.ips.len());
-
-    for ip in client_list.ips.into_iter() {
-      let name = if !ip.is_loopback() {
-        lookup_ip(ip).map_or(None, |n| Some(n))
-      } else {
-        None
-      };
-
-      clients.push(ClientInfo { addr: ip, name })
-    }
+    let names = client_resolver
+      .resolve_all(client_list.ips.iter().copied())
+      .await;
+
+    let clients = client_list
+      .ips
+      .into_iter()
+      .map(|addr| ClientInfo {
+        addr,
+        name: names.get(&addr).cloned().flatten(),
+      })
+      .collect();
 
     Ok(clients)
   }
@@ -486,26 +608,33 @@ impl DeviceSyncTask {
           ));
         }
         VarType::Range => {
-          let mut range_list = client.list_range(ups_name, &var_name).await?;
-
-          return match range_list.ranges.pop() {
-            Some((min, max)) => Ok((range_list.name, VarDetail::Range { min, max })),
-            None => {
-              warn!(
-                message = "nut driver reports variable type as range, but it does not provide any range information",
-                var_name = %var_name,
-                device = %ups_name
-              );
-
-              Ok((
-                range_list.name,
-                VarDetail::Range {
+          let range_list = client.list_range(ups_name, &var_name).await?;
+
+          if range_list.ranges.is_empty() {
+            warn!(
+              message = "nut driver reports variable type as range, but it does not provide any range information",
+              var_name = %var_name,
+              device = %ups_name
+            );
+
+            return Ok((
+              range_list.name,
+              VarDetail::Range {
+                ranges: vec![VarRange {
                   min: Value::from(i64::MIN),
                   max: Value::from(i64::MAX),
-                },
-              ))
-            }
-          };
+                }],
+              },
+            ));
+          }
+
+          let ranges = range_list
+            .ranges
+            .into_iter()
+            .map(|(min, max)| VarRange { min, max })
+            .collect();
+
+          return Ok((range_list.name, VarDetail::Range { ranges }));
         }
         VarType::String { max_len } => {
           return Ok((var_name, VarDetail::String { max_len }));
@@ -0,0 +1,467 @@
+use crate::{
+  background_service::BackgroundService,
+  event::{batch::EventBatch, channel::EventChannel},
+  state::{ConnectionStatus, DeviceEntry, DeviceFreshness, UpsdState, VarDetail, VarRange},
+};
+use chrono::Utc;
+use futures::future::join_all;
+use nut_webgui_upsmc::{
+  UpsName, VarName, VarType,
+  driver::{DriverClient, DriverMessage, DriverState},
+  error::{Error, ErrorKind},
+  ups_status::UpsStatus,
+  ups_variables::UpsVariables,
+};
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
+use tokio::{
+  select,
+  time::{Instant, MissedTickBehavior, interval, interval_at, sleep},
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+/// Reads UPS devices directly from NUT driver state sockets, without upsd.
+pub struct DriverSyncService {
+  event_channel: EventChannel,
+  state: Arc<UpsdState>,
+}
+
+struct DriverSyncTask {
+  state: Arc<UpsdState>,
+  event_channel: EventChannel,
+  ups_name: UpsName,
+  socket_path: PathBuf,
+}
+
+/// Part of the device entry affected by a driver message.
+enum DriverChange {
+  Variable(VarName),
+  VarDetail(VarName),
+  Commands,
+  DataState,
+  KeepAlive,
+}
+
+impl DriverSyncService {
+  pub fn new(event_channel: EventChannel, state: Arc<UpsdState>) -> Self {
+    Self {
+      event_channel,
+      state,
+    }
+  }
+}
+
+impl BackgroundService for DriverSyncService {
+  fn run(
+    &self,
+    token: CancellationToken,
+  ) -> core::pin::Pin<Box<dyn core::future::Future<Output = ()> + Send>> {
+    let event_channel = self.event_channel.clone();
+    let state = self.state.clone();
+
+    Box::pin(async move {
+      let namespace = state.namespace.clone();
+      let tasks = state.config.drivers.iter().map(|(ups_name, socket_path)| {
+        let task = DriverSyncTask {
+          state: state.clone(),
+          event_channel: event_channel.clone(),
+          ups_name: ups_name.clone(),
+          socket_path: socket_path.clone(),
+        };
+
+        task.run(token.clone())
+      });
+
+      join_all(tasks).await;
+
+      debug!(
+        message = "driver sync task stopped",
+        namespace = %namespace
+      );
+    })
+  }
+}
+
+impl DriverSyncTask {
+  async fn run(self, token: CancellationToken) {
+    let retry_delay = Duration::from_secs(self.state.config.poll_freq);
+
+    'MAIN: loop {
+      let result = select! {
+        result = self.stream() => result,
+        _ = token.cancelled() => { break 'MAIN; }
+      };
+
+      if let Err(err) = result {
+        error!(
+          message = "driver socket connection failed",
+          namespace = %self.state.namespace,
+          device = %self.ups_name,
+          socket = %self.socket_path.display(),
+          reason = %err
+        );
+      }
+
+      self.mark_as_dead().await;
+
+      select! {
+        _ = sleep(retry_delay) => {}
+        _ = token.cancelled() => { break 'MAIN; }
+      };
+    }
+  }
+
+  /// Loads the full driver state with `DUMPALL`, then keeps the device entry updated from
+  /// streamed driver messages. Only returns when the connection fails.
+  async fn stream(&self) -> Result<(), Error> {
+    let poll_freq = Duration::from_secs(self.state.config.poll_freq);
+    let poll_interval = Duration::from_secs(self.state.config.poll_interval.max(1));
+
+    let mut client = DriverClient::connect(&self.socket_path).await?;
+    client.set_timeout(poll_freq);
+
+    let mut driver_state = client.dump_all().await?;
+    self.load_device(&driver_state).await;
+
+    let mut flush = interval(poll_interval);
+    flush.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    let mut keepalive = interval_at(Instant::now() + poll_freq, poll_freq);
+    keepalive.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    let mut awaiting_pong = false;
+    let mut updated = false;
+
+    loop {
+      select! {
+        message = client.next_message() => {
+          awaiting_pong = false;
+          updated |= self.apply(&mut driver_state, message?).await;
+        }
+        _ = flush.tick() => {
+          if updated {
+            updated = false;
+
+            let mut events = EventBatch::new(self.state.namespace.clone());
+            events.updated_device(self.ups_name.clone());
+            self.send_events(events);
+          }
+        }
+        _ = keepalive.tick() => {
+          if awaiting_pong {
+            return Err(ErrorKind::RequestTimeout.into());
+          }
+
+          client.ping().await?;
+          awaiting_pong = true;
+        }
+      };
+    }
+  }
+
+  async fn load_device(&self, driver_state: &DriverState) {
+    let mut events = EventBatch::new(self.state.namespace.clone());
+    let entry = device_entry(self.ups_name.clone(), driver_state);
+
+    {
+      let mut write_lock = self.state.daemon_state.write().await;
+      let new_status = entry.status;
+
+      match write_lock.devices.insert(self.ups_name.clone(), entry) {
+        Some(previous) => {
+          if previous.status != new_status {
+            events.status_change(self.ups_name.clone(), previous.status, new_status);
+          }
+
+          events.updated_device(self.ups_name.clone());
+        }
+        None => {
+          info!(
+            message = "device connected",
+            namespace = %self.state.namespace,
+            device = %self.ups_name
+          );
+
+          events.new_device(self.ups_name.clone());
+        }
+      }
+
+      if write_lock.status != ConnectionStatus::Online {
+        info!(
+          message = "driver socket is online",
+          namespace = %self.state.namespace,
+          device = %self.ups_name
+        );
+
+        write_lock.status = ConnectionStatus::Online;
+        events.set_upsd_status(ConnectionStatus::Online);
+      }
+
+      write_lock.last_device_sync = Some(Utc::now());
+    }
+
+    self.send_events(events);
+  }
+
+  /// Marks the device as `NOCOMM`. Namespace is reported as dead when all of its devices are
+  /// unreachable.
+  async fn mark_as_dead(&self) {
+    let mut events = EventBatch::new(self.state.namespace.clone());
+
+    {
+      let mut write_lock = self.state.daemon_state.write().await;
+
+      match write_lock.devices.get_mut(&self.ups_name) {
+        Some(entry) => {
+          if entry.status != UpsStatus::NOCOMM {
+            events.status_change(self.ups_name.clone(), entry.status, UpsStatus::NOCOMM);
+          }
+
+          entry.mark_as_dead_with(UpsStatus::NOCOMM);
+        }
+        None => {
+          // NOTE: Driver is not reachable since the startup. Device is still reported as NOCOMM,
+          // similar to devices listed by upsd without any details.
+          let entry = DeviceEntry {
+            attached: Vec::new(),
+            commands: Vec::new(),
+            desc: Box::from(""),
+            last_modified: Utc::now(),
+            last_updated: DeviceFreshness::new(Utc::now()),
+            name: self.ups_name.clone(),
+            num_logins: None,
+            rw_variables: HashMap::new(),
+            status: UpsStatus::NOCOMM,
+            variables: UpsVariables::new(),
+          };
+
+          _ = write_lock.devices.insert(self.ups_name.clone(), entry);
+          events.new_device(self.ups_name.clone());
+        }
+      }
+
+      let all_dead = write_lock
+        .devices
+        .values()
+        .all(|device| device.status.has(UpsStatus::NOCOMM));
+
+      if all_dead && write_lock.status != ConnectionStatus::Dead {
+        error!(
+          message = "all driver sockets are disconnected",
+          namespace = %self.state.namespace
+        );
+
+        write_lock.status = ConnectionStatus::Dead;
+        events.set_upsd_status(ConnectionStatus::Dead);
+      }
+
+      write_lock.last_device_sync = Some(Utc::now());
+    }
+
+    self.send_events(events);
+  }
+
+  /// Applies a driver message to both driver and device states. Returns `true` when the device
+  /// entry is changed.
+  async fn apply(&self, driver_state: &mut DriverState, message: DriverMessage) -> bool {
+    let change = match &message {
+      DriverMessage::SetInfo { name, .. } | DriverMessage::DelInfo { name } => {
+        DriverChange::Variable(name.clone())
+      }
+      DriverMessage::AddEnum { name, .. }
+      | DriverMessage::DelEnum { name, .. }
+      | DriverMessage::AddRange { name, .. }
+      | DriverMessage::DelRange { name, .. }
+      | DriverMessage::SetAux { name, .. }
+      | DriverMessage::SetFlags { name, .. } => DriverChange::VarDetail(name.clone()),
+      DriverMessage::AddCmd { .. } | DriverMessage::DelCmd { .. } => DriverChange::Commands,
+      DriverMessage::DataOk | DriverMessage::DataStale => DriverChange::DataState,
+      DriverMessage::Pong => DriverChange::KeepAlive,
+      DriverMessage::DumpDone | DriverMessage::Ping | DriverMessage::Tracking { .. } => {
+        return false;
+      }
+    };
+
+    driver_state.apply(message);
+
+    let mut events = EventBatch::new(self.state.namespace.clone());
+
+    {
+      let mut write_lock = self.state.daemon_state.write().await;
+      let entry = match write_lock.devices.get_mut(&self.ups_name) {
+        Some(entry) => entry,
+        None => return false,
+      };
+
+      match change {
+        DriverChange::DataState if driver_state.is_stale() => {
+          if entry.status == UpsStatus::NOCOMM {
+            return false;
+          }
+
+          warn!(
+            message = "driver reports stale data",
+            namespace = %self.state.namespace,
+            device = %self.ups_name
+          );
+
+          events.status_change(self.ups_name.clone(), entry.status, UpsStatus::NOCOMM);
+          entry.mark_as_dead_with(UpsStatus::NOCOMM);
+        }
+        DriverChange::DataState => {
+          let new_entry = device_entry(self.ups_name.clone(), driver_state);
+
+          if entry.status != new_entry.status {
+            events.status_change(self.ups_name.clone(), entry.status, new_entry.status);
+          }
+
+          events.variables_change(
+            self.ups_name.clone(),
+            &entry.variables,
+            &new_entry.variables,
+          );
+
+          *entry = new_entry;
+        }
+        // Updates are only tracked in the driver state until the driver reports `DATAOK`.
+        _ if driver_state.is_stale() => return false,
+        // Driver only sends changes, a responsive driver with `DATAOK` state keeps all values
+        // fresh.
+        DriverChange::KeepAlive => {
+          entry.last_updated = DeviceFreshness::new(Utc::now());
+          return false;
+        }
+        DriverChange::Variable(name) => {
+          let new_value = driver_state.variables().get(&name);
+
+          match new_value {
+            Some(value) => {
+              if name == VarName::UPS_STATUS {
+                let new_status = UpsStatus::from(value);
+
+                if entry.status != new_status {
+                  events.status_change(self.ups_name.clone(), entry.status, new_status);
+                  entry.status = new_status;
+                }
+
+                entry.last_updated.status = Utc::now();
+              }
+
+              let old_value = entry.variables.insert(name.clone(), value.clone());
+              events.variable_change(self.ups_name.clone(), &name, old_value.as_ref(), new_value);
+            }
+            None => {
+              if let Some((_, old_value)) = entry.variables.remove(&name) {
+                events.variable_change(self.ups_name.clone(), &name, Some(&old_value), None);
+              }
+            }
+          }
+
+          update_var_detail(entry, driver_state, name);
+          entry.last_updated.variables = Utc::now();
+        }
+        DriverChange::VarDetail(name) => update_var_detail(entry, driver_state, name),
+        DriverChange::Commands => {
+          entry.commands = driver_state.commands().to_vec();
+          entry.last_updated.commands = Utc::now();
+        }
+      }
+
+      entry.last_modified = Utc::now();
+    }
+
+    self.send_events(events);
+
+    true
+  }
+
+  fn send_events(&self, events: EventBatch) {
+    _ = self.event_channel.send_batch(events).inspect_err(|err| {
+      warn!(
+        message = "cannot write new system events to channel",
+        namespace = %self.state.namespace,
+        reason = %err
+      );
+    });
+  }
+}
+
+fn device_entry(name: UpsName, driver_state: &DriverState) -> DeviceEntry {
+  let variables = driver_state.variables();
+  let status = match variables.get(VarName::UPS_STATUS) {
+    Some(value) => UpsStatus::from(value),
+    None => UpsStatus::default(),
+  };
+
+  let rw_variables = driver_state
+    .rw_variables()
+    .map(|var_name| (var_name.clone(), var_detail(driver_state, var_name)))
+    .collect();
+
+  let mut entry = DeviceEntry {
+    attached: Vec::new(),
+    commands: driver_state.commands().to_vec(),
+    desc: Box::from(""),
+    last_modified: Utc::now(),
+    last_updated: DeviceFreshness::new(Utc::now()),
+    name,
+    num_logins: None,
+    rw_variables,
+    status,
+    variables: variables.clone(),
+  };
+
+  if driver_state.is_stale() {
+    entry.mark_as_dead_with(UpsStatus::NOCOMM);
+  }
+
+  entry
+}
+
+fn update_var_detail(entry: &mut DeviceEntry, driver_state: &DriverState, name: VarName) {
+  let is_rw = driver_state.variables().contains_key(&name)
+    && driver_state
+      .var_info(&name)
+      .is_some_and(|info| info.flags.rw);
+
+  if is_rw {
+    let detail = var_detail(driver_state, &name);
+    _ = entry.rw_variables.insert(name, detail);
+  } else {
+    _ = entry.rw_variables.remove(&name);
+  }
+}
+
+/// Same type precedence as upsd based device loading, see `DeviceSyncTask::load_var_detail`.
+fn var_detail(driver_state: &DriverState, name: &VarName) -> VarDetail {
+  for var_type in driver_state.var_types(name) {
+    match var_type {
+      VarType::ReadWrite => continue,
+      VarType::Enum => {
+        let options = driver_state
+          .var_info(name)
+          .map_or_else(Vec::new, |info| info.enums.clone());
+
+        return VarDetail::Enum { options };
+      }
+      VarType::Range => {
+        let ranges = driver_state.var_info(name).map_or_else(Vec::new, |info| {
+          info
+            .ranges
+            .iter()
+            .map(|(min, max)| VarRange {
+              min: min.clone(),
+              max: max.clone(),
+            })
+            .collect()
+        });
+
+        return VarDetail::Range { ranges };
+      }
+      VarType::String { max_len } => return VarDetail::String { max_len },
+      VarType::Number => return VarDetail::Number,
+    }
+  }
+
+  VarDetail::String { max_len: 64 }
+}
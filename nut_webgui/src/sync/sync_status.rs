@@ -1,16 +1,26 @@
 use crate::{
   background_service::BackgroundService,
-  event::{DeviceStatusChange, SystemEvent, batch::EventBatch, channel::EventChannel},
+  config::poll_schedule::PollSchedule,
+  event::{batch::EventBatch, channel::EventChannel},
   state::{ClientInfo, UpsdState},
-  sync::reverse_dns::lookup_ip,
+  sync::client_resolver::ClientResolver,
 };
 use chrono::Utc;
-use futures::{future::join_all, join};
-use nut_webgui_upsmc::{UpsName, VarName, client::AsyncNutClient, ups_status::UpsStatus};
-use std::{net::IpAddr, sync::Arc, time::Duration};
+use nut_webgui_upsmc::{
+  UpsName, VarName,
+  client::{Batch, BatchHandle, BatchItem, BatchResults, NutPoolClient},
+  error::Error,
+  ups_status::UpsStatus,
+};
+use std::{
+  collections::{HashMap, HashSet},
+  net::IpAddr,
+  sync::Arc,
+  time::Duration,
+};
 use tokio::{
   select,
-  time::{Instant, Interval, MissedTickBehavior, interval},
+  time::{Instant, sleep_until},
 };
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
@@ -18,23 +28,36 @@ use tracing::{debug, error, info, warn};
 pub struct StatusSyncService {
   event_channel: EventChannel,
   state: Arc<UpsdState>,
+  connection_pool: NutPoolClient,
+  client_resolver: Arc<ClientResolver>,
+}
+
+/// Per-device poll schedule. Intervals are resolved from the device's current status with
+/// [crate::config::UpsdConfig::poll_schedule].
+struct UpsPollScheduler {
+  state: Arc<UpsdState>,
+  devices: HashMap<UpsName, DevicePollState>,
 }
 
-struct UpsPollInterval {
-  interval: Interval,
+struct DevicePollState {
+  status: UpsStatus,
+  status_since: Instant,
   last_full_sync: Option<Instant>,
-  full_sync_period: Duration,
+  last_partial_sync: Option<Instant>,
 }
 
-#[derive(Debug, Clone, Copy)]
-enum UpsPollType {
-  Full,
-  Partial,
+/// Devices due for a full and a partial (`ups.status` only) sync.
+#[derive(Default)]
+struct UpsPollBatch {
+  full: Vec<UpsName>,
+  partial: Vec<UpsName>,
 }
 
 struct StatusSyncTask {
   state: Arc<UpsdState>,
   event_channel: EventChannel,
+  connection_pool: NutPoolClient,
+  client_resolver: Arc<ClientResolver>,
 }
 
 struct ClientDiff {
@@ -43,10 +66,17 @@ struct ClientDiff {
 }
 
 impl StatusSyncService {
-  pub fn new(event_channel: EventChannel, state: Arc<UpsdState>) -> Self {
+  pub fn new(
+    event_channel: EventChannel,
+    state: Arc<UpsdState>,
+    connection_pool: NutPoolClient,
+    client_resolver: Arc<ClientResolver>,
+  ) -> Self {
     Self {
       state,
       event_channel,
+      connection_pool,
+      client_resolver,
     }
   }
 }
@@ -58,54 +88,50 @@ impl BackgroundService for StatusSyncService {
   ) -> std::pin::Pin<Box<dyn core::future::Future<Output = ()> + Send>> {
     let event_channel = self.event_channel.clone();
     let state = self.state.clone();
+    let connection_pool = self.connection_pool.clone();
+    let client_resolver = self.client_resolver.clone();
 
     Box::pin(async move {
       let namespace = state.namespace.clone();
-      let poll_freq = Duration::from_secs(state.config.poll_freq);
-      let poll_interval = {
-        if state.config.poll_interval >= state.config.poll_freq {
-          Duration::from_secs(state.config.poll_freq)
-        } else {
-          Duration::from_secs(state.config.poll_interval)
-        }
-      };
+      let mut scheduler = UpsPollScheduler::new(state.clone());
 
       let task = StatusSyncTask {
         event_channel,
         state,
+        connection_pool,
+        client_resolver,
       };
 
-      let mut interval = UpsPollInterval::new(poll_interval, poll_freq);
-
       'MAIN: loop {
-        let poll_type = select! {
-          poll_type = interval.tick() => poll_type,
+        let batch = select! {
+          batch = scheduler.next(&task) => batch,
           _ = token.cancelled() => { break 'MAIN; }
         };
 
-        match poll_type {
-          UpsPollType::Full => {
-            select! {
-              _ = task.state_sync() => {
-                debug!(
-                  message = "full device status sync completed",
-                  namespace = %namespace
-                );
-              }
-              _ = token.cancelled() => { break 'MAIN; }
-            };
-          }
-          UpsPollType::Partial => {
-            select! {
-              _ = task.status_sync() => {
-                debug!(
-                  message = "partial device status sync completed",
-                  namespace = %namespace
-                );
-              }
-              _ = token.cancelled() => { break 'MAIN; }
-            };
-          }
+        if !batch.full.is_empty() {
+          select! {
+            _ = task.state_sync(&batch.full) => {
+              debug!(
+                message = "full device status sync completed",
+                namespace = %namespace,
+                device_count = batch.full.len()
+              );
+            }
+            _ = token.cancelled() => { break 'MAIN; }
+          };
+        }
+
+        if !batch.partial.is_empty() {
+          select! {
+            _ = task.status_sync(&batch.partial) => {
+              debug!(
+                message = "partial device status sync completed",
+                namespace = %namespace,
+                device_count = batch.partial.len()
+              );
+            }
+            _ = token.cancelled() => { break 'MAIN; }
+          };
         }
       }
 
@@ -118,7 +144,7 @@ impl BackgroundService for StatusSyncService {
 }
 
 impl StatusSyncTask {
-  async fn snapshot_active_device_names(&self) -> Vec<UpsName> {
+  async fn snapshot_active_devices(&self) -> Vec<(UpsName, UpsStatus)> {
     let read_lock = self.state.daemon_state.read().await;
     read_lock
       .devices
@@ -127,38 +153,43 @@ impl StatusSyncTask {
         if v.status.has(UpsStatus::NOCOMM) {
           None
         } else {
-          Some(k.clone())
+          Some((k.clone(), v.status))
         }
       })
       .collect()
   }
 
-  /// Only syncs `ups.status` variables for existing devices.
-  pub async fn status_sync(&self) {
-    let devices = self.snapshot_active_device_names().await;
-
-    if devices.is_empty() {
-      debug!(
-        message = "no device available, nothing to sync",
-        namespace = %self.state.namespace
-      );
-      return;
-    }
+  /// Only syncs `ups.status` variables for the given devices.
+  pub async fn status_sync(&self, devices: &[UpsName]) {
+    let status_var = VarName::UPS_STATUS;
+    let mut batch = Batch::with_capacity(devices.len());
+    let handles: Vec<_> = devices
+      .iter()
+      .map(|device| (device, batch.get_var(device, &status_var)))
+      .collect();
+
+    let mut results = match self.connection_pool.send_batch(&batch).await {
+      Ok(results) => results,
+      Err(err) => {
+        debug!(
+          message = "failed to read ups status",
+          namespace = %self.state.namespace,
+          reason = %err
+        );
+        return;
+      }
+    };
 
-    let responses = join_all(devices.iter().map(|device| async move {
-      self
-        .state
-        .connection_pool
-        .get_var(device, VarName::UPS_STATUS)
-        .await
-        .map_err(|err| (device, err))
-    }))
-    .await;
+    let responses: Vec<_> = handles
+      .into_iter()
+      .map(|(device, handle)| results.take(handle).map_err(|err| (device, err)))
+      .collect();
 
-    let mut changes: Vec<DeviceStatusChange> = Vec::with_capacity(responses.len());
+    let mut events = EventBatch::new(self.state.namespace.clone());
 
     {
       let mut write_lock = self.state.daemon_state.write().await;
+      write_lock.update_connection(&self.connection_pool);
 
       for result in responses {
         match result {
@@ -167,17 +198,24 @@ impl StatusSyncTask {
               let old_status = entry.status;
               let new_status = UpsStatus::from(&variable.value);
 
+              let old_value = entry
+                .variables
+                .insert(variable.name.clone(), variable.value.clone());
+
               entry.status = new_status;
-              entry.variables.insert(variable.name, variable.value);
               entry.last_modified = Utc::now();
+              entry.last_updated.status = entry.last_modified;
 
               if old_status != new_status {
-                changes.push(DeviceStatusChange {
-                  status_new: new_status,
-                  status_old: old_status,
-                  name: variable.ups_name,
-                });
+                events.status_change(variable.ups_name.clone(), old_status, new_status);
               }
+
+              events.variable_change(
+                variable.ups_name,
+                &variable.name,
+                old_value.as_ref(),
+                Some(&variable.value),
+              );
             }
           }
           Err((device, err)) => {
@@ -191,50 +229,57 @@ impl StatusSyncTask {
       }
     };
 
-    if !changes.is_empty() {
-      _ = self
-        .event_channel
-        .send(SystemEvent::DeviceStatusChange {
-          changes,
-          namespace: self.state.namespace.clone(),
-        })
-        .inspect_err(|err| {
-          warn!(
-            message = "cannot write new system events to channel",
-            namespace = %self.state.namespace,
-            reason = %err
-          );
-        });
-    }
-  }
-
-  pub async fn state_sync(&self) {
-    let devices = self.snapshot_active_device_names().await;
-
-    if devices.is_empty() {
-      debug!(
-        message = "no device available, nothing to sync",
-        namespace = %self.state.namespace
+    _ = self.event_channel.send_batch(events).inspect_err(|err| {
+      warn!(
+        message = "cannot write new system events to channel",
+        namespace = %self.state.namespace,
+        reason = %err
       );
+    });
+  }
 
-      return;
-    }
+  pub async fn state_sync(&self, devices: &[UpsName]) {
+    let mut batch = Batch::with_capacity(devices.len() * 3);
+    let handles: Vec<_> = devices
+      .iter()
+      .map(|device| {
+        (
+          device,
+          batch.list_var(device),
+          batch.list_client(device),
+          batch.list_cmd(device),
+        )
+      })
+      .collect();
+
+    let mut results = self.connection_pool.send_batch(&batch).await;
+    let responses: Vec<_> = handles
+      .into_iter()
+      .map(|(name, var_list, clients, commands)| {
+        (
+          name,
+          take_batch_result(&mut results, var_list),
+          take_batch_result(&mut results, clients),
+          take_batch_result(&mut results, commands),
+        )
+      })
+      .collect();
 
-    let client = &self.state.connection_pool;
-    let responses = join_all(devices.iter().map(|device| async move {
-      join!(
-        std::future::ready(device), // Passes device name to the join result for traceback
-        client.list_var(device),
-        client.list_client(device),
-        client.list_cmd(device),
-      )
-    }))
-    .await;
+    // NOTE: Client names are resolved before taking the write lock, so slow reverse DNS lookups do
+    // not block readers. Already attached clients are included as well, cached names are returned
+    // immediately and expired ones are refreshed.
+    let client_addrs: HashSet<IpAddr> = responses
+      .iter()
+      .filter_map(|(_, _, clients, _)| clients.as_ref().ok())
+      .flat_map(|clients| clients.ips.iter().copied())
+      .collect();
 
+    let client_names = self.client_resolver.resolve_all(client_addrs).await;
     let mut events = EventBatch::new(self.state.namespace.clone());
 
     {
       let mut write_lock = self.state.daemon_state.write().await;
+      let now = Utc::now();
 
       for (name, var_list, clients, commands) in responses {
         if let Some(entry) = write_lock.devices.get_mut(name) {
@@ -250,7 +295,10 @@ impl StatusSyncTask {
                 }
               }
 
+              events.variables_change(name.clone(), &entry.variables, &v.variables);
               entry.variables = v.variables;
+              entry.last_updated.status = now;
+              entry.last_updated.variables = now;
             }
             Err(err) => {
               error!(
@@ -266,19 +314,34 @@ impl StatusSyncTask {
             }
           };
 
-          let attached = clients
-            .inspect_err(|err| {
+          // NOTE: Failed groups keep their previous values, and their freshness timestamps are
+          // not updated.
+          let attached = match clients {
+            Ok(clients) => {
+              entry.last_updated.clients = now;
+              clients.ips
+            }
+            Err(err) => {
               warn!(
                 message = "unable to get attached clients list from upsd",
                 namespace = %self.state.namespace,
                 device = %name,
                 reason = %err,
-              )
-            })
-            .map_or_else(|_| Vec::new(), |v| v.ips);
+              );
+
+              entry.attached.iter().map(|v| v.addr).collect()
+            }
+          };
 
           let client_diff = ClientDiff::diff(&entry.attached, &attached);
 
+          // NOTE: Timed out lookups are missing from the resolved names, previous names are kept.
+          for client in entry.attached.iter_mut() {
+            if let Some(name) = client_names.get(&client.addr) {
+              client.name.clone_from(name);
+            }
+          }
+
           if !client_diff.disconnected.is_empty() {
             for client_ip in client_diff.disconnected.iter() {
               if let Some(idx) = entry.attached.iter().position(|c| c.addr == *client_ip) {
@@ -298,15 +361,9 @@ impl StatusSyncTask {
 
           if !client_diff.connected.is_empty() {
             for client_ip in client_diff.connected.iter() {
-              let client_name = if !client_ip.is_loopback() {
-                lookup_ip(*client_ip).map_or(None, |v| Some(v))
-              } else {
-                None
-              };
-
               entry.attached.push(ClientInfo {
                 addr: *client_ip,
-                name: client_name,
+                name: client_names.get(client_ip).cloned().flatten(),
               });
 
               info!(
@@ -320,19 +377,22 @@ impl StatusSyncTask {
             events.client_connection(name.clone(), client_diff.connected);
           }
 
-          let commands = commands
-            .inspect_err(|err| {
+          match commands {
+            Ok(commands) => {
+              entry.commands = commands.cmds;
+              entry.last_updated.commands = now;
+            }
+            Err(err) => {
               warn!(
                 message = "unable to get supported commands list from upsd",
                 namespace = %self.state.namespace,
                 device = %&name,
                 reason = %err
-              )
-            })
-            .map_or_else(|_| Vec::new(), |v| v.cmds);
+              );
+            }
+          }
 
-          entry.commands = commands;
-          entry.last_modified = Utc::now();
+          entry.last_modified = now;
           events.updated_device(name.clone());
         }
       }
@@ -348,47 +408,124 @@ impl StatusSyncTask {
   }
 }
 
-impl std::fmt::Display for UpsPollType {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    match self {
-      UpsPollType::Full => f.write_str("Full"),
-      UpsPollType::Partial => f.write_str("Partial"),
-    }
+/// Takes a reply from batch results. A failed batch is reported for every handle, so devices are
+/// handled the same way as individually failed requests.
+pub(super) fn take_batch_result<T>(
+  results: &mut Result<BatchResults, Error>,
+  handle: BatchHandle<T>,
+) -> Result<T, Error>
+where
+  T: BatchItem,
+{
+  match results {
+    Ok(results) => results.take(handle),
+    Err(err) => Err(Error::from(err.kind().clone())),
   }
 }
 
-impl UpsPollInterval {
-  pub fn new(poll_interval: Duration, poll_freq: Duration) -> Self {
-    let mut interval = interval(poll_interval);
-    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
-
+impl UpsPollScheduler {
+  pub fn new(state: Arc<UpsdState>) -> Self {
     Self {
-      interval,
-      last_full_sync: None,
-      full_sync_period: poll_freq,
+      state,
+      devices: HashMap::new(),
     }
   }
 
-  pub async fn tick(&mut self) -> UpsPollType {
-    let instant = self.interval.tick().await;
+  /// Waits until at least one device is due for a sync.
+  pub async fn next(&mut self, task: &StatusSyncTask) -> UpsPollBatch {
+    loop {
+      let now = Instant::now();
+      self.refresh(task.snapshot_active_devices().await, now);
 
-    match self.last_full_sync {
-      Some(last_sync) => {
-        if instant.duration_since(last_sync) >= self.full_sync_period {
-          self.last_full_sync = Some(instant);
-          UpsPollType::Full
-        } else {
-          UpsPollType::Partial
-        }
+      let (batch, deadline) = self.poll(now);
+
+      if !batch.full.is_empty() || !batch.partial.is_empty() {
+        return batch;
+      }
+
+      // NOTE: Devices can be added or revived by the device sync service at any time, so the
+      // device list is re-checked at least once per namespace poll interval.
+      let idle_deadline = now + Duration::from_secs(self.state.config.poll_interval.max(1));
+      sleep_until(deadline.map_or(idle_deadline, |v| v.min(idle_deadline))).await;
+    }
+  }
+
+  /// Collects devices due for a sync at `now`, and returns the earliest upcoming sync deadline.
+  fn poll(&mut self, now: Instant) -> (UpsPollBatch, Option<Instant>) {
+    let mut batch = UpsPollBatch::default();
+    let mut deadline: Option<Instant> = None;
+
+    for (name, device) in self.devices.iter_mut() {
+      let schedule = self.state.config.poll_schedule(
+        name,
+        device.status,
+        now.duration_since(device.status_since),
+      );
+
+      if device.next_full_sync(schedule).is_none_or(|v| v <= now) {
+        device.last_full_sync = Some(now);
+        batch.full.push(name.clone());
+      } else if device.next_partial_sync(schedule).is_none_or(|v| v <= now) {
+        device.last_partial_sync = Some(now);
+        batch.partial.push(name.clone());
       }
-      None => {
-        self.last_full_sync = Some(instant);
-        UpsPollType::Full
+
+      let device_deadline = device
+        .next_full_sync(schedule)
+        .into_iter()
+        .chain(device.next_partial_sync(schedule))
+        .min();
+
+      deadline = deadline.into_iter().chain(device_deadline).min();
+    }
+
+    (batch, deadline)
+  }
+
+  fn refresh(&mut self, active_devices: Vec<(UpsName, UpsStatus)>, now: Instant) {
+    self
+      .devices
+      .retain(|name, _| active_devices.iter().any(|(active, _)| active == name));
+
+    for (name, status) in active_devices {
+      match self.devices.get_mut(&name) {
+        Some(device) => {
+          if device.status != status {
+            device.status = status;
+            device.status_since = now;
+          }
+        }
+        None => {
+          _ = self.devices.insert(
+            name,
+            DevicePollState {
+              status,
+              status_since: now,
+              last_full_sync: None,
+              last_partial_sync: None,
+            },
+          );
+        }
       }
     }
   }
 }
 
+impl DevicePollState {
+  #[inline]
+  fn next_full_sync(&self, schedule: PollSchedule) -> Option<Instant> {
+    self.last_full_sync.map(|v| v + schedule.freq)
+  }
+
+  #[inline]
+  fn next_partial_sync(&self, schedule: PollSchedule) -> Option<Instant> {
+    self
+      .last_partial_sync
+      .max(self.last_full_sync)
+      .map(|v| v + schedule.interval)
+  }
+}
+
 impl ClientDiff {
   pub fn diff(source: &[ClientInfo], target: &[IpAddr]) -> Self {
     let mut diff = ClientDiff {
@@ -411,3 +548,163 @@ impl ClientDiff {
     diff
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{UpsPollBatch, UpsPollScheduler};
+  use crate::{
+    config::{
+      UpsdConfig,
+      poll_schedule::{DevicePollConfig, PollRule},
+    },
+    state::{DaemonState, UpsdState},
+  };
+  use nut_webgui_upsmc::{UpsName, ups_status::UpsStatus};
+  use std::{sync::Arc, time::Duration};
+  use tokio::{sync::RwLock, time::Instant};
+
+  fn scheduler(config: UpsdConfig) -> UpsPollScheduler {
+    UpsPollScheduler::new(Arc::new(UpsdState {
+      daemon_state: RwLock::new(DaemonState::new()),
+      connection_pool: None,
+      config,
+      namespace: "default".into(),
+    }))
+  }
+
+  fn devices(devices: &[(&str, UpsStatus)]) -> Vec<(UpsName, UpsStatus)> {
+    devices
+      .iter()
+      .map(|(name, status)| (UpsName::new_unchecked(name), *status))
+      .collect()
+  }
+
+  fn names(devices: &[UpsName]) -> Vec<&str> {
+    let mut names: Vec<_> = devices.iter().map(|v| v.as_str()).collect();
+    names.sort_unstable();
+    names
+  }
+
+  fn is_idle(batch: &UpsPollBatch) -> bool {
+    batch.full.is_empty() && batch.partial.is_empty()
+  }
+
+  #[test]
+  fn rule_applies_on_status_change() {
+    let mut scheduler = scheduler(UpsdConfig {
+      poll_interval: 10,
+      poll_freq: 60,
+      poll_rules: vec![PollRule {
+        status: UpsStatus::ON_BATTERY,
+        stable_for: 0,
+        poll_interval: Some(1),
+        poll_freq: Some(5),
+      }],
+      ..Default::default()
+    });
+
+    let start = Instant::now();
+    let at = |secs: u64| start + Duration::from_secs(secs);
+
+    scheduler.refresh(devices(&[("ups", UpsStatus::ONLINE)]), at(0));
+    let (batch, _) = scheduler.poll(at(0));
+    assert_eq!(names(&batch.full), ["ups"]);
+
+    let (batch, deadline) = scheduler.poll(at(2));
+    assert!(is_idle(&batch));
+    assert_eq!(deadline, Some(at(10)));
+
+    scheduler.refresh(devices(&[("ups", UpsStatus::ON_BATTERY)]), at(2));
+    let (batch, deadline) = scheduler.poll(at(2));
+    assert_eq!(names(&batch.partial), ["ups"]);
+    assert_eq!(deadline, Some(at(3)));
+
+    let (batch, _) = scheduler.poll(at(5));
+    assert_eq!(names(&batch.full), ["ups"]);
+  }
+
+  #[test]
+  fn stable_status_promotes_rule() {
+    let mut scheduler = scheduler(UpsdConfig {
+      poll_interval: 2,
+      poll_freq: 30,
+      poll_rules: vec![PollRule {
+        status: UpsStatus::ONLINE,
+        stable_for: 600,
+        poll_interval: Some(30),
+        poll_freq: Some(300),
+      }],
+      ..Default::default()
+    });
+
+    let start = Instant::now();
+    let at = |secs: u64| start + Duration::from_secs(secs);
+
+    scheduler.refresh(devices(&[("ups", UpsStatus::ONLINE)]), at(0));
+    let (batch, deadline) = scheduler.poll(at(0));
+    assert_eq!(names(&batch.full), ["ups"]);
+    assert_eq!(deadline, Some(at(2)));
+
+    // Status is unchanged between refreshes, so the device becomes stable after 600 seconds.
+    scheduler.refresh(devices(&[("ups", UpsStatus::ONLINE)]), at(600));
+    let (batch, deadline) = scheduler.poll(at(600));
+    assert_eq!(names(&batch.full), ["ups"]);
+    assert_eq!(deadline, Some(at(630)));
+
+    // Any status change resets the stability timer.
+    scheduler.refresh(devices(&[("ups", UpsStatus::ON_BATTERY)]), at(601));
+    scheduler.refresh(devices(&[("ups", UpsStatus::ONLINE)]), at(602));
+    let (batch, deadline) = scheduler.poll(at(602));
+    assert_eq!(names(&batch.partial), ["ups"]);
+    assert_eq!(deadline, Some(at(604)));
+  }
+
+  #[test]
+  fn device_override_precedence() {
+    let mut config = UpsdConfig {
+      poll_interval: 2,
+      poll_freq: 30,
+      poll_rules: vec![PollRule {
+        status: UpsStatus::ON_BATTERY,
+        stable_for: 0,
+        poll_interval: Some(1),
+        poll_freq: None,
+      }],
+      ..Default::default()
+    };
+    config.poll_overrides.insert(
+      UpsName::new_unchecked("slow"),
+      DevicePollConfig {
+        poll_interval: Some(5),
+        poll_freq: None,
+      },
+    );
+
+    let mut scheduler = scheduler(config);
+    let start = Instant::now();
+    let at = |secs: u64| start + Duration::from_secs(secs);
+
+    scheduler.refresh(
+      devices(&[("ups", UpsStatus::ONLINE), ("slow", UpsStatus::ONLINE)]),
+      at(0),
+    );
+    let (batch, _) = scheduler.poll(at(0));
+    assert_eq!(names(&batch.full), ["slow", "ups"]);
+
+    // Device overrides take precedence over namespace defaults.
+    let (batch, _) = scheduler.poll(at(2));
+    assert_eq!(names(&batch.partial), ["ups"]);
+
+    let (batch, _) = scheduler.poll(at(5));
+    assert_eq!(names(&batch.partial), ["slow", "ups"]);
+
+    // Matching poll rules take precedence over device overrides.
+    scheduler.refresh(
+      devices(&[("ups", UpsStatus::ONLINE), ("slow", UpsStatus::ON_BATTERY)]),
+      at(5),
+    );
+    let (batch, deadline) = scheduler.poll(at(6));
+    assert_eq!(names(&batch.partial), ["slow"]);
+    assert_eq!(deadline, Some(at(7)));
+  }
+}
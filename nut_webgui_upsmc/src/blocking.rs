@@ -0,0 +1,89 @@
+//! Synchronous client based on [`std::net::TcpStream`].
+//!
+//! Intended for small tools and language bindings where running an async runtime is not worth it.
+//! Commands, responses and errors are the same types used by the async [`crate::client`] module.
+
+use crate::{
+  CmdName, UpsName, VarName,
+  client::ProtVerCommand,
+  error::Error,
+  response::{
+    ClientList, CmdDesc, CmdList, DaemonVer, EnumList, Help, NumLogins, ProtVer, RangeList, RwList,
+    UpsDesc, UpsList, UpsVar, UpsVarDesc, UpsVarList, UpsVarType,
+  },
+};
+use core::borrow::Borrow;
+
+mod client_auth;
+mod client_base;
+
+pub use client_auth::NutAuthClient;
+pub use client_base::NutClient;
+
+/// Blocking counterpart of [`crate::client::AsyncNutClient`].
+pub trait BlockingNutClient {
+  fn get_cmd_desc<N, C>(&mut self, ups: N, cmd: C) -> Result<CmdDesc, Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>;
+
+  fn get_num_logins<N>(&mut self, ups: N) -> Result<NumLogins, Error>
+  where
+    N: Borrow<UpsName>;
+
+  /// Gets the network protocol version with legacy `NETVER` command for compatibility.
+  fn get_protver(&mut self) -> Result<ProtVer, Error>;
+
+  fn get_protver_with(&mut self, command: ProtVerCommand) -> Result<ProtVer, Error>;
+
+  fn get_ups_desc<N>(&mut self, ups: N) -> Result<UpsDesc, Error>
+  where
+    N: Borrow<UpsName>;
+
+  fn get_var<N, V>(&mut self, ups: N, var: V) -> Result<UpsVar, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>;
+
+  fn get_var_type<N, V>(&mut self, ups: N, var: V) -> Result<UpsVarType, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>;
+
+  fn get_var_desc<N, V>(&mut self, ups: N, var: V) -> Result<UpsVarDesc, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>;
+
+  fn get_ver(&mut self) -> Result<DaemonVer, Error>;
+
+  fn help(&mut self) -> Result<Help, Error>;
+
+  fn list_client<N>(&mut self, ups: N) -> Result<ClientList, Error>
+  where
+    N: Borrow<UpsName>;
+
+  fn list_cmd<N>(&mut self, ups: N) -> Result<CmdList, Error>
+  where
+    N: Borrow<UpsName>;
+
+  fn list_enum<N, V>(&mut self, ups: N, var: V) -> Result<EnumList, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>;
+
+  fn list_range<N, V>(&mut self, ups: N, var: V) -> Result<RangeList, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>;
+
+  fn list_rw<N>(&mut self, ups: N) -> Result<RwList, Error>
+  where
+    N: Borrow<UpsName>;
+
+  fn list_ups(&mut self) -> Result<UpsList, Error>;
+
+  fn list_var<N>(&mut self, ups: N) -> Result<UpsVarList, Error>
+  where
+    N: Borrow<UpsName>;
+}
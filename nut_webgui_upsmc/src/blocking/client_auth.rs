@@ -0,0 +1,427 @@
+use super::NutClient;
+use crate::{
+  CmdName, UpsName, Value, VarName,
+  blocking::BlockingNutClient,
+  client::ProtVerCommand,
+  command,
+  error::{Error, ErrorKind},
+  internal::Serialize,
+  response,
+};
+use core::{borrow::Borrow, time::Duration};
+use std::{
+  io::{Read, Write},
+  net::{TcpStream, ToSocketAddrs},
+  time::Instant,
+};
+
+const TRACKING_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+pub struct NutAuthClient<T>
+where
+  T: Read + Write,
+{
+  inner: NutClient<T>,
+  tracking: bool,
+}
+
+impl<T> BlockingNutClient for NutAuthClient<T>
+where
+  T: Read + Write,
+{
+  #[inline]
+  fn get_cmd_desc<N, C>(&mut self, ups: N, cmd: C) -> Result<response::CmdDesc, Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+  {
+    self.inner.get_cmd_desc(ups, cmd)
+  }
+
+  #[inline]
+  fn get_num_logins<N>(&mut self, ups: N) -> Result<response::NumLogins, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    self.inner.get_num_logins(ups)
+  }
+
+  #[inline]
+  fn get_protver(&mut self) -> Result<response::ProtVer, Error> {
+    self.inner.get_protver()
+  }
+
+  #[inline]
+  fn get_protver_with(&mut self, command: ProtVerCommand) -> Result<response::ProtVer, Error> {
+    self.inner.get_protver_with(command)
+  }
+
+  #[inline]
+  fn get_ups_desc<N>(&mut self, ups: N) -> Result<response::UpsDesc, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    self.inner.get_ups_desc(ups)
+  }
+
+  #[inline]
+  fn get_var<N, V>(&mut self, ups: N, var: V) -> Result<response::UpsVar, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    self.inner.get_var(ups, var)
+  }
+
+  #[inline]
+  fn get_var_type<N, V>(&mut self, ups: N, var: V) -> Result<response::UpsVarType, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    self.inner.get_var_type(ups, var)
+  }
+
+  #[inline]
+  fn get_var_desc<N, V>(&mut self, ups: N, var: V) -> Result<response::UpsVarDesc, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    self.inner.get_var_desc(ups, var)
+  }
+
+  #[inline]
+  fn get_ver(&mut self) -> Result<response::DaemonVer, Error> {
+    self.inner.get_ver()
+  }
+
+  #[inline]
+  fn help(&mut self) -> Result<response::Help, Error> {
+    self.inner.help()
+  }
+
+  #[inline]
+  fn list_client<N>(&mut self, ups: N) -> Result<response::ClientList, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    self.inner.list_client(ups)
+  }
+
+  #[inline]
+  fn list_cmd<N>(&mut self, ups: N) -> Result<response::CmdList, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    self.inner.list_cmd(ups)
+  }
+
+  #[inline]
+  fn list_enum<N, V>(&mut self, ups: N, var: V) -> Result<response::EnumList, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    self.inner.list_enum(ups, var)
+  }
+
+  #[inline]
+  fn list_range<N, V>(&mut self, ups: N, var: V) -> Result<response::RangeList, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    self.inner.list_range(ups, var)
+  }
+
+  #[inline]
+  fn list_rw<N>(&mut self, ups: N) -> Result<response::RwList, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    self.inner.list_rw(ups)
+  }
+
+  #[inline]
+  fn list_ups(&mut self) -> Result<response::UpsList, Error> {
+    self.inner.list_ups()
+  }
+
+  #[inline]
+  fn list_var<N>(&mut self, ups: N) -> Result<response::UpsVarList, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    self.inner.list_var(ups)
+  }
+}
+
+impl<T> NutAuthClient<T>
+where
+  T: Read + Write,
+{
+  pub fn attach<N>(&mut self, ups: N) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = command::AttachCommand { ups: ups.borrow() }.serialize();
+    _ = self.inner.send::<_, response::ProtOk>(command)?;
+
+    Ok(())
+  }
+
+  /// Requests primary role for the UPS device.
+  pub fn primary<N>(&mut self, ups: N) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = command::PrimaryCommand { ups: ups.borrow() }.serialize();
+    _ = self.inner.send::<_, response::ProtOkPrimary>(command)?;
+
+    Ok(())
+  }
+
+  /// Legacy alias of [`NutAuthClient::primary`] for upsd versions older than NUT 2.8.0.
+  pub fn master<N>(&mut self, ups: N) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = command::MasterCommand { ups: ups.borrow() }.serialize();
+    _ = self.inner.send::<_, response::ProtOkMaster>(command)?;
+
+    Ok(())
+  }
+
+  #[inline]
+  pub fn detach(self) -> Result<(), Error> {
+    self.close()
+  }
+
+  #[inline]
+  pub fn into_inner(self) -> T {
+    self.inner.into_inner()
+  }
+
+  pub fn fsd<N>(&mut self, ups: N) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = command::FsdCommand { ups: ups.borrow() }.serialize();
+    _ = self.inner.send::<_, response::ProtOkFsd>(command)?;
+
+    Ok(())
+  }
+
+  pub fn set_var<N, V, D>(&mut self, ups: N, var: V, value: D) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+    D: Borrow<Value>,
+  {
+    let command = command::SetVariable {
+      ups: ups.borrow(),
+      var: var.borrow(),
+      value: value.borrow(),
+    }
+    .serialize();
+
+    if self.tracking {
+      _ = self.inner.send::<_, response::TrackingId>(command)?;
+    } else {
+      _ = self.inner.send::<_, response::ProtOk>(command)?;
+    }
+
+    Ok(())
+  }
+
+  /// Sends `SET VAR` with tracking enabled and returns the tracking id assigned by `upsd`.
+  ///
+  /// Enables tracking on the connection if it's not already enabled.
+  pub fn set_var_tracked<N, V, D>(
+    &mut self,
+    ups: N,
+    var: V,
+    value: D,
+  ) -> Result<response::TrackingId, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+    D: Borrow<Value>,
+  {
+    if !self.tracking {
+      self.set_tracking(true)?;
+    }
+
+    let command = command::SetVariable {
+      ups: ups.borrow(),
+      var: var.borrow(),
+      value: value.borrow(),
+    }
+    .serialize();
+
+    self.inner.send::<_, response::TrackingId>(command)
+  }
+
+  pub fn instcmd<N, C>(&mut self, ups: N, cmd: C) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+  {
+    self.send_instcmd(ups.borrow(), cmd.borrow(), None)
+  }
+
+  /// Sends `INSTCMD` with a command argument, e.g. `load.off.delay 120`.
+  pub fn instcmd_with_arg<N, C, D>(&mut self, ups: N, cmd: C, value: D) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+    D: Borrow<Value>,
+  {
+    self.send_instcmd(ups.borrow(), cmd.borrow(), Some(value.borrow()))
+  }
+
+  fn send_instcmd(
+    &mut self,
+    ups: &UpsName,
+    cmd: &CmdName,
+    value: Option<&Value>,
+  ) -> Result<(), Error> {
+    let command = command::InstCmd { ups, cmd, value }.serialize();
+
+    if self.tracking {
+      _ = self.inner.send::<_, response::TrackingId>(command)?;
+    } else {
+      _ = self.inner.send::<_, response::ProtOk>(command)?;
+    }
+
+    Ok(())
+  }
+
+  /// Sends `INSTCMD` with tracking enabled and an optional command argument, returns the tracking
+  /// id assigned by `upsd`.
+  ///
+  /// Enables tracking on the connection if it's not already enabled.
+  pub fn instcmd_tracked<N, C>(
+    &mut self,
+    ups: N,
+    cmd: C,
+    value: Option<&Value>,
+  ) -> Result<response::TrackingId, Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+  {
+    if !self.tracking {
+      self.set_tracking(true)?;
+    }
+
+    let command = command::InstCmd {
+      ups: ups.borrow(),
+      cmd: cmd.borrow(),
+      value,
+    }
+    .serialize();
+
+    self.inner.send::<_, response::TrackingId>(command)
+  }
+
+  /// Enables or disables `INSTCMD` and `SET VAR` result tracking for the current connection.
+  pub fn set_tracking(&mut self, enabled: bool) -> Result<(), Error> {
+    let command = command::SetTracking { enabled }.serialize();
+    _ = self.inner.send::<_, response::ProtOk>(command)?;
+    self.tracking = enabled;
+
+    Ok(())
+  }
+
+  #[inline]
+  pub const fn is_tracking(&self) -> bool {
+    self.tracking
+  }
+
+  /// Queries the current state of a tracked command.
+  ///
+  /// Errors reported by `upsd` for the tracked command are returned as
+  /// [`response::TrackingStatus::Failed`].
+  pub fn get_tracking(
+    &mut self,
+    id: &response::TrackingId,
+  ) -> Result<response::TrackingStatus, Error> {
+    let command = command::GetTracking { id: id.as_str() }.serialize();
+
+    match self.inner.send::<_, response::TrackingStatus>(command) {
+      Ok(status) => Ok(status),
+      Err(err) => match err.kind() {
+        ErrorKind::ProtocolError { inner } => Ok(response::TrackingStatus::Failed(inner.clone())),
+        _ => Err(err),
+      },
+    }
+  }
+
+  /// Polls `GET TRACKING` until the tracked command resolves or `wait_timeout` is elapsed.
+  ///
+  /// Returns [`response::TrackingStatus::Pending`] if the command is still pending after timeout.
+  pub fn wait_tracking(
+    &mut self,
+    id: &response::TrackingId,
+    wait_timeout: Duration,
+  ) -> Result<response::TrackingStatus, Error> {
+    let deadline = Instant::now() + wait_timeout;
+
+    loop {
+      let status = self.get_tracking(id)?;
+      let now = Instant::now();
+
+      if !status.is_pending() || now >= deadline {
+        return Ok(status);
+      }
+
+      std::thread::sleep(TRACKING_POLL_INTERVAL.min(deadline - now));
+    }
+  }
+
+  #[inline]
+  pub fn is_open(&mut self) -> bool {
+    self.inner.is_open()
+  }
+
+  pub fn close(mut self) -> Result<(), Error> {
+    _ = self
+      .inner
+      .send::<_, response::ProtOkDetach>(command::DetachCommand.serialize())?;
+
+    self.inner.close()
+  }
+}
+
+impl NutAuthClient<TcpStream> {
+  pub fn connect<A>(addr: A, username: &str, password: &str) -> Result<Self, Error>
+  where
+    A: ToSocketAddrs,
+  {
+    NutClient::connect(addr)?.authenticate(username, password)
+  }
+}
+
+impl<T> NutClient<T>
+where
+  T: Read + Write,
+{
+  pub fn authenticate(self, username: &str, password: &str) -> Result<NutAuthClient<T>, Error> {
+    let mut client = NutAuthClient {
+      inner: self,
+      tracking: false,
+    };
+
+    _ = client
+      .inner
+      .send::<_, response::ProtOk>(command::Username { username }.serialize())?;
+
+    _ = client
+      .inner
+      .send::<_, response::ProtOk>(command::Password { password }.serialize())?;
+
+    Ok(client)
+  }
+}
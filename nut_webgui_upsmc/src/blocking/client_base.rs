@@ -0,0 +1,382 @@
+use crate::{
+  CmdName, UpsName, VarName,
+  blocking::BlockingNutClient,
+  client::ProtVerCommand,
+  command,
+  error::{Error, ErrorKind, ProtocolError},
+  internal::{Deserialize, Serialize, lexer::Lexer},
+  response,
+};
+use core::{borrow::Borrow, time::Duration};
+use std::{
+  io::{BufRead, BufReader, Read, Write},
+  net::{TcpStream, ToSocketAddrs},
+};
+use tracing::{error, trace};
+
+// Expected message sizes are around 4KiB. This soft limit is a safeguard to prevent holding huge
+// chunks of memory.
+const SCRATCH_SOFT_LIMIT: usize = 1024 * 12;
+const LIST_START: &str = "BEGIN LIST";
+const LIST_END: &str = "END LIST";
+const PROT_ERR: &str = "ERR";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub struct NutClient<S>
+where
+  S: Read + Write,
+{
+  stream: BufReader<S>,
+  scratch_buff: String,
+  poisoned: bool,
+}
+
+impl NutClient<TcpStream> {
+  pub fn connect<A>(addr: A) -> Result<Self, Error>
+  where
+    A: ToSocketAddrs,
+  {
+    let connection = TcpStream::connect(addr)?;
+    connection.set_nodelay(true)?;
+
+    let client = Self::new(connection);
+    client.set_timeout(DEFAULT_TIMEOUT)?;
+
+    Ok(client)
+  }
+
+  /// Sets read and write timeouts of the underlying socket. Requests exceeding the timeout fail
+  /// with [`ErrorKind::RequestTimeout`].
+  pub fn set_timeout(&self, timeout: Duration) -> Result<(), Error> {
+    let stream = self.stream.get_ref();
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    Ok(())
+  }
+}
+
+impl<S> From<S> for NutClient<S>
+where
+  S: Read + Write,
+{
+  fn from(value: S) -> Self {
+    Self::new(value)
+  }
+}
+
+impl<S> NutClient<S>
+where
+  S: Read + Write,
+{
+  pub fn new(stream: S) -> Self {
+    Self {
+      stream: BufReader::new(stream),
+      scratch_buff: String::new(),
+      poisoned: false,
+    }
+  }
+
+  /// Returns `true` when the last request did not complete cleanly, e.g. it's timed out or failed
+  /// with an IO error. Poisoned connections may still have unread response data in the stream and
+  /// should not be reused.
+  #[inline]
+  pub const fn is_poisoned(&self) -> bool {
+    self.poisoned
+  }
+
+  pub fn into_inner(self) -> S {
+    self.stream.into_inner()
+  }
+
+  pub fn is_open(&mut self) -> bool {
+    match self.send_raw(command::GetNetVer.serialize()) {
+      Err(_) => false,
+      Ok(v) => !v.is_empty(),
+    }
+  }
+
+  pub fn close(mut self) -> Result<(), Error> {
+    self.stream.get_mut().flush()?;
+    Ok(())
+  }
+
+  fn send_raw(&mut self, request: &str) -> Result<&str, Error> {
+    match self.inner_send_raw(request) {
+      Ok(_) => Ok(self.scratch_buff.as_str()),
+      Err(err) => match err.kind() {
+        ErrorKind::IOError {
+          kind: std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut,
+        } => Err(ErrorKind::RequestTimeout.into()),
+        _ => Err(err),
+      },
+    }
+  }
+
+  fn inner_send_raw(&mut self, send: &str) -> Result<usize, Error> {
+    trace!(message = "tcp message", send = send);
+
+    // Stays poisoned if it fails before the response is fully read.
+    self.poisoned = true;
+
+    let writer = self.stream.get_mut();
+    writer.write_all(send.as_bytes())?;
+    writer.flush()?;
+
+    let result = self.read_response(send);
+
+    match &result {
+      Ok(0) => {}
+      Ok(_) => self.poisoned = false,
+      Err(err) if matches!(err.kind(), ErrorKind::ProtocolError { .. }) => self.poisoned = false,
+      Err(_) => {}
+    }
+
+    result
+  }
+
+  fn read_response(&mut self, send: &str) -> Result<usize, Error> {
+    if self.scratch_buff.len() >= SCRATCH_SOFT_LIMIT {
+      self.scratch_buff = String::new()
+    } else {
+      self.scratch_buff.clear();
+    }
+
+    let mut total_read = self.stream.read_line(&mut self.scratch_buff)?;
+
+    if self.scratch_buff.starts_with(LIST_START) {
+      loop {
+        let read = self.stream.read_line(&mut self.scratch_buff)?;
+        let line = &self.scratch_buff[total_read..];
+
+        if read == 0 {
+          return Err(std::io::ErrorKind::UnexpectedEof.into());
+        } else if line.starts_with(LIST_END) {
+          break;
+        } else {
+          total_read += read;
+        }
+      }
+
+      trace!(
+        message = "upsd tcp protocol: list message received",
+        response = &self.scratch_buff,
+        command = send
+      );
+
+      Ok(total_read)
+    } else if let Some(prot_err) = self.scratch_buff.strip_prefix(PROT_ERR) {
+      let prot_err = ProtocolError::from(prot_err.trim());
+
+      error!(
+        message = "upsd tcp protocol: error received",
+        response = &self.scratch_buff,
+        command = send
+      );
+
+      Err(prot_err.into())
+    } else {
+      trace!(
+        message = "upsd tcp protocol: line message received",
+        response = &self.scratch_buff,
+        command = send
+      );
+
+      Ok(total_read)
+    }
+  }
+
+  pub(crate) fn send<C, R>(&mut self, command: C) -> Result<R, Error>
+  where
+    R: Deserialize<Error = Error>,
+    C: AsRef<str>,
+  {
+    let response = self.send_raw(command.as_ref())?;
+
+    if response.is_empty() {
+      Err(ErrorKind::EmptyResponse.into())
+    } else {
+      let mut lexer = Lexer::new(response);
+      R::deserialize(&mut lexer)
+    }
+  }
+
+  fn send_text(&mut self, command: &str) -> Result<String, Error> {
+    let response = self.send_raw(command)?;
+
+    if response.is_empty() {
+      Err(ErrorKind::EmptyResponse.into())
+    } else {
+      Ok(response.trim().to_owned())
+    }
+  }
+}
+
+impl<S> BlockingNutClient for NutClient<S>
+where
+  S: Read + Write,
+{
+  fn get_cmd_desc<N, C>(&mut self, ups: N, cmd: C) -> Result<response::CmdDesc, Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+  {
+    let command = command::GetCmdDesc {
+      ups: ups.borrow(),
+      cmd: cmd.borrow(),
+    }
+    .serialize();
+
+    self.send(command)
+  }
+
+  fn get_num_logins<N>(&mut self, ups: N) -> Result<response::NumLogins, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = command::GetNumLogins { ups: ups.borrow() }.serialize();
+    self.send(command)
+  }
+
+  #[inline]
+  fn get_protver(&mut self) -> Result<response::ProtVer, Error> {
+    self.get_protver_with(ProtVerCommand::NetVer)
+  }
+
+  fn get_protver_with(&mut self, command: ProtVerCommand) -> Result<response::ProtVer, Error> {
+    let command = match command {
+      ProtVerCommand::NetVer => command::GetNetVer.serialize(),
+      ProtVerCommand::ProtVer => command::GetProtVer.serialize(),
+    };
+
+    Ok(response::ProtVer {
+      value: self.send_text(command)?,
+    })
+  }
+
+  fn get_ups_desc<N>(&mut self, ups: N) -> Result<response::UpsDesc, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = command::GetUpsDesc { ups: ups.borrow() }.serialize();
+    self.send(command)
+  }
+
+  fn get_var<N, V>(&mut self, ups: N, var: V) -> Result<response::UpsVar, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = command::GetVar {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.send(command)
+  }
+
+  fn get_var_type<N, V>(&mut self, ups: N, var: V) -> Result<response::UpsVarType, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = command::GetVarType {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.send(command)
+  }
+
+  fn get_var_desc<N, V>(&mut self, ups: N, var: V) -> Result<response::UpsVarDesc, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = command::GetVarDesc {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.send(command)
+  }
+
+  fn get_ver(&mut self) -> Result<response::DaemonVer, Error> {
+    Ok(response::DaemonVer {
+      value: self.send_text(command::GetDaemonVer.serialize())?,
+    })
+  }
+
+  fn help(&mut self) -> Result<response::Help, Error> {
+    Ok(response::Help {
+      value: self.send_text(command::Help.serialize())?,
+    })
+  }
+
+  fn list_client<N>(&mut self, ups: N) -> Result<response::ClientList, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = command::ListClient { ups: ups.borrow() }.serialize();
+    self.send(command)
+  }
+
+  fn list_cmd<N>(&mut self, ups: N) -> Result<response::CmdList, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = command::ListCmd { ups: ups.borrow() }.serialize();
+    self.send(command)
+  }
+
+  fn list_enum<N, V>(&mut self, ups: N, var: V) -> Result<response::EnumList, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = command::ListEnum {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.send(command)
+  }
+
+  fn list_range<N, V>(&mut self, ups: N, var: V) -> Result<response::RangeList, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = command::ListRange {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.send(command)
+  }
+
+  fn list_rw<N>(&mut self, ups: N) -> Result<response::RwList, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = command::ListRw { ups: ups.borrow() }.serialize();
+    self.send(command)
+  }
+
+  fn list_ups(&mut self) -> Result<response::UpsList, Error> {
+    self.send(command::ListUps.serialize())
+  }
+
+  fn list_var<N>(&mut self, ups: N) -> Result<response::UpsVarList, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = command::ListVar { ups: ups.borrow() }.serialize();
+    self.send(command)
+  }
+}
@@ -1,22 +1,64 @@
 use crate::{
   CmdName, UpsName, VarName,
-  error::Error,
+  error::{Error, ErrorKind},
   response::{
-    ClientList, CmdDesc, CmdList, DaemonVer, EnumList, ProtVer, RangeList, RwList, UpsDesc,
-    UpsList, UpsVar, UpsVarDesc, UpsVarList, UpsVarType,
+    ClientList, CmdDesc, CmdList, DaemonVer, EnumList, Help, NumLogins, ProtVer, RangeList, RwList,
+    UpsDesc, UpsList, UpsVar, UpsVarDesc, UpsVarList, UpsVarType,
   },
 };
 use core::borrow::Borrow;
+
+#[cfg(feature = "tokio")]
 mod client_auth;
+#[cfg(feature = "tokio")]
 mod client_base;
+#[cfg(feature = "tokio")]
+mod client_batch;
+#[cfg(feature = "tokio")]
 mod client_pool;
+#[cfg(feature = "tokio")]
+mod client_proxy;
+#[cfg(feature = "tokio")]
+mod client_record;
+#[cfg(feature = "tokio")]
+mod client_replay;
 
 #[cfg(feature = "rustls")]
 mod client_tls;
 
+#[cfg(feature = "tokio")]
+pub use crate::internal::item_pool::PoolStats;
+#[cfg(feature = "tokio")]
 pub use client_auth::NutAuthClient;
+#[cfg(feature = "tokio")]
 pub use client_base::NutClient;
-pub use client_pool::{ClientStream, NutPoolClient, NutPoolClientBuilder};
+#[cfg(feature = "tokio")]
+pub use client_batch::{Batch, BatchHandle, BatchItem, BatchResults};
+#[cfg(feature = "tokio")]
+pub use client_pool::{
+  ClientStream, CommandEvent, CommandObserver, Endpoint, EndpointStatus, NegotiatedTls,
+  NutPoolClient, NutPoolClientBuilder, ServerAddr,
+};
+#[cfg(feature = "tokio")]
+pub use client_proxy::{Proxy, ProxyAuth};
+#[cfg(feature = "tokio")]
+pub use client_record::{RecordStream, SessionRecorder};
+#[cfg(feature = "tokio")]
+pub use client_replay::{Fixture, ReplayStream};
+
+#[cfg(feature = "rustls")]
+pub use client_tls::TlsNegotiation;
+
+/// Command used for querying the network protocol version.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProtVerCommand {
+  /// `NETVER`, supported by all upsd versions, but deprecated since NUT 2.8.0.
+  #[default]
+  NetVer,
+
+  /// `PROTVER`, only supported by NUT 2.8.0 and later.
+  ProtVer,
+}
 
 pub trait AsyncNutClient {
   fn get_cmd_desc<N, C>(self, ups: N, cmd: C) -> impl Future<Output = Result<CmdDesc, Error>>
@@ -24,8 +66,37 @@ pub trait AsyncNutClient {
     N: Borrow<UpsName>,
     C: Borrow<CmdName>;
 
+  /// Gets the number of clients logged into the UPS with `GET NUMLOGINS`.
+  ///
+  /// The default implementation returns [`ErrorKind::Unimplemented`].
+  fn get_num_logins<N>(self, ups: N) -> impl Future<Output = Result<NumLogins, Error>>
+  where
+    Self: Sized,
+    N: Borrow<UpsName>,
+  {
+    _ = ups;
+    core::future::ready(Err(ErrorKind::Unimplemented.into()))
+  }
+
+  /// Gets the network protocol version with legacy `NETVER` command for compatibility.
   fn get_protver(self) -> impl Future<Output = Result<ProtVer, Error>>;
 
+  /// Gets the network protocol version with the given command.
+  ///
+  /// The default implementation only supports [`ProtVerCommand::NetVer`], and returns
+  /// [`ErrorKind::Unimplemented`] for [`ProtVerCommand::ProtVer`].
+  fn get_protver_with(self, command: ProtVerCommand) -> impl Future<Output = Result<ProtVer, Error>>
+  where
+    Self: Sized,
+  {
+    async move {
+      match command {
+        ProtVerCommand::NetVer => self.get_protver().await,
+        ProtVerCommand::ProtVer => Err(ErrorKind::Unimplemented.into()),
+      }
+    }
+  }
+
   fn get_ups_desc<N>(self, ups: N) -> impl Future<Output = Result<UpsDesc, Error>>
   where
     N: Borrow<UpsName>;
@@ -47,6 +118,16 @@ pub trait AsyncNutClient {
 
   fn get_ver(self) -> impl Future<Output = Result<DaemonVer, Error>>;
 
+  /// Lists the commands supported by upsd with `HELP`.
+  ///
+  /// The default implementation returns [`ErrorKind::Unimplemented`].
+  fn help(self) -> impl Future<Output = Result<Help, Error>>
+  where
+    Self: Sized,
+  {
+    core::future::ready(Err(ErrorKind::Unimplemented.into()))
+  }
+
   fn list_client<N>(self, ups: N) -> impl Future<Output = Result<ClientList, Error>>
   where
     N: Borrow<UpsName>;
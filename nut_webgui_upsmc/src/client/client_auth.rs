@@ -1,19 +1,27 @@
 use super::NutClient;
 use crate::{
-  CmdName, UpsName, Value, VarName, client::AsyncNutClient, command, error::Error,
-  internal::Serialize, response,
+  CmdName, UpsName, Value, VarName,
+  client::{AsyncNutClient, Batch, BatchResults, ProtVerCommand},
+  command,
+  error::{Error, ErrorKind},
+  internal::Serialize,
+  response,
 };
-use core::borrow::Borrow;
+use core::{borrow::Borrow, time::Duration};
 use tokio::{
   io::{AsyncRead, AsyncWrite},
   net::{TcpStream, ToSocketAddrs},
+  time::{Instant, sleep},
 };
 
+const TRACKING_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
 pub struct NutAuthClient<T>
 where
   T: AsyncRead + AsyncWrite + Unpin + Send + Sync,
 {
   inner: NutClient<T>,
+  tracking: bool,
 }
 
 impl<T> AsyncNutClient for &mut NutAuthClient<T>
@@ -32,10 +40,24 @@ where
     self.inner.get_cmd_desc(ups, cmd)
   }
 
+  fn get_num_logins<N>(self, ups: N) -> impl Future<Output = Result<response::NumLogins, Error>>
+  where
+    N: std::borrow::Borrow<UpsName>,
+  {
+    self.inner.get_num_logins(ups)
+  }
+
   fn get_protver(self) -> impl Future<Output = Result<response::ProtVer, Error>> {
     self.inner.get_protver()
   }
 
+  fn get_protver_with(
+    self,
+    command: ProtVerCommand,
+  ) -> impl Future<Output = Result<response::ProtVer, Error>> {
+    self.inner.get_protver_with(command)
+  }
+
   fn get_ups_desc<N>(self, ups: N) -> impl Future<Output = Result<response::UpsDesc, Error>>
   where
     N: std::borrow::Borrow<UpsName>,
@@ -79,6 +101,10 @@ where
     self.inner.get_ver()
   }
 
+  fn help(self) -> impl Future<Output = Result<response::Help, Error>> {
+    self.inner.help()
+  }
+
   fn list_client<N>(self, ups: N) -> impl Future<Output = Result<response::ClientList, Error>>
   where
     N: std::borrow::Borrow<UpsName>,
@@ -150,6 +176,34 @@ where
     Ok(())
   }
 
+  /// Requests primary role for the UPS device.
+  pub async fn primary<N>(&mut self, ups: N) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = command::PrimaryCommand { ups: ups.borrow() }.serialize();
+    _ = self
+      .inner
+      .send::<_, response::ProtOkPrimary>(command)
+      .await?;
+
+    Ok(())
+  }
+
+  /// Legacy alias of [`NutAuthClient::primary`] for upsd versions older than NUT 2.8.0.
+  pub async fn master<N>(&mut self, ups: N) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = command::MasterCommand { ups: ups.borrow() }.serialize();
+    _ = self
+      .inner
+      .send::<_, response::ProtOkMaster>(command)
+      .await?;
+
+    Ok(())
+  }
+
   #[inline]
   pub fn detach(self) -> impl Future<Output = Result<(), Error>> {
     self.close()
@@ -183,27 +237,172 @@ where
     }
     .serialize();
 
-    _ = self.inner.send::<_, response::ProtOk>(command).await?;
+    if self.tracking {
+      _ = self.inner.send::<_, response::TrackingId>(command).await?;
+    } else {
+      _ = self.inner.send::<_, response::ProtOk>(command).await?;
+    }
 
     Ok(())
   }
 
+  /// Sends `SET VAR` with tracking enabled and returns the tracking id assigned by `upsd`.
+  ///
+  /// Enables tracking on the connection if it's not already enabled.
+  pub async fn set_var_tracked<N, V, D>(
+    &mut self,
+    ups: N,
+    var: V,
+    value: D,
+  ) -> Result<response::TrackingId, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+    D: Borrow<Value>,
+  {
+    if !self.tracking {
+      self.set_tracking(true).await?;
+    }
+
+    let command = command::SetVariable {
+      ups: ups.borrow(),
+      var: var.borrow(),
+      value: value.borrow(),
+    }
+    .serialize();
+
+    self.inner.send::<_, response::TrackingId>(command).await
+  }
+
   pub async fn instcmd<N, C>(&mut self, ups: N, cmd: C) -> Result<(), Error>
   where
     N: Borrow<UpsName>,
     C: Borrow<CmdName>,
   {
+    self.send_instcmd(ups.borrow(), cmd.borrow(), None).await
+  }
+
+  /// Sends `INSTCMD` with a command argument, e.g. `load.off.delay 120`.
+  pub async fn instcmd_with_arg<N, C, D>(&mut self, ups: N, cmd: C, value: D) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+    D: Borrow<Value>,
+  {
+    self
+      .send_instcmd(ups.borrow(), cmd.borrow(), Some(value.borrow()))
+      .await
+  }
+
+  async fn send_instcmd(
+    &mut self,
+    ups: &UpsName,
+    cmd: &CmdName,
+    value: Option<&Value>,
+  ) -> Result<(), Error> {
+    let command = command::InstCmd { ups, cmd, value }.serialize();
+
+    if self.tracking {
+      _ = self.inner.send::<_, response::TrackingId>(command).await?;
+    } else {
+      _ = self.inner.send::<_, response::ProtOk>(command).await?;
+    }
+
+    Ok(())
+  }
+
+  /// Sends `INSTCMD` with tracking enabled and an optional command argument, returns the tracking
+  /// id assigned by `upsd`.
+  ///
+  /// Enables tracking on the connection if it's not already enabled.
+  pub async fn instcmd_tracked<N, C>(
+    &mut self,
+    ups: N,
+    cmd: C,
+    value: Option<&Value>,
+  ) -> Result<response::TrackingId, Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+  {
+    if !self.tracking {
+      self.set_tracking(true).await?;
+    }
+
     let command = command::InstCmd {
       ups: ups.borrow(),
       cmd: cmd.borrow(),
+      value,
     }
     .serialize();
 
+    self.inner.send::<_, response::TrackingId>(command).await
+  }
+
+  /// Enables or disables `INSTCMD` and `SET VAR` result tracking for the current connection.
+  pub async fn set_tracking(&mut self, enabled: bool) -> Result<(), Error> {
+    let command = command::SetTracking { enabled }.serialize();
     _ = self.inner.send::<_, response::ProtOk>(command).await?;
+    self.tracking = enabled;
 
     Ok(())
   }
 
+  #[inline]
+  pub const fn is_tracking(&self) -> bool {
+    self.tracking
+  }
+
+  /// Queries the current state of a tracked command.
+  ///
+  /// Errors reported by `upsd` for the tracked command are returned as
+  /// [`response::TrackingStatus::Failed`].
+  pub async fn get_tracking(
+    &mut self,
+    id: &response::TrackingId,
+  ) -> Result<response::TrackingStatus, Error> {
+    let command = command::GetTracking { id: id.as_str() }.serialize();
+
+    match self
+      .inner
+      .send::<_, response::TrackingStatus>(command)
+      .await
+    {
+      Ok(status) => Ok(status),
+      Err(err) => match err.kind() {
+        ErrorKind::ProtocolError { inner } => Ok(response::TrackingStatus::Failed(inner.clone())),
+        _ => Err(err),
+      },
+    }
+  }
+
+  /// Polls `GET TRACKING` until the tracked command resolves or `wait_timeout` is elapsed.
+  ///
+  /// Returns [`response::TrackingStatus::Pending`] if the command is still pending after timeout.
+  pub async fn wait_tracking(
+    &mut self,
+    id: &response::TrackingId,
+    wait_timeout: Duration,
+  ) -> Result<response::TrackingStatus, Error> {
+    let deadline = Instant::now() + wait_timeout;
+
+    loop {
+      let status = self.get_tracking(id).await?;
+      let now = Instant::now();
+
+      if !status.is_pending() || now >= deadline {
+        return Ok(status);
+      }
+
+      sleep(TRACKING_POLL_INTERVAL.min(deadline - now)).await;
+    }
+  }
+
+  #[inline]
+  pub async fn send_batch(&mut self, batch: &Batch<'_>) -> Result<BatchResults, Error> {
+    self.inner.send_batch(batch).await
+  }
+
   #[inline]
   pub fn is_open(&mut self) -> impl Future<Output = bool> {
     self.inner.is_open()
@@ -244,7 +443,10 @@ where
     username: &str,
     password: &str,
   ) -> Result<NutAuthClient<T>, Error> {
-    let mut client = NutAuthClient { inner: self };
+    let mut client = NutAuthClient {
+      inner: self,
+      tracking: false,
+    };
     _ = client
       .inner
       .send::<_, response::ProtOk>(command::Username { username }.serialize())
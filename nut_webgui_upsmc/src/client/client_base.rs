@@ -1,6 +1,9 @@
 use crate::{
   CmdName, UpsName, VarName,
-  client::AsyncNutClient,
+  client::{
+    AsyncNutClient, ProtVerCommand,
+    client_batch::{Batch, BatchResults},
+  },
   command,
   error::{Error, ErrorKind, ProtocolError},
   internal::{Deserialize, Serialize, lexer::Lexer},
@@ -32,6 +35,7 @@ where
   writer: WriteHalf<S>,
   timeout: Duration,
   scratch_buff: String,
+  poisoned: bool,
 }
 
 impl NutClient<TcpStream> {
@@ -71,6 +75,7 @@ where
       reader,
       timeout: Duration::from_secs(60),
       scratch_buff: String::new(),
+      poisoned: false,
     }
   }
 
@@ -79,6 +84,14 @@ where
     self.timeout = timeout;
   }
 
+  /// Returns `true` when the last request did not complete cleanly, e.g. it's timed out, cancelled
+  /// or failed with an IO error. Poisoned connections may still have unread response data in the
+  /// stream and should not be reused.
+  #[inline]
+  pub const fn is_poisoned(&self) -> bool {
+    self.poisoned
+  }
+
   pub fn into_inner(self) -> S {
     let read_half = self.reader.into_inner();
     let write_half = self.writer;
@@ -88,7 +101,7 @@ where
   }
 
   pub async fn is_open(&mut self) -> bool {
-    match self.send_raw(command::GetProtVer.serialize()).await {
+    match self.send_raw(command::GetNetVer.serialize()).await {
       Err(_) => false,
       Ok(v) => !v.is_empty(),
     }
@@ -110,15 +123,89 @@ where
   async fn inner_send_raw(&mut self, send: &str) -> Result<usize, Error> {
     trace!(message = "tcp message", send = send);
 
+    // Stays poisoned if the future is dropped or fails before the response is fully read.
+    self.poisoned = true;
+
+    self.writer.write_all(send.as_bytes()).await?;
+    self.writer.flush().await?;
+
+    let result = self.read_response(send).await;
+
+    match &result {
+      Ok(0) => {}
+      Ok(_) => self.poisoned = false,
+      Err(err) if matches!(err.kind(), ErrorKind::ProtocolError { .. }) => self.poisoned = false,
+      Err(_) => {}
+    }
+
+    result
+  }
+
+  /// Sends all commands in the batch with a single write, then reads replies in the same order.
+  ///
+  /// Only IO errors, timeouts and connection drops fail the whole batch. Per command errors are
+  /// kept in [`BatchResults`].
+  ///
+  /// The request timeout applies to the write and to each reply separately, so large batches are
+  /// not limited by a single request's timeout.
+  pub async fn send_batch(&mut self, batch: &Batch<'_>) -> Result<BatchResults, Error> {
+    if batch.is_empty() {
+      return Ok(BatchResults::default());
+    }
+
+    let (send, offsets) = batch.serialize();
+
+    trace!(message = "tcp batch message", send = send);
+
+    self.poisoned = true;
+
+    let write = async {
+      self.writer.write_all(send.as_bytes()).await?;
+      self.writer.flush().await
+    };
+
+    match timeout(self.timeout, write).await {
+      Ok(result) => result?,
+      Err(_) => return Err(ErrorKind::RequestTimeout.into()),
+    }
+
+    let mut results = BatchResults::with_capacity(offsets.len());
+    let mut start = 0;
+
+    for (index, end) in offsets.into_iter().enumerate() {
+      let command = &send[start..end];
+      start = end;
+
+      let response = match timeout(self.timeout, self.read_response(command)).await {
+        Ok(response) => response,
+        Err(_) => return Err(ErrorKind::RequestTimeout.into()),
+      };
+
+      let result = match response {
+        Ok(0) => return Err(ErrorKind::EmptyResponse.into()),
+        Ok(_) => {
+          let mut lexer = Lexer::new(&self.scratch_buff);
+          batch.deserialize_at(index, &mut lexer)
+        }
+        Err(err) if matches!(err.kind(), ErrorKind::ProtocolError { .. }) => Err(err),
+        Err(err) => return Err(err),
+      };
+
+      results.push(result);
+    }
+
+    self.poisoned = false;
+
+    Ok(results)
+  }
+
+  async fn read_response(&mut self, send: &str) -> Result<usize, Error> {
     if self.scratch_buff.len() >= SCRATCH_SOFT_LIMIT {
       self.scratch_buff = String::new()
     } else {
       self.scratch_buff.clear();
     }
 
-    self.writer.write_all(send.as_bytes()).await?;
-    self.writer.flush().await?;
-
     let mut total_read = self.reader.read_line(&mut self.scratch_buff).await?;
 
     if self.scratch_buff.starts_with(LIST_START) {
@@ -126,7 +213,9 @@ where
         let read = self.reader.read_line(&mut self.scratch_buff).await?;
         let line = &self.scratch_buff[total_read..];
 
-        if line.starts_with(LIST_END) {
+        if read == 0 {
+          return Err(std::io::ErrorKind::UnexpectedEof.into());
+        } else if line.starts_with(LIST_END) {
           break;
         } else {
           total_read += read;
@@ -199,8 +288,26 @@ where
     self.send::<_, response::CmdDesc>(command)
   }
 
-  async fn get_protver(self) -> Result<response::ProtVer, Error> {
-    let response = self.send_raw(command::GetProtVer.serialize()).await?;
+  fn get_num_logins<N>(self, ups: N) -> impl Future<Output = Result<response::NumLogins, Error>>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = command::GetNumLogins { ups: ups.borrow() }.serialize();
+    self.send::<_, response::NumLogins>(command)
+  }
+
+  #[inline]
+  fn get_protver(self) -> impl Future<Output = Result<response::ProtVer, Error>> {
+    self.get_protver_with(ProtVerCommand::NetVer)
+  }
+
+  async fn get_protver_with(self, command: ProtVerCommand) -> Result<response::ProtVer, Error> {
+    let command = match command {
+      ProtVerCommand::NetVer => command::GetNetVer.serialize(),
+      ProtVerCommand::ProtVer => command::GetProtVer.serialize(),
+    };
+
+    let response = self.send_raw(command).await?;
 
     if response.is_empty() {
       Err(ErrorKind::EmptyResponse.into())
@@ -281,6 +388,18 @@ where
     }
   }
 
+  async fn help(self) -> Result<response::Help, Error> {
+    let response = self.send_raw(command::Help.serialize()).await?;
+
+    if response.is_empty() {
+      Err(ErrorKind::EmptyResponse.into())
+    } else {
+      Ok(response::Help {
+        value: response.trim().to_owned(),
+      })
+    }
+  }
+
   fn list_client<N>(self, ups: N) -> impl Future<Output = Result<response::ClientList, Error>>
   where
     N: Borrow<UpsName>,
@@ -0,0 +1,229 @@
+use crate::{
+  CmdName, UpsName, VarName, command,
+  error::{Error, ErrorKind},
+  internal::{Deserialize, Serialize, lexer::Lexer},
+  response,
+};
+use core::marker::PhantomData;
+
+/// A list of read-only commands sent back to back over a single connection.
+///
+/// Each added command returns a [`BatchHandle`], which can be used to take its typed reply from
+/// [`BatchResults`] after the batch is sent.
+///
+/// ```ignore
+/// let mut batch = Batch::new();
+/// let status = batch.get_var(&ups_name, VarName::UPS_STATUS);
+/// let clients = batch.list_client(&ups_name);
+///
+/// let mut results = client.send_batch(&batch).await?;
+/// let status = results.take(status)?;
+/// let clients = results.take(clients)?;
+/// ```
+#[derive(Default)]
+pub struct Batch<'a> {
+  requests: Vec<BatchRequest<'a>>,
+}
+
+/// Typed reference to a single command in a [`Batch`].
+#[derive(Debug)]
+pub struct BatchHandle<T> {
+  index: usize,
+  _marker: PhantomData<fn() -> T>,
+}
+
+/// Replies of a sent [`Batch`], in the same order as the commands.
+#[derive(Debug, Default)]
+pub struct BatchResults {
+  items: Vec<Option<Result<BatchResponse, Error>>>,
+}
+
+/// Sealed trait for response types that can be taken from [`BatchResults`].
+pub trait BatchItem: Sized {
+  #[doc(hidden)]
+  fn from_batch_response(response: BatchResponse) -> Option<Self>;
+}
+
+macro_rules! impl_batch_commands {
+  ($( $(#[$doc:meta])* $fn:ident => $variant:ident { $($field:ident : $type:ty),* } => $response:ident; )+) => {
+    enum BatchRequest<'a> {
+      $( $variant { $($field: &'a $type),* }, )+
+    }
+
+    #[doc(hidden)]
+    #[derive(Debug)]
+    pub enum BatchResponse {
+      $( $variant(response::$response), )+
+    }
+
+    impl BatchRequest<'_> {
+      fn serialize_into(&self, buffer: &mut String) {
+        match self {
+          $(
+            Self::$variant { $($field),* } => {
+              buffer.push_str(command::$variant { $($field),* }.serialize().as_ref())
+            }
+          )+
+        }
+      }
+
+      fn deserialize(&self, lexer: &mut Lexer) -> Result<BatchResponse, Error> {
+        match self {
+          $(
+            Self::$variant { .. } => {
+              response::$response::deserialize(lexer).map(BatchResponse::$variant)
+            }
+          )+
+        }
+      }
+    }
+
+    $(
+      impl BatchItem for response::$response {
+        fn from_batch_response(response: BatchResponse) -> Option<Self> {
+          match response {
+            BatchResponse::$variant(value) => Some(value),
+            _ => None,
+          }
+        }
+      }
+    )+
+
+    impl<'a> Batch<'a> {
+      $(
+        $(#[$doc])*
+        pub fn $fn(&mut self $(, $field: &'a $type)*) -> BatchHandle<response::$response> {
+          self.push(BatchRequest::$variant { $($field),* })
+        }
+      )+
+    }
+  };
+}
+
+impl_batch_commands! {
+  /// Adds `GET CMDDESC` command to the batch.
+  get_cmd_desc => GetCmdDesc { ups: UpsName, cmd: CmdName } => CmdDesc;
+  /// Adds `GET NUMLOGINS` command to the batch.
+  get_num_logins => GetNumLogins { ups: UpsName } => NumLogins;
+  /// Adds `GET UPSDESC` command to the batch.
+  get_ups_desc => GetUpsDesc { ups: UpsName } => UpsDesc;
+  /// Adds `GET VAR` command to the batch.
+  get_var => GetVar { ups: UpsName, var: VarName } => UpsVar;
+  /// Adds `GET DESC` command to the batch.
+  get_var_desc => GetVarDesc { ups: UpsName, var: VarName } => UpsVarDesc;
+  /// Adds `GET TYPE` command to the batch.
+  get_var_type => GetVarType { ups: UpsName, var: VarName } => UpsVarType;
+  /// Adds `LIST CLIENT` command to the batch.
+  list_client => ListClient { ups: UpsName } => ClientList;
+  /// Adds `LIST CMD` command to the batch.
+  list_cmd => ListCmd { ups: UpsName } => CmdList;
+  /// Adds `LIST ENUM` command to the batch.
+  list_enum => ListEnum { ups: UpsName, var: VarName } => EnumList;
+  /// Adds `LIST RANGE` command to the batch.
+  list_range => ListRange { ups: UpsName, var: VarName } => RangeList;
+  /// Adds `LIST RW` command to the batch.
+  list_rw => ListRw { ups: UpsName } => RwList;
+  /// Adds `LIST UPS` command to the batch.
+  list_ups => ListUps {} => UpsList;
+  /// Adds `LIST VAR` command to the batch.
+  list_var => ListVar { ups: UpsName } => UpsVarList;
+}
+
+impl<'a> Batch<'a> {
+  #[inline]
+  pub fn new() -> Self {
+    Self {
+      requests: Vec::new(),
+    }
+  }
+
+  #[inline]
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self {
+      requests: Vec::with_capacity(capacity),
+    }
+  }
+
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.requests.len()
+  }
+
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.requests.is_empty()
+  }
+
+  fn push<T>(&mut self, request: BatchRequest<'a>) -> BatchHandle<T> {
+    let index = self.requests.len();
+    self.requests.push(request);
+
+    BatchHandle {
+      index,
+      _marker: PhantomData,
+    }
+  }
+
+  /// Serializes all commands into a single buffer. Returned offsets mark the end of each command.
+  pub(super) fn serialize(&self) -> (String, Vec<usize>) {
+    let mut buffer = String::new();
+    let mut offsets = Vec::with_capacity(self.requests.len());
+
+    for request in self.requests.iter() {
+      request.serialize_into(&mut buffer);
+      offsets.push(buffer.len());
+    }
+
+    (buffer, offsets)
+  }
+
+  pub(super) fn deserialize_at(
+    &self,
+    index: usize,
+    lexer: &mut Lexer,
+  ) -> Result<BatchResponse, Error> {
+    self.requests[index].deserialize(lexer)
+  }
+}
+
+impl BatchResults {
+  #[inline]
+  pub(super) fn with_capacity(capacity: usize) -> Self {
+    Self {
+      items: Vec::with_capacity(capacity),
+    }
+  }
+
+  #[inline]
+  pub(super) fn push(&mut self, item: Result<BatchResponse, Error>) {
+    self.items.push(Some(item));
+  }
+
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.items.len()
+  }
+
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.items.is_empty()
+  }
+
+  /// Takes the reply of a batched command.
+  ///
+  /// Protocol errors (e.g. `ERR VAR-NOT-SUPPORTED`) and parse errors are reported per command
+  /// and do not affect the other replies. Handles from a different batch return
+  /// [`ErrorKind::EmptyResponse`].
+  pub fn take<T>(&mut self, handle: BatchHandle<T>) -> Result<T, Error>
+  where
+    T: BatchItem,
+  {
+    match self.items.get_mut(handle.index).and_then(Option::take) {
+      Some(Ok(response)) => {
+        T::from_batch_response(response).ok_or_else(|| ErrorKind::EmptyResponse.into())
+      }
+      Some(Err(err)) => Err(err),
+      None => Err(ErrorKind::EmptyResponse.into()),
+    }
+  }
+}
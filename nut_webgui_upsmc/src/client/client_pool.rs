@@ -1,20 +1,34 @@
 use super::AsyncNutClient;
 use crate::{
   CmdName, UpsName, VarName,
-  client::NutClient,
+  client::{Batch, BatchResults, NutClient, ProtVerCommand, Proxy, SessionRecorder},
   error::{Error, ErrorKind},
-  internal::item_pool::{ItemAllocator, ItemPool, ItemPoolError, ItemState},
+  internal::item_pool::{
+    ItemAllocator, ItemPool, ItemPoolError, ItemPoolPolicy, ItemState, PoolStats,
+  },
   response,
 };
-use core::num::NonZeroUsize;
 use core::time::Duration;
+use core::{
+  num::NonZeroUsize,
+  ops::{Deref, DerefMut},
+};
 use std::{
   net::{SocketAddr, ToSocketAddrs},
   pin::Pin,
-  sync::Arc,
+  sync::{
+    Arc, Mutex,
+    atomic::{AtomicU8, AtomicUsize, Ordering},
+  },
+  time::SystemTime,
+};
+use tokio::{
+  io::{AsyncRead, AsyncWrite},
+  time::Instant,
 };
-use tokio::io::{AsyncRead, AsyncWrite};
-use tracing::warn;
+use tracing::{error, info, warn};
+
+const DEFAULT_FAILBACK_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Debug)]
 pub enum ServerAddr {
@@ -33,6 +47,15 @@ impl ToSocketAddrs for ServerAddr {
   }
 }
 
+impl core::fmt::Display for ServerAddr {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      ServerAddr::SocketAddr(socket_addr) => core::fmt::Display::fmt(socket_addr, f),
+      ServerAddr::Host(host) => f.write_str(host),
+    }
+  }
+}
+
 impl From<String> for ServerAddr {
   #[inline]
   fn from(value: String) -> Self {
@@ -57,45 +80,347 @@ impl From<ItemPoolError<Error>> for Error {
   }
 }
 
-#[cfg(feature = "rustls")]
+/// A single upsd address used by [NutPoolClient].
+#[derive(Debug)]
+pub struct Endpoint {
+  addr: ServerAddr,
+
+  #[cfg(feature = "rustls")]
+  srv_name: Option<tokio_rustls::rustls::pki_types::ServerName<'static>>,
+}
+
+impl Endpoint {
+  #[inline]
+  pub fn new(addr: ServerAddr) -> Self {
+    Self {
+      addr,
+
+      #[cfg(feature = "rustls")]
+      srv_name: None,
+    }
+  }
+
+  /// Sets the TLS server name for the endpoint. Endpoints without a server name use the primary
+  /// endpoint's server name.
+  #[cfg(feature = "rustls")]
+  #[inline]
+  pub fn with_server_name(
+    mut self,
+    srv_name: tokio_rustls::rustls::pki_types::ServerName<'static>,
+  ) -> Self {
+    self.srv_name = Some(srv_name);
+    self
+  }
+}
+
+impl From<ServerAddr> for Endpoint {
+  #[inline]
+  fn from(value: ServerAddr) -> Self {
+    Self::new(value)
+  }
+}
+
+/// Active endpoint details of a [NutPoolClient].
+#[derive(Debug, Clone)]
+pub struct EndpointStatus {
+  /// Endpoint priority index. `0` is the primary endpoint.
+  pub index: usize,
+
+  /// Endpoint address.
+  pub addr: Box<str>,
+
+  /// Last time the pool switched to another endpoint.
+  pub last_failover: Option<SystemTime>,
+}
+
+#[derive(Debug)]
+struct FailoverState {
+  active: AtomicUsize,
+  last_failover: Mutex<Option<SystemTime>>,
+  last_failback_attempt: Mutex<Option<Instant>>,
+  endpoints: Box<[Box<str>]>,
+}
+
+impl FailoverState {
+  fn new(endpoints: &[Endpoint]) -> Self {
+    Self {
+      active: AtomicUsize::new(0),
+      last_failover: Mutex::new(None),
+      last_failback_attempt: Mutex::new(None),
+      endpoints: endpoints
+        .iter()
+        .map(|endpoint| endpoint.addr.to_string().into_boxed_str())
+        .collect(),
+    }
+  }
+
+  #[inline]
+  fn active(&self) -> usize {
+    self.active.load(Ordering::Acquire)
+  }
+
+  fn set_active(&self, index: usize) {
+    let previous = self.active.swap(index, Ordering::AcqRel);
+
+    if previous != index {
+      if let Ok(mut last_failover) = self.last_failover.lock() {
+        *last_failover = Some(SystemTime::now());
+      }
+
+      if index > previous {
+        self.mark_failback_attempt(Instant::now());
+
+        warn!(
+          message = "upsd endpoint failed over",
+          from = %self.endpoints[previous],
+          to = %self.endpoints[index]
+        );
+      } else {
+        info!(
+          message = "upsd endpoint failed back",
+          from = %self.endpoints[previous],
+          to = %self.endpoints[index]
+        );
+      }
+    }
+  }
+
+  #[inline]
+  fn mark_failback_attempt(&self, now: Instant) {
+    if let Ok(mut last_attempt) = self.last_failback_attempt.lock() {
+      *last_attempt = Some(now);
+    }
+  }
+
+  /// Returns `true` at most once per `interval` while a fallback endpoint is active, allowing
+  /// a single allocation to retry higher priority endpoints.
+  fn try_failback(&self, interval: Duration) -> bool {
+    let now = Instant::now();
+
+    match self.last_failback_attempt.lock() {
+      Ok(mut last_attempt) => {
+        if last_attempt.is_none_or(|v| now.duration_since(v) >= interval) {
+          *last_attempt = Some(now);
+          true
+        } else {
+          false
+        }
+      }
+      Err(_) => true,
+    }
+  }
+
+  fn status(&self) -> EndpointStatus {
+    let index = self.active();
+
+    EndpointStatus {
+      index,
+      addr: self.endpoints[index].clone(),
+      last_failover: self.last_failover.lock().ok().and_then(|v| *v),
+    }
+  }
+}
+
+/// Transport security negotiated with upsd.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NegotiatedTls {
+  /// No connection has been established yet.
+  Pending,
+
+  /// Connections are upgraded with `STARTTLS`.
+  Tls,
+
+  /// Connections are plaintext, either TLS is not configured on the client, or server does not
+  /// support it in prefer mode.
+  Plaintext,
+}
+
+impl NegotiatedTls {
+  pub const fn as_str(&self) -> &'static str {
+    match self {
+      Self::Pending => "pending",
+      Self::Tls => "tls",
+      Self::Plaintext => "plaintext",
+    }
+  }
+
+  const fn from_u8(value: u8) -> Self {
+    match value {
+      1 => Self::Tls,
+      2 => Self::Plaintext,
+      _ => Self::Pending,
+    }
+  }
+}
+
+impl core::fmt::Display for NegotiatedTls {
+  #[inline]
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for NegotiatedTls {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    serializer.serialize_str(self.as_str())
+  }
+}
+
 #[derive(Debug)]
-struct TlsConfig {
-  srv_name: tokio_rustls::rustls::pki_types::ServerName<'static>,
-  config: Arc<tokio_rustls::rustls::ClientConfig>,
+struct TlsState {
+  negotiated: AtomicU8,
+}
+
+impl TlsState {
+  const fn new() -> Self {
+    Self {
+      negotiated: AtomicU8::new(NegotiatedTls::Pending as u8),
+    }
+  }
+
+  #[inline]
+  fn get(&self) -> NegotiatedTls {
+    NegotiatedTls::from_u8(self.negotiated.load(Ordering::Acquire))
+  }
+
+  #[cfg(feature = "rustls")]
+  #[inline]
+  fn set_tls(&self) {
+    self
+      .negotiated
+      .store(NegotiatedTls::Tls as u8, Ordering::Release);
+  }
+
+  /// Returns `false` if TLS is already negotiated before, and plaintext would be a downgrade.
+  #[inline]
+  fn set_plaintext(&self) -> bool {
+    match self.negotiated.compare_exchange(
+      NegotiatedTls::Pending as u8,
+      NegotiatedTls::Plaintext as u8,
+      Ordering::AcqRel,
+      Ordering::Acquire,
+    ) {
+      Ok(_) => true,
+      Err(current) => NegotiatedTls::from_u8(current) == NegotiatedTls::Plaintext,
+    }
+  }
+}
+
+/// Pooled connection with its endpoint details.
+struct PooledClient {
+  inner: NutClient<Box<dyn ClientStream>>,
+  endpoint: usize,
+  connected_at: Instant,
+}
+
+impl Deref for PooledClient {
+  type Target = NutClient<Box<dyn ClientStream>>;
+
+  #[inline]
+  fn deref(&self) -> &Self::Target {
+    &self.inner
+  }
+}
+
+impl DerefMut for PooledClient {
+  #[inline]
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.inner
+  }
 }
 
 #[derive(Debug)]
 struct ClientAllocator {
-  addr: Arc<ServerAddr>,
+  endpoints: Vec<Endpoint>,
+  failover: Arc<FailoverState>,
+  failback_interval: Duration,
   timeout: Option<Duration>,
+  connect_timeout: Option<Duration>,
+  tls_state: Arc<TlsState>,
+  recorder: Option<SessionRecorder>,
+  proxy: Option<Proxy>,
+
+  #[cfg(feature = "rustls")]
+  tls_config: Option<Arc<tokio_rustls::rustls::ClientConfig>>,
 
   #[cfg(feature = "rustls")]
-  tls_config: Option<TlsConfig>,
+  tls_prefer: bool,
+}
+
+/// Details of a completed pooled command.
+#[derive(Debug, Clone, Copy)]
+pub struct CommandEvent<'a> {
+  /// Client method name, e.g. `get_var`, `list_var` or `send_batch`.
+  pub command: &'static str,
+
+  /// Time spent on the command, excluding the connection checkout.
+  pub duration: Duration,
+
+  /// Error kind, if the command failed.
+  pub error: Option<&'a ErrorKind>,
+}
+
+/// Instrumentation hook called after every command sent through [NutPoolClient]. Retried
+/// commands are reported once per attempt.
+pub trait CommandObserver: Send + Sync {
+  fn on_command(&self, event: CommandEvent<'_>);
 }
 
 pub struct NutPoolClientBuilder {
-  allocator: ClientAllocator,
+  endpoints: Vec<Endpoint>,
+  failback_interval: Duration,
+  timeout: Option<Duration>,
+  connect_timeout: Option<Duration>,
+  recorder: Option<SessionRecorder>,
+  proxy: Option<Proxy>,
   limit: NonZeroUsize,
+  policy: ItemPoolPolicy,
+  observer: Option<Arc<dyn CommandObserver>>,
+
+  #[cfg(feature = "rustls")]
+  tls_config: Option<Arc<tokio_rustls::rustls::ClientConfig>>,
+
+  #[cfg(feature = "rustls")]
+  tls_prefer: bool,
 }
 
 impl NutPoolClientBuilder {
   #[inline]
   pub fn new(addr: ServerAddr) -> Self {
     Self {
+      endpoints: vec![Endpoint::new(addr)],
+      failback_interval: DEFAULT_FAILBACK_INTERVAL,
+      timeout: None,
+      connect_timeout: None,
+      recorder: None,
+      proxy: None,
       limit: NonZeroUsize::new(1).unwrap(),
-      allocator: ClientAllocator {
-        addr: Arc::new(addr),
-        timeout: None,
+      policy: ItemPoolPolicy::default(),
+      observer: None,
+
+      #[cfg(feature = "rustls")]
+      tls_config: None,
 
-        #[cfg(feature = "rustls")]
-        tls_config: None,
-      },
+      #[cfg(feature = "rustls")]
+      tls_prefer: false,
     }
   }
 
   #[inline]
   pub const fn with_timeout(mut self, timeout: Duration) -> Self {
-    self.allocator.timeout = Some(timeout);
+    self.timeout = Some(timeout);
+    self
+  }
+
+  /// Limits the time spent on opening a connection, including proxy handshake and `STARTTLS`.
+  /// Connection attempts exceeding it fail with [std::io::ErrorKind::TimedOut].
+  #[inline]
+  pub const fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+    self.connect_timeout = Some(timeout);
     self
   }
 
@@ -105,6 +430,77 @@ impl NutPoolClientBuilder {
     self
   }
 
+  /// Skips the `NETVER` connection check for connections used within the window. Default is
+  /// zero.
+  ///
+  /// The check only runs for checked checkouts, i.e. [NutPoolClient::get_client()] and the retry
+  /// after a failed pooled call. Regular pooled calls take the first idle connection unchecked
+  /// and rely on the retry to replace a broken one. Connections past `max_lifetime` or
+  /// `idle_timeout` are discarded on both paths before they're handed out.
+  #[inline]
+  pub const fn with_validation_window(mut self, window: Duration) -> Self {
+    self.policy.validation_window = window;
+    self
+  }
+
+  /// Closes connections older than `max_lifetime` instead of reusing them. Expired connections
+  /// are never handed out, regardless of the validation window.
+  #[inline]
+  pub const fn with_max_lifetime(mut self, max_lifetime: Duration) -> Self {
+    self.policy.max_lifetime = Some(max_lifetime);
+    self
+  }
+
+  /// Closes connections that stay idle in the pool longer than `idle_timeout`. Like
+  /// `max_lifetime`, it's applied to both checked and unchecked checkouts.
+  #[inline]
+  pub const fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+    self.policy.idle_timeout = Some(idle_timeout);
+    self
+  }
+
+  /// Adds a lower priority endpoint. Pool fails over to the next available endpoint in the
+  /// order they're added, when the primary endpoint is unreachable.
+  #[inline]
+  pub fn with_fallback<E>(mut self, endpoint: E) -> Self
+  where
+    E: Into<Endpoint>,
+  {
+    self.endpoints.push(endpoint.into());
+    self
+  }
+
+  /// Connections to fallback endpoints are retired after this interval, so the pool can try
+  /// higher priority endpoints again. While a fallback endpoint is active, higher priority
+  /// endpoints are retried at most once per interval. Default is 60 seconds.
+  #[inline]
+  pub const fn with_failback_interval(mut self, interval: Duration) -> Self {
+    self.failback_interval = interval;
+    self
+  }
+
+  #[inline]
+  pub fn with_observer(mut self, observer: Arc<dyn CommandObserver>) -> Self {
+    self.observer = Some(observer);
+    self
+  }
+
+  /// Connects all endpoints through the proxy. `STARTTLS` is still negotiated with upsd, so the
+  /// proxy only sees encrypted traffic when TLS is enabled.
+  #[inline]
+  pub fn with_proxy(mut self, proxy: Proxy) -> Self {
+    self.proxy = Some(proxy);
+    self
+  }
+
+  /// Records traffic of every pooled connection. Connections are recorded after `STARTTLS`, so
+  /// recordings are in plaintext even when TLS is used.
+  #[inline]
+  pub fn with_recorder(mut self, recorder: SessionRecorder) -> Self {
+    self.recorder = Some(recorder);
+    self
+  }
+
   #[cfg(feature = "rustls")]
   #[inline]
   pub fn with_tls(
@@ -112,14 +508,54 @@ impl NutPoolClientBuilder {
     srv_name: tokio_rustls::rustls::pki_types::ServerName<'static>,
     config: Arc<tokio_rustls::rustls::ClientConfig>,
   ) -> Self {
-    self.allocator.tls_config = Some(TlsConfig { srv_name, config });
+    self.endpoints[0].srv_name = Some(srv_name);
+    self.tls_config = Some(config);
+    self.tls_prefer = false;
+    self
+  }
+
+  /// Opportunistic TLS. Connections fall back to plaintext only when the server answers
+  /// `STARTTLS` with `ERR FEATURE-NOT-CONFIGURED`. Once TLS is negotiated, later plaintext
+  /// fallbacks are refused as a possible downgrade.
+  #[cfg(feature = "rustls")]
+  #[inline]
+  pub fn with_prefer_tls(
+    mut self,
+    srv_name: tokio_rustls::rustls::pki_types::ServerName<'static>,
+    config: Arc<tokio_rustls::rustls::ClientConfig>,
+  ) -> Self {
+    self.endpoints[0].srv_name = Some(srv_name);
+    self.tls_config = Some(config);
+    self.tls_prefer = true;
     self
   }
 
   #[inline]
   pub fn build(self) -> NutPoolClient {
+    let failover = Arc::new(FailoverState::new(&self.endpoints));
+    let tls_state = Arc::new(TlsState::new());
+    let allocator = ClientAllocator {
+      endpoints: self.endpoints,
+      failover: failover.clone(),
+      failback_interval: self.failback_interval,
+      timeout: self.timeout,
+      connect_timeout: self.connect_timeout,
+      tls_state: tls_state.clone(),
+      recorder: self.recorder,
+      proxy: self.proxy,
+
+      #[cfg(feature = "rustls")]
+      tls_config: self.tls_config,
+
+      #[cfg(feature = "rustls")]
+      tls_prefer: self.tls_prefer,
+    };
+
     NutPoolClient {
-      pool: ItemPool::new(self.limit, self.allocator),
+      tls_state,
+      pool: ItemPool::new(self.limit, allocator, self.policy),
+      observer: self.observer,
+      failover,
     }
   }
 }
@@ -127,69 +563,196 @@ impl NutPoolClientBuilder {
 pub trait ClientStream: AsyncRead + AsyncWrite + Send + Sync + Unpin {}
 impl<A> ClientStream for A where A: AsyncWrite + AsyncRead + Send + Sync + Unpin {}
 
+impl ClientAllocator {
+  async fn connect_with_timeout(
+    &self,
+    endpoint: &Endpoint,
+  ) -> Result<NutClient<Box<dyn ClientStream>>, Error> {
+    match self.connect_timeout {
+      Some(connect_timeout) => tokio::time::timeout(connect_timeout, self.connect(endpoint))
+        .await
+        .unwrap_or_else(|_| Err(std::io::ErrorKind::TimedOut.into())),
+      None => self.connect(endpoint).await,
+    }
+  }
+
+  async fn connect(&self, endpoint: &Endpoint) -> Result<NutClient<Box<dyn ClientStream>>, Error> {
+    let client = match &self.proxy {
+      Some(proxy) => NutClient::new(proxy.connect(&endpoint.addr).await?),
+      None => {
+        // Resolved with tokio, blocking getaddrinfo would not be interrupted by connect_timeout.
+        let addr_resolutions: Vec<_> = match &endpoint.addr {
+          ServerAddr::SocketAddr(socket_addr) => vec![*socket_addr],
+          ServerAddr::Host(host) => tokio::net::lookup_host(host.as_str()).await?.collect(),
+        };
+
+        NutClient::connect(addr_resolutions.as_slice()).await?
+      }
+    };
+
+    #[cfg(feature = "rustls")]
+    let connection: Box<dyn ClientStream> = {
+      let srv_name = endpoint
+        .srv_name
+        .as_ref()
+        .or(self.endpoints[0].srv_name.as_ref());
+
+      if let (Some(config), Some(srv_name)) = (&self.tls_config, srv_name) {
+        self.start_tls(endpoint, client, srv_name, config).await?
+      } else {
+        self.plaintext(endpoint, client).await?
+      }
+    };
+
+    #[cfg(not(feature = "rustls"))]
+    let connection: Box<dyn ClientStream> = self.plaintext(endpoint, client).await?;
+
+    let connection: Box<dyn ClientStream> = match &self.recorder {
+      Some(recorder) => Box::new(recorder.wrap(connection)),
+      None => connection,
+    };
+
+    let mut client = NutClient::from(connection);
+
+    if let Some(timeout) = self.timeout {
+      client.set_timeout(timeout);
+    }
+
+    Ok(client)
+  }
+
+  #[cfg(feature = "rustls")]
+  async fn start_tls(
+    &self,
+    endpoint: &Endpoint,
+    mut client: NutClient<tokio::net::TcpStream>,
+    srv_name: &tokio_rustls::rustls::pki_types::ServerName<'static>,
+    config: &Arc<tokio_rustls::rustls::ClientConfig>,
+  ) -> Result<Box<dyn ClientStream>, Error> {
+    use crate::{client::client_tls::TlsNegotiation, error::ProtocolError};
+
+    if let Some(timeout) = self.timeout {
+      client.set_timeout(timeout);
+    }
+
+    match client.start_tls(srv_name.clone(), config.clone()).await? {
+      TlsNegotiation::Enabled(client) => {
+        self.tls_state.set_tls();
+        Ok(Box::new(client.into_inner()))
+      }
+      TlsNegotiation::NotConfigured(client) if self.tls_prefer => {
+        self.plaintext(endpoint, client).await
+      }
+      TlsNegotiation::NotConfigured(client) => {
+        _ = client.close().await;
+        Err(ProtocolError::FeatureNotConfigured.into())
+      }
+    }
+  }
+
+  async fn plaintext(
+    &self,
+    endpoint: &Endpoint,
+    client: NutClient<tokio::net::TcpStream>,
+  ) -> Result<Box<dyn ClientStream>, Error> {
+    if self.tls_state.set_plaintext() {
+      Ok(Box::new(client.into_inner()))
+    } else {
+      error!(
+        message = "upsd refused TLS after it was negotiated before, possible downgrade attack",
+        endpoint = %endpoint.addr
+      );
+
+      _ = client.close().await;
+      Err(ErrorKind::TlsDowngradeRefused.into())
+    }
+  }
+
+  /// Endpoint indexes in connection order. Allocations start with the last good endpoint, so a
+  /// dead primary endpoint is not tried on every allocation. Higher priority endpoints are retried
+  /// once per failback interval.
+  fn connect_order(&self) -> Vec<usize> {
+    let active = self.failover.active();
+
+    if active == 0 || self.failover.try_failback(self.failback_interval) {
+      (0..self.endpoints.len()).collect()
+    } else {
+      core::iter::once(active)
+        .chain((0..self.endpoints.len()).filter(|index| *index != active))
+        .collect()
+    }
+  }
+
+  /// Connections to lower priority endpoints are retired once a higher priority endpoint is
+  /// active again, or after the failback interval to give higher priority endpoints a chance.
+  #[inline]
+  fn is_retired(&self, item: &PooledClient) -> bool {
+    item.endpoint > 0
+      && (item.endpoint > self.failover.active()
+        || item.connected_at.elapsed() >= self.failback_interval)
+  }
+}
+
 impl ItemAllocator for ClientAllocator {
-  type Item = NutClient<Box<dyn ClientStream>>;
+  type Item = PooledClient;
   type Error = Error;
 
   fn init(&self) -> Pin<Box<dyn Future<Output = Result<Self::Item, Self::Error>> + Send + '_>> {
     Box::pin(async move {
-      let addr_resolutions: Vec<_> = self.addr.to_socket_addrs()?.collect();
-      let connection: Box<dyn ClientStream> = {
-        #[cfg(feature = "rustls")]
-        {
-          if let Some(tls_config) = &self.tls_config {
-            Box::new(
-              NutClient::connect_with_tls(
-                addr_resolutions.as_slice(),
-                tls_config.srv_name.clone(),
-                tls_config.config.clone(),
-              )
-              .await?
-              .into_inner(),
-            )
-          } else {
-            Box::new(
-              NutClient::connect(addr_resolutions.as_slice())
-                .await?
-                .into_inner(),
-            )
-          }
-        }
+      let mut last_err = None;
 
-        #[cfg(not(feature = "rustls"))]
-        {
-          Box::new(
-            NutClient::connect(addr_resolutions.as_slice())
-              .await?
-              .into_inner(),
-          )
-        }
-      };
+      for index in self.connect_order() {
+        let endpoint = &self.endpoints[index];
 
-      let mut client = NutClient::from(connection);
+        match self.connect_with_timeout(endpoint).await {
+          Ok(client) => {
+            self.failover.set_active(index);
 
-      if let Some(timeout) = self.timeout {
-        client.set_timeout(timeout);
+            return Ok(PooledClient {
+              inner: client,
+              endpoint: index,
+              connected_at: Instant::now(),
+            });
+          }
+          Err(err) => {
+            if self.endpoints.len() > 1 {
+              warn!(
+                message = "unable to connect upsd endpoint",
+                endpoint = %endpoint.addr,
+                reason = %err
+              );
+            }
+
+            last_err = Some(err);
+          }
+        }
       }
 
-      Ok(client)
+      Err(last_err.unwrap_or_else(|| ErrorKind::EmptyResponse.into()))
     })
   }
 
   fn dealloc(&self, item: Self::Item) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
     Box::pin(async move {
-      if let Err(err) = item.close().await {
+      if let Err(err) = item.inner.close().await {
         warn!(message = "unable to close a tcp connection in pool", error = %err);
       }
     })
   }
 
+  #[inline]
+  fn is_recyclable(&self, item: &Self::Item) -> bool {
+    !item.is_poisoned() && !self.is_retired(item)
+  }
+
   fn prealloc_check(
     &self,
     mut item: Self::Item,
   ) -> Pin<Box<dyn Future<Output = ItemState<Self::Item>> + Send + '_>> {
     Box::pin(async move {
-      if item.is_open().await {
+      if item.is_poisoned() || self.is_retired(&item) {
+        ItemState::Destroy(item)
+      } else if item.is_open().await {
         ItemState::Ready(item)
       } else {
         ItemState::Destroy(item)
@@ -200,6 +763,9 @@ impl ItemAllocator for ClientAllocator {
 
 pub struct NutPoolClient {
   pool: ItemPool<ClientAllocator>,
+  observer: Option<Arc<dyn CommandObserver>>,
+  failover: Arc<FailoverState>,
+  tls_state: Arc<TlsState>,
 }
 
 impl Clone for NutPoolClient {
@@ -207,11 +773,32 @@ impl Clone for NutPoolClient {
   fn clone(&self) -> Self {
     Self {
       pool: self.pool.clone(),
+      observer: self.observer.clone(),
+      failover: self.failover.clone(),
+      tls_state: self.tls_state.clone(),
     }
   }
 }
 
 impl NutPoolClient {
+  /// Returns the currently active endpoint.
+  #[inline]
+  pub fn active_endpoint(&self) -> EndpointStatus {
+    self.failover.status()
+  }
+
+  /// Returns the transport security negotiated by the latest connection.
+  #[inline]
+  pub fn negotiated_tls(&self) -> NegotiatedTls {
+    self.tls_state.get()
+  }
+
+  /// Returns a snapshot of the connection pool statistics.
+  #[inline]
+  pub fn stats(&self) -> PoolStats {
+    self.pool.stats()
+  }
+
   #[inline]
   pub fn close(self) -> impl Future<Output = ()> {
     self.pool.close()
@@ -224,28 +811,28 @@ impl NutPoolClient {
 
   pub async fn get_client(&self) -> Result<NutClient<Box<dyn ClientStream>>, ItemPoolError<Error>> {
     let client = self.pool.get_checked().await?.into_inner();
-    Ok(client)
+    Ok(client.inner)
   }
 }
 
 macro_rules! impl_pooled_call {
-  ($pool:expr, $fn:ident $( , $($args:expr),+ )?) => {{
-    let mut client = match $pool.get().await {
+  ($pool_client:expr, $fn:ident $( , $($args:expr),+ )?) => {{
+    let mut client = match $pool_client.pool.get().await {
       Ok(c) => c,
       Err(err) => {return Err(err.into());}
     };
 
-    match impl_pooled_call!(@action client, $fn $(, $($args),+)?) {
+    match impl_pooled_call!(@action $pool_client, client, $fn $(, $($args),+)?) {
       Ok(res) => Ok(res),
       Err(err) => {
         match err.kind() {
           ErrorKind::IOError { .. } | ErrorKind::EmptyResponse | ErrorKind::RequestTimeout => {
-            let mut client = match $pool.get_checked().await {
+            let mut client = match $pool_client.pool.get_checked().await {
               Ok(c) => c,
               Err(err) => {return Err(err.into());}
             };
 
-            impl_pooled_call!(@action client, $fn $(, $($args),+)?)
+            impl_pooled_call!(@action $pool_client, client, $fn $(, $($args),+)?)
           }
           _ => Err(err)
         }
@@ -253,25 +840,25 @@ macro_rules! impl_pooled_call {
     }
   }};
 
-  (@action $client:expr, $fn:ident $( , $($args:expr),+ )?) => {{
-    match $client.$fn($($($args),+)?).await {
-      Ok(result) => {
-        _ = $client.release().await;
-        Ok(result)
-      },
-      Err(err) => {
-        match err.kind() {
-          ErrorKind::IOError { .. } | ErrorKind::ConnectionPoolClosed | ErrorKind::EmptyResponse | ErrorKind::RequestTimeout => {
-          drop($client);
-        }
-          _ => {
-            _ = $client.release().await;
-          }
-        };
-
-        Err(err)
-      }
+  // Connections are always released back to the pool. Pool discards the poisoned connections
+  // (timed out, IO errors, partially read responses), and if the call itself is cancelled, the
+  // dropped guard closes the connection without recycling it.
+  (@action $pool_client:expr, $client:expr, $fn:ident $( , $($args:expr),+ )?) => {{
+    let started = Instant::now();
+    let result = $client.$fn($($($args),+)?).await;
+    let duration = started.elapsed();
+
+    $client.release().await;
+
+    if let Some(observer) = $pool_client.observer.as_deref() {
+      observer.on_command(CommandEvent {
+        command: stringify!($fn),
+        duration,
+        error: result.as_ref().err().map(|err| err.kind()),
+      });
     }
+
+    result
   }};
 }
 
@@ -281,18 +868,29 @@ impl AsyncNutClient for &NutPoolClient {
     N: std::borrow::Borrow<UpsName>,
     C: std::borrow::Borrow<CmdName>,
   {
-    impl_pooled_call!(self.pool, get_cmd_desc, ups.borrow(), cmd.borrow())
+    impl_pooled_call!(self, get_cmd_desc, ups.borrow(), cmd.borrow())
+  }
+
+  async fn get_num_logins<N>(self, ups: N) -> Result<response::NumLogins, Error>
+  where
+    N: std::borrow::Borrow<UpsName>,
+  {
+    impl_pooled_call!(self, get_num_logins, ups.borrow())
   }
 
   async fn get_protver(self) -> Result<response::ProtVer, Error> {
-    impl_pooled_call!(self.pool, get_protver)
+    impl_pooled_call!(self, get_protver)
+  }
+
+  async fn get_protver_with(self, command: ProtVerCommand) -> Result<response::ProtVer, Error> {
+    impl_pooled_call!(self, get_protver_with, command)
   }
 
   async fn get_ups_desc<N>(self, ups: N) -> Result<response::UpsDesc, Error>
   where
     N: std::borrow::Borrow<UpsName>,
   {
-    impl_pooled_call!(self.pool, get_ups_desc, ups.borrow())
+    impl_pooled_call!(self, get_ups_desc, ups.borrow())
   }
 
   async fn get_var<N, V>(self, ups: N, var: V) -> Result<response::UpsVar, Error>
@@ -300,7 +898,7 @@ impl AsyncNutClient for &NutPoolClient {
     N: std::borrow::Borrow<UpsName>,
     V: std::borrow::Borrow<VarName>,
   {
-    impl_pooled_call!(self.pool, get_var, ups.borrow(), var.borrow())
+    impl_pooled_call!(self, get_var, ups.borrow(), var.borrow())
   }
 
   async fn get_var_type<N, V>(self, ups: N, var: V) -> Result<response::UpsVarType, Error>
@@ -308,7 +906,7 @@ impl AsyncNutClient for &NutPoolClient {
     N: std::borrow::Borrow<UpsName>,
     V: std::borrow::Borrow<VarName>,
   {
-    impl_pooled_call!(self.pool, get_var_type, ups.borrow(), var.borrow())
+    impl_pooled_call!(self, get_var_type, ups.borrow(), var.borrow())
   }
 
   async fn get_var_desc<N, V>(self, ups: N, var: V) -> Result<response::UpsVarDesc, Error>
@@ -316,25 +914,29 @@ impl AsyncNutClient for &NutPoolClient {
     N: std::borrow::Borrow<UpsName>,
     V: std::borrow::Borrow<VarName>,
   {
-    impl_pooled_call!(self.pool, get_var_desc, ups.borrow(), var.borrow())
+    impl_pooled_call!(self, get_var_desc, ups.borrow(), var.borrow())
   }
 
   async fn get_ver(self) -> Result<response::DaemonVer, Error> {
-    impl_pooled_call!(self.pool, get_ver)
+    impl_pooled_call!(self, get_ver)
+  }
+
+  async fn help(self) -> Result<response::Help, Error> {
+    impl_pooled_call!(self, help)
   }
 
   async fn list_client<N>(self, ups: N) -> Result<response::ClientList, Error>
   where
     N: std::borrow::Borrow<UpsName>,
   {
-    impl_pooled_call!(self.pool, list_client, ups.borrow())
+    impl_pooled_call!(self, list_client, ups.borrow())
   }
 
   async fn list_cmd<N>(self, ups: N) -> Result<response::CmdList, Error>
   where
     N: std::borrow::Borrow<UpsName>,
   {
-    impl_pooled_call!(self.pool, list_cmd, ups.borrow())
+    impl_pooled_call!(self, list_cmd, ups.borrow())
   }
 
   async fn list_enum<N, V>(self, ups: N, var: V) -> Result<response::EnumList, Error>
@@ -342,7 +944,7 @@ impl AsyncNutClient for &NutPoolClient {
     N: std::borrow::Borrow<UpsName>,
     V: std::borrow::Borrow<VarName>,
   {
-    impl_pooled_call!(self.pool, list_enum, ups.borrow(), var.borrow())
+    impl_pooled_call!(self, list_enum, ups.borrow(), var.borrow())
   }
 
   async fn list_range<N, V>(self, ups: N, var: V) -> Result<response::RangeList, Error>
@@ -350,24 +952,31 @@ impl AsyncNutClient for &NutPoolClient {
     N: std::borrow::Borrow<UpsName>,
     V: std::borrow::Borrow<VarName>,
   {
-    impl_pooled_call!(self.pool, list_range, ups.borrow(), var.borrow())
+    impl_pooled_call!(self, list_range, ups.borrow(), var.borrow())
   }
 
   async fn list_rw<N>(self, ups: N) -> Result<response::RwList, Error>
   where
     N: std::borrow::Borrow<UpsName>,
   {
-    impl_pooled_call!(self.pool, list_rw, ups.borrow())
+    impl_pooled_call!(self, list_rw, ups.borrow())
   }
 
   async fn list_ups(self) -> Result<response::UpsList, Error> {
-    impl_pooled_call!(self.pool, list_ups)
+    impl_pooled_call!(self, list_ups)
   }
 
   async fn list_var<N>(self, ups: N) -> Result<response::UpsVarList, Error>
   where
     N: std::borrow::Borrow<UpsName>,
   {
-    impl_pooled_call!(self.pool, list_var, ups.borrow())
+    impl_pooled_call!(self, list_var, ups.borrow())
+  }
+}
+
+impl NutPoolClient {
+  /// Sends all commands in the batch over a single pooled connection.
+  pub async fn send_batch(&self, batch: &Batch<'_>) -> Result<BatchResults, Error> {
+    impl_pooled_call!(self, send_batch, batch)
   }
 }
@@ -0,0 +1,323 @@
+use crate::{
+  client::ServerAddr,
+  error::{Error, ProxyError},
+};
+use core::net::IpAddr;
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  net::TcpStream,
+};
+
+const SOCKS_VERSION: u8 = 0x05;
+const SOCKS_AUTH_VERSION: u8 = 0x01;
+const SOCKS_NO_AUTH: u8 = 0x00;
+const SOCKS_USER_PASS: u8 = 0x02;
+const SOCKS_NO_ACCEPTABLE: u8 = 0xFF;
+const SOCKS_CMD_CONNECT: u8 = 0x01;
+const SOCKS_ATYP_IPV4: u8 = 0x01;
+const SOCKS_ATYP_DOMAIN: u8 = 0x03;
+const SOCKS_ATYP_IPV6: u8 = 0x04;
+
+// Upper limit for HTTP CONNECT response headers.
+const HTTP_HEADER_LIMIT: usize = 8 * 1024;
+
+/// Proxy used for reaching upsd. Only the TCP tunnel is handled by the proxy, so `STARTTLS` is
+/// still negotiated end to end with upsd.
+#[derive(Clone, Debug)]
+pub enum Proxy {
+  /// SOCKS5 proxy ([RFC 1928](https://www.rfc-editor.org/rfc/rfc1928)). Hostnames are resolved by
+  /// the proxy.
+  Socks5 {
+    addr: Box<str>,
+    auth: Option<ProxyAuth>,
+  },
+
+  /// HTTP proxy with `CONNECT` method support. Credentials are sent with basic authentication.
+  HttpConnect {
+    addr: Box<str>,
+    auth: Option<ProxyAuth>,
+  },
+}
+
+/// Proxy username and password.
+#[derive(Clone)]
+pub struct ProxyAuth {
+  pub username: Box<str>,
+  pub password: Box<str>,
+}
+
+impl core::fmt::Debug for ProxyAuth {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("ProxyAuth")
+      .field("username", &self.username)
+      .field("password", &"******")
+      .finish()
+  }
+}
+
+enum TargetHost<'a> {
+  Ip(IpAddr),
+  Domain(&'a str),
+}
+
+impl Proxy {
+  /// SOCKS5 proxy at `addr`, e.g. `bastion:1080`.
+  #[inline]
+  pub fn socks5<A>(addr: A) -> Self
+  where
+    A: Into<Box<str>>,
+  {
+    Self::Socks5 {
+      addr: addr.into(),
+      auth: None,
+    }
+  }
+
+  /// HTTP CONNECT proxy at `addr`, e.g. `bastion:3128`.
+  #[inline]
+  pub fn http_connect<A>(addr: A) -> Self
+  where
+    A: Into<Box<str>>,
+  {
+    Self::HttpConnect {
+      addr: addr.into(),
+      auth: None,
+    }
+  }
+
+  pub fn with_auth<U, P>(mut self, username: U, password: P) -> Self
+  where
+    U: Into<Box<str>>,
+    P: Into<Box<str>>,
+  {
+    let new_auth = Some(ProxyAuth {
+      username: username.into(),
+      password: password.into(),
+    });
+
+    match &mut self {
+      Self::Socks5 { auth, .. } | Self::HttpConnect { auth, .. } => *auth = new_auth,
+    }
+
+    self
+  }
+
+  /// Proxy address.
+  #[inline]
+  pub fn addr(&self) -> &str {
+    match self {
+      Self::Socks5 { addr, .. } | Self::HttpConnect { addr, .. } => addr,
+    }
+  }
+
+  /// Opens a tunnel to `target` through the proxy.
+  pub async fn connect(&self, target: &ServerAddr) -> Result<TcpStream, Error> {
+    let (host, port) = split_target(target)?;
+    let mut stream = TcpStream::connect(self.addr()).await?;
+    stream.set_nodelay(true)?;
+
+    match self {
+      Self::Socks5 { auth, .. } => socks5_handshake(&mut stream, host, port, auth.as_ref()).await?,
+      Self::HttpConnect { auth, .. } => {
+        http_connect_handshake(&mut stream, host, port, auth.as_ref()).await?
+      }
+    }
+
+    Ok(stream)
+  }
+}
+
+fn split_target(target: &ServerAddr) -> Result<(TargetHost<'_>, u16), Error> {
+  match target {
+    ServerAddr::SocketAddr(addr) => Ok((TargetHost::Ip(addr.ip()), addr.port())),
+    ServerAddr::Host(addr) => {
+      let (host, port) = addr
+        .rsplit_once(':')
+        .ok_or(std::io::ErrorKind::InvalidInput)?;
+      let port = port
+        .parse::<u16>()
+        .map_err(|_| std::io::ErrorKind::InvalidInput)?;
+      let host = host.trim_start_matches('[').trim_end_matches(']');
+
+      match host.parse::<IpAddr>() {
+        Ok(ip) => Ok((TargetHost::Ip(ip), port)),
+        Err(_) => Ok((TargetHost::Domain(host), port)),
+      }
+    }
+  }
+}
+
+async fn socks5_handshake(
+  stream: &mut TcpStream,
+  host: TargetHost<'_>,
+  port: u16,
+  auth: Option<&ProxyAuth>,
+) -> Result<(), Error> {
+  let greeting: &[u8] = match auth {
+    Some(_) => &[SOCKS_VERSION, 2, SOCKS_NO_AUTH, SOCKS_USER_PASS],
+    None => &[SOCKS_VERSION, 1, SOCKS_NO_AUTH],
+  };
+
+  stream.write_all(greeting).await?;
+
+  let mut reply = [0u8; 2];
+  stream.read_exact(&mut reply).await?;
+
+  match reply {
+    [SOCKS_VERSION, SOCKS_NO_AUTH] => {}
+    [SOCKS_VERSION, SOCKS_USER_PASS] => match auth {
+      Some(auth) => socks5_authenticate(stream, auth).await?,
+      None => return Err(ProxyError::UnsupportedAuthMethod.into()),
+    },
+    [SOCKS_VERSION, SOCKS_NO_ACCEPTABLE] => return Err(ProxyError::UnsupportedAuthMethod.into()),
+    _ => return Err(ProxyError::InvalidResponse.into()),
+  }
+
+  let mut request = vec![SOCKS_VERSION, SOCKS_CMD_CONNECT, 0x00];
+
+  match host {
+    TargetHost::Ip(IpAddr::V4(ip)) => {
+      request.push(SOCKS_ATYP_IPV4);
+      request.extend_from_slice(&ip.octets());
+    }
+    TargetHost::Ip(IpAddr::V6(ip)) => {
+      request.push(SOCKS_ATYP_IPV6);
+      request.extend_from_slice(&ip.octets());
+    }
+    TargetHost::Domain(domain) => {
+      let len = u8::try_from(domain.len()).map_err(|_| std::io::ErrorKind::InvalidInput)?;
+      request.push(SOCKS_ATYP_DOMAIN);
+      request.push(len);
+      request.extend_from_slice(domain.as_bytes());
+    }
+  }
+
+  request.extend_from_slice(&port.to_be_bytes());
+  stream.write_all(&request).await?;
+
+  let mut reply = [0u8; 4];
+  stream.read_exact(&mut reply).await?;
+
+  match reply {
+    [SOCKS_VERSION, 0x00, _, _] => {}
+    [SOCKS_VERSION, code, _, _] => return Err(ProxyError::ConnectRejected { code }.into()),
+    _ => return Err(ProxyError::InvalidResponse.into()),
+  }
+
+  // Bound address is not used, but it still needs to be consumed.
+  let bound_len = match reply[3] {
+    SOCKS_ATYP_IPV4 => 4,
+    SOCKS_ATYP_IPV6 => 16,
+    SOCKS_ATYP_DOMAIN => stream.read_u8().await? as usize,
+    _ => return Err(ProxyError::InvalidResponse.into()),
+  };
+
+  let mut bound_addr = vec![0u8; bound_len + 2];
+  stream.read_exact(&mut bound_addr).await?;
+
+  Ok(())
+}
+
+async fn socks5_authenticate(stream: &mut TcpStream, auth: &ProxyAuth) -> Result<(), Error> {
+  let username = auth.username.as_bytes();
+  let password = auth.password.as_bytes();
+
+  let (Ok(username_len), Ok(password_len)) =
+    (u8::try_from(username.len()), u8::try_from(password.len()))
+  else {
+    return Err(std::io::ErrorKind::InvalidInput.into());
+  };
+
+  let mut request = Vec::with_capacity(3 + username.len() + password.len());
+  request.push(SOCKS_AUTH_VERSION);
+  request.push(username_len);
+  request.extend_from_slice(username);
+  request.push(password_len);
+  request.extend_from_slice(password);
+
+  stream.write_all(&request).await?;
+
+  let mut reply = [0u8; 2];
+  stream.read_exact(&mut reply).await?;
+
+  match reply {
+    [SOCKS_AUTH_VERSION, 0x00] => Ok(()),
+    _ => Err(ProxyError::AuthenticationFailed.into()),
+  }
+}
+
+async fn http_connect_handshake(
+  stream: &mut TcpStream,
+  host: TargetHost<'_>,
+  port: u16,
+  auth: Option<&ProxyAuth>,
+) -> Result<(), Error> {
+  let authority = match host {
+    TargetHost::Ip(IpAddr::V6(ip)) => format!("[{ip}]:{port}"),
+    TargetHost::Ip(IpAddr::V4(ip)) => format!("{ip}:{port}"),
+    TargetHost::Domain(domain) => format!("{domain}:{port}"),
+  };
+
+  let mut request = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n");
+
+  if let Some(auth) = auth {
+    let credentials = format!("{}:{}", auth.username, auth.password);
+    request.push_str("Proxy-Authorization: Basic ");
+    request.push_str(&base64_encode(credentials.as_bytes()));
+    request.push_str("\r\n");
+  }
+
+  request.push_str("\r\n");
+  stream.write_all(request.as_bytes()).await?;
+
+  // Reads byte by byte, so nothing past the response headers is consumed from the tunnel.
+  let mut response = Vec::new();
+
+  while !response.ends_with(b"\r\n\r\n") {
+    if response.len() >= HTTP_HEADER_LIMIT {
+      return Err(ProxyError::InvalidResponse.into());
+    }
+
+    response.push(stream.read_u8().await?);
+  }
+
+  let status = response
+    .split(|v| *v == b' ')
+    .nth(1)
+    .and_then(|v| core::str::from_utf8(v).ok())
+    .and_then(|v| v.parse::<u16>().ok());
+
+  match status {
+    _ if !response.starts_with(b"HTTP/1.") => Err(ProxyError::InvalidResponse.into()),
+    Some(200..=299) => Ok(()),
+    Some(407) => Err(ProxyError::AuthenticationFailed.into()),
+    Some(code) => Err(ProxyError::HttpStatus { code }.into()),
+    None => Err(ProxyError::InvalidResponse.into()),
+  }
+}
+
+fn base64_encode(input: &[u8]) -> String {
+  const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+  let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+
+  for chunk in input.chunks(3) {
+    let bytes = [
+      chunk[0],
+      chunk.get(1).copied().unwrap_or(0),
+      chunk.get(2).copied().unwrap_or(0),
+    ];
+    let group = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+
+    for idx in 0..4 {
+      if idx <= chunk.len() {
+        let sextet = (group >> (18 - idx * 6)) & 0x3F;
+        output.push(ALPHABET[sextet as usize] as char);
+      } else {
+        output.push('=');
+      }
+    }
+  }
+
+  output
+}
@@ -0,0 +1,236 @@
+use crate::{client::ClientStream, error::Error};
+use core::{
+  pin::Pin,
+  task::{Context, Poll},
+};
+use std::{
+  fs::File,
+  path::Path,
+  sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+  },
+};
+use tokio::{
+  io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufWriter, ReadBuf},
+  sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+};
+use tracing::warn;
+
+/// Recorded `PASSWORD` commands are replaced with this line.
+pub(super) const REDACTED_PASSWORD: &str = "PASSWORD ******";
+
+/// Fixture writer for upsd sessions.
+///
+/// Every connection wrapped by [`SessionRecorder::wrap`] gets its own session number, and each
+/// protocol line is written as `<session>> <line>` for requests and `<session>< <line>` for
+/// responses:
+///
+/// ```text
+/// 1> GET VAR ups ups.status
+/// 1< VAR ups ups.status "OL"
+/// ```
+///
+/// Passwords are redacted, so recordings can be attached to bug reports. Recordings can be read
+/// back with [`super::Fixture`].
+///
+/// Lines are queued to a background task which owns the buffered writer, so recording never blocks
+/// the wrapped connections. The writer is flushed whenever the queue is drained, and shut down once
+/// the recorder and all recorded streams are dropped.
+#[derive(Clone)]
+pub struct SessionRecorder {
+  inner: Arc<RecorderInner>,
+}
+
+struct RecorderInner {
+  lines: UnboundedSender<String>,
+  last_session: AtomicUsize,
+}
+
+impl SessionRecorder {
+  /// Starts the background writer task, must be called within a tokio runtime.
+  pub fn new<W>(writer: W) -> Self
+  where
+    W: AsyncWrite + Send + Unpin + 'static,
+  {
+    let (lines, receiver) = unbounded_channel();
+
+    tokio::spawn(write_lines(BufWriter::new(writer), receiver));
+
+    Self {
+      inner: Arc::new(RecorderInner {
+        lines,
+        last_session: AtomicUsize::new(0),
+      }),
+    }
+  }
+
+  /// Creates or truncates the fixture file at `path`, must be called within a tokio runtime.
+  pub fn create<P>(path: P) -> Result<Self, Error>
+  where
+    P: AsRef<Path>,
+  {
+    let file = File::create(path)?;
+    Ok(Self::new(tokio::fs::File::from_std(file)))
+  }
+
+  /// Wraps a stream and starts a new session.
+  pub fn wrap<S>(&self, stream: S) -> RecordStream<S>
+  where
+    S: ClientStream,
+  {
+    RecordStream {
+      inner: stream,
+      recorder: self.clone(),
+      session: self.inner.last_session.fetch_add(1, Ordering::Relaxed) + 1,
+      sent: Vec::new(),
+      received: Vec::new(),
+    }
+  }
+
+  /// Queues a line for the writer task. Lines are silently dropped after the writer task stops on
+  /// a write error.
+  fn write_line(&self, session: usize, direction: char, line: &str) {
+    _ = self
+      .inner
+      .lines
+      .send(format!("{session}{direction} {line}\n"));
+  }
+}
+
+async fn write_lines<W>(mut writer: BufWriter<W>, mut lines: UnboundedReceiver<String>)
+where
+  W: AsyncWrite + Unpin,
+{
+  while let Some(line) = lines.recv().await {
+    let mut result = writer.write_all(line.as_bytes()).await;
+
+    if result.is_ok() && lines.is_empty() {
+      result = writer.flush().await;
+    }
+
+    if let Err(err) = result {
+      warn!(message = "unable to write upsd session recording", reason = %err);
+      return;
+    }
+  }
+
+  if let Err(err) = writer.shutdown().await {
+    warn!(message = "unable to close upsd session recording", reason = %err);
+  }
+}
+
+impl core::fmt::Debug for SessionRecorder {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SessionRecorder")
+      .field("last_session", &self.inner.last_session)
+      .finish_non_exhaustive()
+  }
+}
+
+/// Stream wrapper created by [`SessionRecorder::wrap`].
+pub struct RecordStream<S> {
+  inner: S,
+  recorder: SessionRecorder,
+  session: usize,
+  sent: Vec<u8>,
+  received: Vec<u8>,
+}
+
+impl<S> RecordStream<S> {
+  #[inline]
+  pub const fn session(&self) -> usize {
+    self.session
+  }
+
+  #[inline]
+  pub fn into_inner(self) -> S {
+    self.inner
+  }
+}
+
+fn record_lines(
+  recorder: &SessionRecorder,
+  session: usize,
+  direction: char,
+  pending: &mut Vec<u8>,
+  data: &[u8],
+) {
+  pending.extend_from_slice(data);
+
+  while let Some(end) = pending.iter().position(|b| *b == b'\n') {
+    let line = String::from_utf8_lossy(&pending[..end]);
+    let line = line.trim_end_matches('\r');
+
+    if direction == '>' && line.starts_with("PASSWORD ") {
+      recorder.write_line(session, direction, REDACTED_PASSWORD);
+    } else {
+      recorder.write_line(session, direction, line);
+    }
+
+    _ = pending.drain(..=end);
+  }
+}
+
+impl<S> AsyncRead for RecordStream<S>
+where
+  S: ClientStream,
+{
+  fn poll_read(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<std::io::Result<()>> {
+    let this = self.get_mut();
+    let filled = buf.filled().len();
+    let result = Pin::new(&mut this.inner).poll_read(cx, buf);
+
+    if let Poll::Ready(Ok(())) = result {
+      record_lines(
+        &this.recorder,
+        this.session,
+        '<',
+        &mut this.received,
+        &buf.filled()[filled..],
+      );
+    }
+
+    result
+  }
+}
+
+impl<S> AsyncWrite for RecordStream<S>
+where
+  S: ClientStream,
+{
+  fn poll_write(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<Result<usize, std::io::Error>> {
+    let this = self.get_mut();
+    let result = Pin::new(&mut this.inner).poll_write(cx, buf);
+
+    if let Poll::Ready(Ok(written)) = result {
+      record_lines(
+        &this.recorder,
+        this.session,
+        '>',
+        &mut this.sent,
+        &buf[..written],
+      );
+    }
+
+    result
+  }
+
+  #[inline]
+  fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), std::io::Error>> {
+    Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+  }
+
+  #[inline]
+  fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), std::io::Error>> {
+    Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+  }
+}
@@ -0,0 +1,199 @@
+use super::client_record::REDACTED_PASSWORD;
+use crate::error::Error;
+use core::{
+  pin::Pin,
+  task::{Context, Poll},
+};
+use std::{collections::VecDeque, io, path::Path};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+#[derive(Debug, Clone)]
+enum Exchange {
+  Write(Box<str>),
+  Read(Vec<u8>),
+}
+
+/// Sessions recorded by [`super::SessionRecorder`].
+///
+/// Lines starting with `#` and empty lines are ignored, so fixtures can be annotated by hand.
+#[derive(Debug, Clone, Default)]
+pub struct Fixture {
+  sessions: Vec<(usize, Vec<Exchange>)>,
+}
+
+impl Fixture {
+  pub fn parse(text: &str) -> Result<Self, Error> {
+    let mut fixture = Self::default();
+
+    for line in text.lines() {
+      if line.trim().is_empty() || line.starts_with('#') {
+        continue;
+      }
+
+      let Some(marker) = line.find(['>', '<']) else {
+        return Err(io::ErrorKind::InvalidData.into());
+      };
+
+      let session: usize = line[..marker]
+        .parse()
+        .map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+      let content = line[marker + 1..]
+        .strip_prefix(' ')
+        .unwrap_or(&line[marker + 1..]);
+
+      let exchanges = match fixture.sessions.iter_mut().find(|(id, _)| *id == session) {
+        Some((_, exchanges)) => exchanges,
+        None => {
+          fixture.sessions.push((session, Vec::new()));
+          &mut fixture.sessions.last_mut().unwrap().1
+        }
+      };
+
+      if line.as_bytes()[marker] == b'>' {
+        exchanges.push(Exchange::Write(Box::from(content)));
+      } else if let Some(Exchange::Read(data)) = exchanges.last_mut() {
+        data.extend_from_slice(content.as_bytes());
+        data.push(b'\n');
+      } else {
+        let mut data = Vec::from(content.as_bytes());
+        data.push(b'\n');
+        exchanges.push(Exchange::Read(data));
+      }
+    }
+
+    Ok(fixture)
+  }
+
+  pub fn load<P>(path: P) -> Result<Self, Error>
+  where
+    P: AsRef<Path>,
+  {
+    let text = std::fs::read_to_string(path)?;
+    Self::parse(&text)
+  }
+
+  /// Number of recorded sessions.
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.sessions.len()
+  }
+
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.sessions.is_empty()
+  }
+
+  /// Creates a replay stream for the session at `index`, in the order sessions first appear in
+  /// the fixture.
+  pub fn session(&self, index: usize) -> Option<ReplayStream> {
+    self.sessions.get(index).map(|(_, exchanges)| ReplayStream {
+      exchanges: exchanges.iter().cloned().collect(),
+      written: Vec::new(),
+      read_pos: 0,
+    })
+  }
+}
+
+/// Serves a recorded session back to a client, in the spirit of `tokio_test::io::Builder`.
+///
+/// Requests must match the recording line by line, a redacted `PASSWORD` line matches any
+/// password. Mismatched requests and reads before the expected requests are sent fail with
+/// [`std::io::ErrorKind::InvalidData`]. Reads return EOF once the session is fully replayed.
+#[derive(Debug)]
+pub struct ReplayStream {
+  exchanges: VecDeque<Exchange>,
+  written: Vec<u8>,
+  read_pos: usize,
+}
+
+impl ReplayStream {
+  /// Returns `true` once all recorded requests and responses are consumed.
+  #[inline]
+  pub fn is_finished(&self) -> bool {
+    self.exchanges.is_empty()
+  }
+}
+
+fn is_expected(expected: &str, line: &str) -> bool {
+  expected == line || (expected == REDACTED_PASSWORD && line.starts_with("PASSWORD "))
+}
+
+impl AsyncRead for ReplayStream {
+  fn poll_read(
+    self: Pin<&mut Self>,
+    _cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<io::Result<()>> {
+    let this = self.get_mut();
+
+    match this.exchanges.front() {
+      Some(Exchange::Read(data)) => {
+        let remaining = &data[this.read_pos..];
+        let len = remaining.len().min(buf.remaining());
+
+        buf.put_slice(&remaining[..len]);
+        this.read_pos += len;
+
+        if this.read_pos == data.len() {
+          _ = this.exchanges.pop_front();
+          this.read_pos = 0;
+        }
+
+        Poll::Ready(Ok(()))
+      }
+      Some(Exchange::Write(expected)) => Poll::Ready(Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("replay expects {expected:?} to be sent before reading"),
+      ))),
+      None => Poll::Ready(Ok(())),
+    }
+  }
+}
+
+impl AsyncWrite for ReplayStream {
+  fn poll_write(
+    self: Pin<&mut Self>,
+    _cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<Result<usize, io::Error>> {
+    let this = self.get_mut();
+    this.written.extend_from_slice(buf);
+
+    while let Some(end) = this.written.iter().position(|b| *b == b'\n') {
+      let line = String::from_utf8_lossy(&this.written[..end]);
+      let line = line.trim_end_matches('\r');
+
+      match this.exchanges.front() {
+        Some(Exchange::Write(expected)) if is_expected(expected, line) => {
+          _ = this.exchanges.pop_front();
+        }
+        Some(Exchange::Write(expected)) => {
+          return Poll::Ready(Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("replay expects {expected:?}, received {line:?}"),
+          )));
+        }
+        _ => {
+          return Poll::Ready(Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("replay does not expect any request, received {line:?}"),
+          )));
+        }
+      }
+
+      _ = this.written.drain(..=end);
+    }
+
+    Poll::Ready(Ok(buf.len()))
+  }
+
+  #[inline]
+  fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+    Poll::Ready(Ok(()))
+  }
+
+  #[inline]
+  fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+    Poll::Ready(Ok(()))
+  }
+}
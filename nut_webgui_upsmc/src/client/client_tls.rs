@@ -1,14 +1,33 @@
 use crate::{
-  client::NutClient, command::StartTls, error::Error, internal::Serialize, response::ProtOkTls,
+  client::NutClient,
+  command::StartTls,
+  error::{Error, ErrorKind, ProtocolError},
+  internal::Serialize,
+  response::ProtOkTls,
 };
 use std::sync::Arc;
-use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio::{
+  io::{AsyncRead, AsyncWrite},
+  net::{TcpStream, ToSocketAddrs},
+};
 use tokio_rustls::{
   TlsConnector,
   client::TlsStream,
   rustls::{ClientConfig, pki_types::ServerName},
 };
 
+/// Result of an opportunistic `STARTTLS` attempt.
+pub enum TlsNegotiation<S>
+where
+  S: AsyncRead + AsyncWrite + Unpin + Send + Sync,
+{
+  /// Server accepted `STARTTLS` and the TLS handshake is completed.
+  Enabled(NutClient<TlsStream<S>>),
+
+  /// Server answered with `ERR FEATURE-NOT-CONFIGURED`. Connection is still usable as plaintext.
+  NotConfigured(NutClient<S>),
+}
+
 impl NutClient<TlsStream<TcpStream>> {
   pub async fn connect_with_tls<A>(
     addr: A,
@@ -28,3 +47,37 @@ impl NutClient<TlsStream<TcpStream>> {
     Ok(NutClient::from(connection))
   }
 }
+
+impl<S> NutClient<S>
+where
+  S: AsyncRead + AsyncWrite + Unpin + Send + Sync + 'static,
+{
+  /// Sends `STARTTLS` and upgrades the connection. Unlike
+  /// [`NutClient::connect_with_tls`], servers without TLS configuration are not treated as an
+  /// error, and the plaintext client is returned instead.
+  pub async fn start_tls(
+    mut self,
+    srv_name: ServerName<'static>,
+    config: Arc<ClientConfig>,
+  ) -> Result<TlsNegotiation<S>, Error> {
+    match self.send::<_, ProtOkTls>(StartTls.serialize()).await {
+      Ok(_) => {
+        let connector = TlsConnector::from(config);
+        let connection = connector.connect(srv_name, self.into_inner()).await?;
+
+        Ok(TlsNegotiation::Enabled(NutClient::from(connection)))
+      }
+      Err(err)
+        if matches!(
+          err.kind(),
+          ErrorKind::ProtocolError {
+            inner: ProtocolError::FeatureNotConfigured
+          }
+        ) =>
+      {
+        Ok(TlsNegotiation::NotConfigured(self))
+      }
+      Err(err) => Err(err),
+    }
+  }
+}
@@ -116,20 +116,41 @@ impl Serialize for GetVar<'_, '_> {
   }
 }
 
-pub struct InstCmd<'a, 'b> {
+pub struct GetTracking<'a> {
+  pub id: &'a str,
+}
+
+impl Serialize for GetTracking<'_> {
+  type Output = String;
+
+  fn serialize(self) -> Self::Output {
+    format!("GET TRACKING {}\n", self.id)
+  }
+}
+
+pub struct InstCmd<'a, 'b, 'c> {
   pub ups: &'a UpsName,
   pub cmd: &'b CmdName,
+  pub value: Option<&'c Value>,
 }
 
-impl Serialize for InstCmd<'_, '_> {
+impl Serialize for InstCmd<'_, '_, '_> {
   type Output = String;
 
   fn serialize(self) -> Self::Output {
-    format!(
-      "INSTCMD {ups_name} {cmd_name}\n",
-      ups_name = self.ups.as_escaped_str(),
-      cmd_name = self.cmd
-    )
+    match self.value {
+      Some(value) => format!(
+        "INSTCMD {ups_name} {cmd_name} \"{value}\"\n",
+        ups_name = self.ups.as_escaped_str(),
+        cmd_name = self.cmd,
+        value = value.as_escaped_str()
+      ),
+      None => format!(
+        "INSTCMD {ups_name} {cmd_name}\n",
+        ups_name = self.ups.as_escaped_str(),
+        cmd_name = self.cmd
+      ),
+    }
   }
 }
 
@@ -269,6 +290,23 @@ impl Serialize for SetVariable<'_, '_, '_> {
   }
 }
 
+pub struct SetTracking {
+  pub enabled: bool,
+}
+
+impl Serialize for SetTracking {
+  type Output = &'static str;
+
+  #[inline]
+  fn serialize(self) -> Self::Output {
+    if self.enabled {
+      "SET TRACKING ON\n"
+    } else {
+      "SET TRACKING OFF\n"
+    }
+  }
+}
+
 pub struct GetDaemonVer;
 
 impl Serialize for GetDaemonVer {
@@ -280,6 +318,17 @@ impl Serialize for GetDaemonVer {
   }
 }
 
+pub struct GetNetVer;
+
+impl Serialize for GetNetVer {
+  type Output = &'static str;
+
+  #[inline]
+  fn serialize(self) -> Self::Output {
+    "NETVER\n"
+  }
+}
+
 pub struct GetProtVer;
 
 impl Serialize for GetProtVer {
@@ -287,7 +336,54 @@ impl Serialize for GetProtVer {
 
   #[inline]
   fn serialize(self) -> Self::Output {
-    "NETVER\n"
+    "PROTVER\n"
+  }
+}
+
+pub struct GetNumLogins<'a> {
+  pub ups: &'a UpsName,
+}
+
+impl Serialize for GetNumLogins<'_> {
+  type Output = String;
+
+  fn serialize(self) -> Self::Output {
+    format!("GET NUMLOGINS {}\n", self.ups.as_escaped_str())
+  }
+}
+
+pub struct Help;
+
+impl Serialize for Help {
+  type Output = &'static str;
+
+  #[inline]
+  fn serialize(self) -> Self::Output {
+    "HELP\n"
+  }
+}
+
+pub struct PrimaryCommand<'a> {
+  pub ups: &'a UpsName,
+}
+
+impl Serialize for PrimaryCommand<'_> {
+  type Output = String;
+
+  fn serialize(self) -> Self::Output {
+    format!("PRIMARY {}\n", self.ups.as_escaped_str())
+  }
+}
+
+pub struct MasterCommand<'a> {
+  pub ups: &'a UpsName,
+}
+
+impl Serialize for MasterCommand<'_> {
+  type Output = String;
+
+  fn serialize(self) -> Self::Output {
+    format!("MASTER {}\n", self.ups.as_escaped_str())
   }
 }
 
@@ -301,3 +397,70 @@ impl Serialize for StartTls {
     "STARTTLS\n"
   }
 }
+
+pub struct DriverDumpAll;
+
+impl Serialize for DriverDumpAll {
+  type Output = &'static str;
+
+  #[inline]
+  fn serialize(self) -> Self::Output {
+    "DUMPALL\n"
+  }
+}
+
+pub struct DriverPing;
+
+impl Serialize for DriverPing {
+  type Output = &'static str;
+
+  #[inline]
+  fn serialize(self) -> Self::Output {
+    "PING\n"
+  }
+}
+
+pub struct DriverInstCmd<'a, 'b, 'c> {
+  pub cmd: &'a CmdName,
+  pub value: Option<&'b Value>,
+  pub tracking: &'c str,
+}
+
+impl Serialize for DriverInstCmd<'_, '_, '_> {
+  type Output = String;
+
+  fn serialize(self) -> Self::Output {
+    match self.value {
+      Some(value) => format!(
+        "INSTCMD {cmd_name} \"{value}\" TRACKING {id}\n",
+        cmd_name = self.cmd,
+        value = value.as_escaped_str(),
+        id = self.tracking
+      ),
+      None => format!(
+        "INSTCMD {cmd_name} TRACKING {id}\n",
+        cmd_name = self.cmd,
+        id = self.tracking
+      ),
+    }
+  }
+}
+
+pub struct DriverSetVariable<'a, 'b, 'c> {
+  pub var: &'a VarName,
+  pub value: &'b Value,
+  pub tracking: &'c str,
+}
+
+impl Serialize for DriverSetVariable<'_, '_, '_> {
+  type Output = String;
+
+  fn serialize(self) -> Self::Output {
+    format!(
+      "SET {var_name} \"{value}\" TRACKING {id}\n",
+      var_name = self.var,
+      value = self.value.as_escaped_str(),
+      id = self.tracking
+    )
+  }
+}
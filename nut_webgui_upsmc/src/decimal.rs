@@ -0,0 +1,108 @@
+use crate::error::NumberParseError;
+
+/// Decimal number that keeps its original text representation.
+///
+/// NUT variables are transferred as text. Keeping the received digits allows values like `0.125`
+/// or `230.0` to be displayed and written back with `SET VAR` without any rounding, while
+/// [`Decimal::value`] is still available for calculations. Comparisons use the numeric value, so
+/// `12.50` and `12.5` are equal.
+#[derive(Debug, Clone)]
+pub struct Decimal {
+  value: f64,
+  text: Box<str>,
+}
+
+impl Decimal {
+  /// Numeric value of the decimal.
+  #[inline]
+  pub const fn value(&self) -> f64 {
+    self.value
+  }
+
+  /// Original text representation of the decimal.
+  #[inline]
+  pub const fn as_str(&self) -> &str {
+    &self.text
+  }
+
+  #[inline]
+  pub fn into_boxed_str(self) -> Box<str> {
+    self.text
+  }
+}
+
+impl core::str::FromStr for Decimal {
+  type Err = NumberParseError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.parse::<f64>() {
+      Ok(value) if value.is_finite() => Ok(Self {
+        value,
+        text: Box::from(s),
+      }),
+      _ => Err(NumberParseError),
+    }
+  }
+}
+
+impl TryFrom<f64> for Decimal {
+  type Error = NumberParseError;
+
+  /// Uses the shortest text representation that parses back to the same `f64`. `NaN` and
+  /// infinities are rejected, since they have no text form in the NUT protocol.
+  fn try_from(value: f64) -> Result<Self, Self::Error> {
+    if value.is_finite() {
+      Ok(Self {
+        value,
+        text: value.to_string().into_boxed_str(),
+      })
+    } else {
+      Err(NumberParseError)
+    }
+  }
+}
+
+impl TryFrom<f32> for Decimal {
+  type Error = NumberParseError;
+
+  /// Uses the shortest text representation that parses back to the same `f32`. `NaN` and
+  /// infinities are rejected, since they have no text form in the NUT protocol.
+  fn try_from(value: f32) -> Result<Self, Self::Error> {
+    if value.is_finite() {
+      Ok(Self {
+        value: value as f64,
+        text: value.to_string().into_boxed_str(),
+      })
+    } else {
+      Err(NumberParseError)
+    }
+  }
+}
+
+impl PartialEq for Decimal {
+  #[inline]
+  fn eq(&self, other: &Self) -> bool {
+    self.value == other.value
+  }
+}
+
+impl PartialOrd for Decimal {
+  #[inline]
+  fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+    self.value.partial_cmp(&other.value)
+  }
+}
+
+impl AsRef<str> for Decimal {
+  #[inline]
+  fn as_ref(&self) -> &str {
+    &self.text
+  }
+}
+
+impl std::fmt::Display for Decimal {
+  #[inline]
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&self.text)
+  }
+}
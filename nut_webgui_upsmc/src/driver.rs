@@ -0,0 +1,15 @@
+//! Client for NUT driver state sockets.
+//!
+//! Drivers expose their state over a Unix socket (e.g. `/var/run/nut/<driver>-<ups>`), which is
+//! normally only used by `upsd`. It can be used directly on single host installations where
+//! `upsd` is not running.
+
+#[cfg(feature = "tokio")]
+mod driver_client;
+mod driver_message;
+mod driver_state;
+
+#[cfg(feature = "tokio")]
+pub use driver_client::DriverClient;
+pub use driver_message::{DriverMessage, VarFlags};
+pub use driver_state::{DriverState, VarInfo};
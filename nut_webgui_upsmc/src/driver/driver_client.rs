@@ -0,0 +1,274 @@
+use super::{driver_message::DriverMessage, driver_state::DriverState};
+use crate::{
+  CmdName, Value, VarName, command,
+  error::{Error, ErrorKind, ProtocolError},
+  internal::{Deserialize, Serialize, lexer::Lexer},
+  response::TrackingStatus,
+};
+use core::{
+  sync::atomic::{AtomicU64, Ordering},
+  time::Duration,
+};
+use tokio::{
+  io::{
+    AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, ReadHalf, WriteHalf, split,
+  },
+  time::timeout,
+};
+use tracing::{trace, warn};
+
+#[cfg(unix)]
+use {std::path::Path, tokio::net::UnixStream};
+
+// Driver messages are single lines, long lines are only expected from `SETINFO` with very long
+// values.
+const LINE_SOFT_LIMIT: usize = 1024 * 4;
+
+static TRACKING_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Client for a NUT driver state socket, e.g. `/var/run/nut/usbhid-ups-myups`.
+///
+/// Driver sockets do not have any authentication, access is only controlled with socket file
+/// permissions. Unlike `upsd`, drivers push variable updates to all connected clients, and the
+/// client must keep reading the socket with [`DriverClient::next_message`].
+pub struct DriverClient<S>
+where
+  S: AsyncRead + AsyncWrite + Unpin + Send + Sync,
+{
+  reader: BufReader<ReadHalf<S>>,
+  writer: WriteHalf<S>,
+  timeout: Duration,
+  line_buff: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TrackedCommand {
+  InstCmd,
+  SetVar,
+}
+
+#[cfg(unix)]
+impl DriverClient<UnixStream> {
+  pub async fn connect<P>(path: P) -> Result<Self, Error>
+  where
+    P: AsRef<Path>,
+  {
+    let connection = UnixStream::connect(path).await?;
+
+    Ok(Self::new(connection))
+  }
+}
+
+impl<S> From<S> for DriverClient<S>
+where
+  S: AsyncWrite + AsyncRead + Unpin + Send + Sync + 'static,
+{
+  fn from(value: S) -> Self {
+    Self::new(value)
+  }
+}
+
+impl<S> DriverClient<S>
+where
+  S: AsyncWrite + AsyncRead + Send + Sync + Unpin,
+{
+  pub fn new(stream: S) -> Self {
+    let (reader, writer) = split(stream);
+    let reader = BufReader::new(reader);
+
+    Self {
+      reader,
+      writer,
+      timeout: Duration::from_secs(60),
+      line_buff: String::new(),
+    }
+  }
+
+  /// Sets the timeout for `DUMPALL` and tracked requests.
+  #[inline]
+  pub fn set_timeout(&mut self, timeout: Duration) {
+    self.timeout = timeout;
+  }
+
+  pub async fn close(mut self) -> Result<(), Error> {
+    self.writer.shutdown().await?;
+    Ok(())
+  }
+
+  async fn write(&mut self, message: &str) -> Result<(), Error> {
+    trace!(message = "driver socket message", send = message);
+
+    self.writer.write_all(message.as_bytes()).await?;
+    self.writer.flush().await?;
+
+    Ok(())
+  }
+
+  /// Sends `DUMPALL` and reads the full driver state until `DUMPDONE`.
+  pub async fn dump_all(&mut self) -> Result<DriverState, Error> {
+    self.write(command::DriverDumpAll.serialize()).await?;
+
+    match timeout(self.timeout, self.read_dump()).await {
+      Ok(result) => result,
+      Err(_) => Err(ErrorKind::RequestTimeout.into()),
+    }
+  }
+
+  async fn read_dump(&mut self) -> Result<DriverState, Error> {
+    let mut state = DriverState::new();
+
+    loop {
+      match self.next_message().await? {
+        DriverMessage::DumpDone => break,
+        message => state.apply(message),
+      }
+    }
+
+    Ok(state)
+  }
+
+  /// Sends `PING`, driver replies with [`DriverMessage::Pong`].
+  #[inline]
+  pub async fn ping(&mut self) -> Result<(), Error> {
+    self.write(command::DriverPing.serialize()).await
+  }
+
+  /// Waits for the next driver message. Unrecognized lines are skipped.
+  ///
+  /// This function does not have a timeout, drivers only send messages when their state changes.
+  /// It's cancellation safe, partially received lines are kept until the next call.
+  pub async fn next_message(&mut self) -> Result<DriverMessage, Error> {
+    loop {
+      let read = self.reader.read_line(&mut self.line_buff).await?;
+
+      if read == 0 {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+      }
+
+      if !self.line_buff.ends_with('\n') {
+        continue;
+      }
+
+      let result = if self.line_buff.trim().is_empty() {
+        None
+      } else {
+        trace!(
+          message = "driver socket message received",
+          response = &self.line_buff
+        );
+
+        let mut lexer = Lexer::new(&self.line_buff);
+
+        match DriverMessage::deserialize(&mut lexer) {
+          Ok(message) => Some(message),
+          Err(err) => {
+            warn!(
+              message = "unrecognized driver socket message",
+              response = self.line_buff.trim_end(),
+              reason = %err
+            );
+
+            None
+          }
+        }
+      };
+
+      if self.line_buff.len() >= LINE_SOFT_LIMIT {
+        self.line_buff = String::new();
+      } else {
+        self.line_buff.clear();
+      }
+
+      if let Some(message) = result {
+        return Ok(message);
+      }
+    }
+  }
+
+  /// Sends a tracked `INSTCMD` and waits for its result.
+  ///
+  /// Drivers older than NUT 2.8.0 do not reply tracking requests, their commands are reported as
+  /// [`TrackingStatus::Pending`] after timeout.
+  pub async fn instcmd(
+    &mut self,
+    cmd: &CmdName,
+    value: Option<&Value>,
+  ) -> Result<TrackingStatus, Error> {
+    let id = next_tracking_id();
+    let command = command::DriverInstCmd {
+      cmd,
+      value,
+      tracking: &id,
+    }
+    .serialize();
+
+    self.write(&command).await?;
+    self.wait_tracking(&id, TrackedCommand::InstCmd).await
+  }
+
+  /// Sends a tracked `SET` and waits for its result.
+  ///
+  /// Drivers older than NUT 2.8.0 do not reply tracking requests, their commands are reported as
+  /// [`TrackingStatus::Pending`] after timeout.
+  pub async fn set_var(&mut self, var: &VarName, value: &Value) -> Result<TrackingStatus, Error> {
+    let id = next_tracking_id();
+    let command = command::DriverSetVariable {
+      var,
+      value,
+      tracking: &id,
+    }
+    .serialize();
+
+    self.write(&command).await?;
+    self.wait_tracking(&id, TrackedCommand::SetVar).await
+  }
+
+  async fn wait_tracking(
+    &mut self,
+    id: &str,
+    command: TrackedCommand,
+  ) -> Result<TrackingStatus, Error> {
+    let wait_timeout = self.timeout;
+    let wait = async {
+      loop {
+        match self.next_message().await? {
+          DriverMessage::Tracking {
+            id: tracking_id,
+            status,
+          } if tracking_id.as_ref() == id => {
+            return Ok::<_, Error>(command.status_from(status));
+          }
+          _ => continue,
+        }
+      }
+    };
+
+    match timeout(wait_timeout, wait).await {
+      Ok(result) => result,
+      Err(_) => Ok(TrackingStatus::Pending),
+    }
+  }
+}
+
+impl TrackedCommand {
+  /// Maps driver `STAT_INSTCMD_*` and `STAT_SET_*` codes to upsd equivalent results.
+  fn status_from(self, status: i32) -> TrackingStatus {
+    match (self, status) {
+      (_, 0) => TrackingStatus::Success,
+      (Self::InstCmd, 1) => TrackingStatus::Failed(ProtocolError::CmdNotSupported),
+      (Self::InstCmd, 2) => TrackingStatus::Failed(ProtocolError::InvalidArgument),
+      (Self::InstCmd, 3) => TrackingStatus::Failed(ProtocolError::InstcmdFailed),
+      (Self::SetVar, 1) => TrackingStatus::Failed(ProtocolError::VarNotSupported),
+      (Self::SetVar, 2) => TrackingStatus::Failed(ProtocolError::InvalidValue),
+      (Self::SetVar, 3) => TrackingStatus::Failed(ProtocolError::SetFailed),
+      (_, 4) => TrackingStatus::Failed(ProtocolError::InvalidValue),
+      (_, code) => TrackingStatus::Failed(ProtocolError::Unknown(code.to_string().into())),
+    }
+  }
+}
+
+fn next_tracking_id() -> String {
+  let seq = TRACKING_SEQ.fetch_add(1, Ordering::Relaxed);
+
+  format!("{pid:x}-{seq:x}", pid = std::process::id())
+}
@@ -0,0 +1,224 @@
+use crate::{
+  CmdName, InferValueFrom, Value, VarName,
+  error::{Error, ErrorKind, ParseError},
+  internal::{
+    Deserialize,
+    lexer::{Lexer, Token},
+    parser_utils::{cmp_literal, end_parser, extract_text, extract_var_name, parse_line},
+  },
+};
+
+/// Variable flags reported by `SETFLAGS`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VarFlags {
+  /// Variable is writeable with `SET`.
+  pub rw: bool,
+
+  /// Variable is a string, its maximum length is reported by `SETAUX`.
+  pub string: bool,
+
+  /// Variable is numeric.
+  pub number: bool,
+}
+
+/// Single line message received from a driver state socket.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DriverMessage {
+  /// `SETINFO <var> "<value>"`
+  SetInfo { name: VarName, value: Value },
+
+  /// `DELINFO <var>`
+  DelInfo { name: VarName },
+
+  /// `ADDENUM <var> "<value>"`
+  AddEnum { name: VarName, value: Value },
+
+  /// `DELENUM <var> "<value>"`
+  DelEnum { name: VarName, value: Value },
+
+  /// `ADDRANGE <var> <min> <max>`
+  AddRange {
+    name: VarName,
+    min: Value,
+    max: Value,
+  },
+
+  /// `DELRANGE <var> <min> <max>`
+  DelRange {
+    name: VarName,
+    min: Value,
+    max: Value,
+  },
+
+  /// `SETAUX <var> <aux>`
+  SetAux { name: VarName, aux: usize },
+
+  /// `SETFLAGS <var> [<flag> ...]`
+  SetFlags { name: VarName, flags: VarFlags },
+
+  /// `ADDCMD <cmd>`
+  AddCmd { cmd: CmdName },
+
+  /// `DELCMD <cmd>`
+  DelCmd { cmd: CmdName },
+
+  /// `DATAOK`, driver is communicating with the device.
+  DataOk,
+
+  /// `DATASTALE`, driver lost communication with the device.
+  DataStale,
+
+  /// `DUMPDONE`, end of the `DUMPALL` reply.
+  DumpDone,
+
+  /// `PING`
+  Ping,
+
+  /// `PONG`
+  Pong,
+
+  /// `TRACKING <id> <status>`, result of a tracked `INSTCMD` or `SET`.
+  Tracking { id: Box<str>, status: i32 },
+}
+
+impl Deserialize for DriverMessage {
+  type Error = Error;
+
+  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+    let message = match lexer.peek_as_str() {
+      Some("SETINFO") => {
+        let (name, value) =
+          parse_line!(lexer, "SETINFO" {VAR, name = var_name} {VALUE, name = value})?;
+        Self::SetInfo { name, value }
+      }
+      Some("DELINFO") => {
+        let name = parse_line!(lexer, "DELINFO" {VAR, name = var_name})?;
+        Self::DelInfo { name }
+      }
+      Some("ADDENUM") => {
+        let (name, value) =
+          parse_line!(lexer, "ADDENUM" {VAR, name = var_name} {VALUE, name = value})?;
+        Self::AddEnum { name, value }
+      }
+      Some("DELENUM") => {
+        let (name, value) =
+          parse_line!(lexer, "DELENUM" {VAR, name = var_name} {VALUE, name = value})?;
+        Self::DelEnum { name, value }
+      }
+      Some("ADDRANGE") => {
+        let (name, min, max) = parse_line!(lexer, "ADDRANGE" {VAR, name = var_name} {TEXT, name = min} {TEXT, name = max})?;
+
+        Self::AddRange {
+          name,
+          min: parse_number(lexer, min)?,
+          max: parse_number(lexer, max)?,
+        }
+      }
+      Some("DELRANGE") => {
+        let (name, min, max) = parse_line!(lexer, "DELRANGE" {VAR, name = var_name} {TEXT, name = min} {TEXT, name = max})?;
+
+        Self::DelRange {
+          name,
+          min: parse_number(lexer, min)?,
+          max: parse_number(lexer, max)?,
+        }
+      }
+      Some("SETAUX") => {
+        let (name, aux) = parse_line!(lexer, "SETAUX" {VAR, name = var_name} {TEXT, name = aux})?;
+        let aux = aux.parse().map_err(|_| ErrorKind::ParseError {
+          inner: ParseError::InvalidNumber,
+          position: lexer.get_positon(),
+        })?;
+
+        Self::SetAux { name, aux }
+      }
+      Some("SETFLAGS") => {
+        cmp_literal(lexer, "SETFLAGS")?;
+        let name = extract_var_name(lexer)?;
+        let mut flags = VarFlags::default();
+
+        while let Some(Token::Text { .. }) = lexer.peek() {
+          match extract_text(lexer)?.as_ref() {
+            "RW" => flags.rw = true,
+            "STRING" => flags.string = true,
+            "NUMBER" => flags.number = true,
+            _ => {}
+          }
+        }
+
+        end_parser(lexer)?;
+
+        Self::SetFlags { name, flags }
+      }
+      Some("ADDCMD") => {
+        let cmd = parse_line!(lexer, "ADDCMD" {CMD, name = cmd})?;
+        Self::AddCmd { cmd }
+      }
+      Some("DELCMD") => {
+        let cmd = parse_line!(lexer, "DELCMD" {CMD, name = cmd})?;
+        Self::DelCmd { cmd }
+      }
+      Some("DATAOK") => {
+        parse_line!(lexer, "DATAOK")?;
+        Self::DataOk
+      }
+      Some("DATASTALE") => {
+        parse_line!(lexer, "DATASTALE")?;
+        Self::DataStale
+      }
+      Some("DUMPDONE") => {
+        parse_line!(lexer, "DUMPDONE")?;
+        Self::DumpDone
+      }
+      Some("PING") => {
+        parse_line!(lexer, "PING")?;
+        Self::Ping
+      }
+      Some("PONG") => {
+        parse_line!(lexer, "PONG")?;
+        Self::Pong
+      }
+      Some("TRACKING") => {
+        let (id, status) = parse_line!(lexer, "TRACKING" {TEXT, name = id} {TEXT, name = status})?;
+        let status = status.parse().map_err(|_| ErrorKind::ParseError {
+          inner: ParseError::InvalidNumber,
+          position: lexer.get_positon(),
+        })?;
+
+        Self::Tracking { id, status }
+      }
+      _ => {
+        return Err(
+          ErrorKind::ParseError {
+            inner: ParseError::InvalidToken,
+            position: lexer.get_positon(),
+          }
+          .into(),
+        );
+      }
+    };
+
+    if lexer.is_finished() {
+      Ok(message)
+    } else {
+      Err(
+        ErrorKind::ParseError {
+          inner: ParseError::InvalidToken,
+          position: lexer.get_positon(),
+        }
+        .into(),
+      )
+    }
+  }
+}
+
+#[inline]
+fn parse_number(lexer: &Lexer, text: Box<str>) -> Result<Value, Error> {
+  Value::infer_number_from(text).map_err(|_| {
+    ErrorKind::ParseError {
+      inner: ParseError::InvalidNumber,
+      position: lexer.get_positon(),
+    }
+    .into()
+  })
+}
@@ -0,0 +1,157 @@
+use super::driver_message::{DriverMessage, VarFlags};
+use crate::{CmdName, Value, VarName, VarType, ups_variables::UpsVariables};
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Variable details reported by `SETFLAGS`, `SETAUX`, `ADDENUM` and `ADDRANGE` messages.
+#[derive(Debug, Clone, Default)]
+pub struct VarInfo {
+  pub flags: VarFlags,
+  pub aux: Option<usize>,
+  pub enums: Vec<Value>,
+  pub ranges: Vec<(Value, Value)>,
+}
+
+/// In-memory copy of a driver's state, built from `DUMPALL` and kept up to date with streamed
+/// driver messages.
+#[derive(Debug, Clone, Default)]
+pub struct DriverState {
+  variables: UpsVariables,
+  var_info: HashMap<VarName, VarInfo>,
+  commands: Vec<CmdName>,
+  stale: bool,
+}
+
+impl DriverState {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  #[inline]
+  pub fn variables(&self) -> &UpsVariables {
+    &self.variables
+  }
+
+  #[inline]
+  pub fn commands(&self) -> &[CmdName] {
+    &self.commands
+  }
+
+  #[inline]
+  pub fn var_info<K>(&self, name: K) -> Option<&VarInfo>
+  where
+    K: core::borrow::Borrow<VarName>,
+  {
+    self.var_info.get(name.borrow())
+  }
+
+  /// Returns `true` after `DATASTALE`, until the driver reports `DATAOK`.
+  #[inline]
+  pub const fn is_stale(&self) -> bool {
+    self.stale
+  }
+
+  /// Iterates over writeable variable names.
+  pub fn rw_variables(&self) -> impl Iterator<Item = &VarName> {
+    self
+      .var_info
+      .iter()
+      .filter(|(name, info)| info.flags.rw && self.variables.contains_key(*name))
+      .map(|(name, _)| name)
+  }
+
+  /// Returns variable types in the same form as upsd's `GET TYPE` reply.
+  pub fn var_types<K>(&self, name: K) -> Vec<VarType>
+  where
+    K: core::borrow::Borrow<VarName>,
+  {
+    let mut var_types = Vec::new();
+
+    if let Some(info) = self.var_info.get(name.borrow()) {
+      if info.flags.rw {
+        var_types.push(VarType::ReadWrite);
+      }
+
+      if !info.enums.is_empty() {
+        var_types.push(VarType::Enum);
+      }
+
+      if !info.ranges.is_empty() {
+        var_types.push(VarType::Range);
+      }
+
+      if info.flags.string {
+        var_types.push(VarType::String {
+          max_len: info.aux.unwrap_or_default(),
+        });
+      }
+
+      if info.flags.number {
+        var_types.push(VarType::Number);
+      }
+    }
+
+    var_types
+  }
+
+  /// Applies a driver message to the state. Messages without any state change (e.g. `PONG`,
+  /// `TRACKING`) are ignored.
+  pub fn apply(&mut self, message: DriverMessage) {
+    match message {
+      DriverMessage::SetInfo { name, value } => {
+        _ = self.variables.insert(name, value);
+      }
+      DriverMessage::DelInfo { name } => {
+        _ = self.variables.remove(&name);
+        _ = self.var_info.remove(&name);
+      }
+      DriverMessage::AddEnum { name, value } => {
+        let info = self.var_info.entry(name).or_default();
+
+        if !info.enums.contains(&value) {
+          info.enums.push(value);
+        }
+      }
+      DriverMessage::DelEnum { name, value } => {
+        if let Some(info) = self.var_info.get_mut(&name) {
+          info.enums.retain(|v| *v != value);
+        }
+      }
+      DriverMessage::AddRange { name, min, max } => {
+        let info = self.var_info.entry(name).or_default();
+        let range = (min, max);
+
+        if !info.ranges.contains(&range) {
+          info.ranges.push(range);
+        }
+      }
+      DriverMessage::DelRange { name, min, max } => {
+        if let Some(info) = self.var_info.get_mut(&name) {
+          info
+            .ranges
+            .retain(|(v_min, v_max)| *v_min != min || *v_max != max);
+        }
+      }
+      DriverMessage::SetAux { name, aux } => {
+        self.var_info.entry(name).or_default().aux = Some(aux);
+      }
+      DriverMessage::SetFlags { name, flags } => {
+        self.var_info.entry(name).or_default().flags = flags;
+      }
+      DriverMessage::AddCmd { cmd } => {
+        if !self.commands.contains(&cmd) {
+          self.commands.push(cmd);
+        }
+      }
+      DriverMessage::DelCmd { cmd } => {
+        self.commands.retain(|v| *v != cmd);
+      }
+      DriverMessage::DataOk => self.stale = false,
+      DriverMessage::DataStale => self.stale = true,
+      message => {
+        debug!(message = "driver message does not change state", driver_message = ?message);
+      }
+    }
+  }
+}
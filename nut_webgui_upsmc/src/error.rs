@@ -23,9 +23,17 @@ pub enum ErrorKind {
   ProtocolError {
     inner: ProtocolError,
   },
+  ProxyError {
+    inner: ProxyError,
+  },
   ConnectionPoolClosed,
   EmptyResponse,
   RequestTimeout,
+  /// upsd stopped offering STARTTLS after TLS was negotiated before on the same pool.
+  TlsDowngradeRefused,
+  /// Client does not implement the command. Unlike [`ProtocolError::FeatureNotSupported`], the
+  /// command is never sent to upsd.
+  Unimplemented,
 }
 
 #[derive(Debug, Clone)]
@@ -45,6 +53,23 @@ pub enum ParseError {
   VarType(VarTypeParseError),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyError {
+  /// Proxy refused the credentials, or requires credentials but none is configured.
+  AuthenticationFailed,
+  /// SOCKS5 proxy does not support any of the offered authentication methods.
+  UnsupportedAuthMethod,
+  /// SOCKS5 proxy refused the connection with a reply code.
+  ConnectRejected {
+    code: u8,
+  },
+  /// HTTP proxy refused the `CONNECT` request with a status code.
+  HttpStatus {
+    code: u16,
+  },
+  InvalidResponse,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum VarTypeParseError {
   Empty,
@@ -57,6 +82,14 @@ pub enum UpsNameParseError {
   InvalidName,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum UpsIdentifierParseError {
+  UpsName(UpsNameParseError),
+  InvalidHost,
+  InvalidPort,
+  UnbracketedIpv6,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum VarNameParseError {
   Empty,
@@ -99,6 +132,37 @@ impl std::fmt::Display for UpsNameParseError {
   }
 }
 
+impl std::fmt::Display for UpsIdentifierParseError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      UpsIdentifierParseError::UpsName(err) => err.fmt(f),
+      UpsIdentifierParseError::InvalidHost => f.write_str("invalid hostname"),
+      UpsIdentifierParseError::InvalidPort => f.write_str("invalid port"),
+      UpsIdentifierParseError::UnbracketedIpv6 => {
+        f.write_str("ambiguous IPv6 host, enclose it in brackets, e.g. [::1]:3493")
+      }
+    }
+  }
+}
+
+impl std::fmt::Display for ProxyError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ProxyError::AuthenticationFailed => f.write_str("proxy authentication failed"),
+      ProxyError::UnsupportedAuthMethod => f.write_str("unsupported proxy authentication method"),
+      ProxyError::ConnectRejected { code } => f.write_fmt(format_args!(
+        "socks5 proxy rejected the connection. code={}",
+        code
+      )),
+      ProxyError::HttpStatus { code } => f.write_fmt(format_args!(
+        "http proxy rejected the connection. status={}",
+        code
+      )),
+      ProxyError::InvalidResponse => f.write_str("invalid proxy response"),
+    }
+  }
+}
+
 impl std::fmt::Display for VarTypeParseError {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
@@ -124,7 +188,11 @@ impl std::fmt::Display for ErrorKind {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
       ErrorKind::RequestTimeout => f.write_str("request timeout"),
+      ErrorKind::TlsDowngradeRefused => {
+        f.write_str("upsd refused TLS after it was negotiated before, plaintext connection denied")
+      }
       ErrorKind::EmptyResponse => f.write_str("empty response received"),
+      ErrorKind::Unimplemented => f.write_str("command is not implemented by the client"),
       ErrorKind::ConnectionPoolClosed => {
         f.write_str("new connection request received but connection pool is already closed")
       }
@@ -137,6 +205,7 @@ impl std::fmt::Display for ErrorKind {
         "nut server responded with an error message. error={}",
         inner
       )),
+      ErrorKind::ProxyError { inner } => f.write_fmt(format_args!("proxy error. {}", inner)),
     }
   }
 }
@@ -261,6 +330,14 @@ impl From<ProtocolError> for Error {
   }
 }
 
+impl From<ProxyError> for Error {
+  fn from(value: ProxyError) -> Self {
+    Self {
+      inner: Box::from(ErrorKind::ProxyError { inner: value }),
+    }
+  }
+}
+
 impl From<ErrorKind> for Error {
   fn from(value: ErrorKind) -> Self {
     Self {
@@ -289,6 +366,8 @@ impl core::error::Error for CmdParseError {}
 impl core::error::Error for Error {}
 impl core::error::Error for ParseError {}
 impl core::error::Error for UpsNameParseError {}
+impl core::error::Error for UpsIdentifierParseError {}
+impl core::error::Error for ProxyError {}
 impl core::error::Error for VarNameParseError {}
 impl core::error::Error for NumberParseError {}
 impl core::error::Error for VarTypeParseError {}
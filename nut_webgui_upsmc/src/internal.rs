@@ -2,6 +2,7 @@ use self::lexer::Lexer;
 
 pub mod ascii_rules;
 pub mod escape;
+#[cfg(feature = "tokio")]
 pub mod item_pool;
 pub mod lexer;
 pub mod parser_utils;
@@ -2,9 +2,17 @@ use core::{
   num::NonZeroUsize,
   ops::{Deref, DerefMut},
   pin::Pin,
+  sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+  time::Duration,
+};
+use std::{
+  collections::VecDeque,
+  sync::{Arc, Weak},
+};
+use tokio::{
+  sync::{AcquireError, Mutex, OwnedSemaphorePermit, Semaphore},
+  time::Instant,
 };
-use std::{collections::VecDeque, sync::Arc};
-use tokio::sync::{AcquireError, Mutex, OwnedSemaphorePermit, Semaphore};
 
 pub enum ItemState<T> {
   Ready(T),
@@ -21,6 +29,10 @@ pub trait ItemAllocator {
   /// Custom async deallocation for item.
   fn dealloc(&self, item: Self::Item) -> Pin<Box<dyn Future<Output = ()> + Send + '_>>;
 
+  /// Custom hook to decide whether a released item can go back to the pool. Items failing this
+  /// check are deallocated instead of being recycled.
+  fn is_recyclable(&self, item: &Self::Item) -> bool;
+
   /// Custom hook to reset or modify an existing pool item before creating a [PoolGuard].
   /// If this function returns false, the pool item will be discarded, and a new one will be dispatched
   /// from either the pool or allocator.
@@ -30,6 +42,73 @@ pub trait ItemAllocator {
   ) -> Pin<Box<dyn Future<Output = ItemState<Self::Item>> + Send + '_>>;
 }
 
+/// Validation and eviction rules for pooled items.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ItemPoolPolicy {
+  /// Items released within this window are handed out without calling
+  /// [ItemAllocator::prealloc_check()].
+  pub validation_window: Duration,
+
+  /// Items older than this are discarded instead of being handed out.
+  pub max_lifetime: Option<Duration>,
+
+  /// Items idle in the pool longer than this are discarded.
+  pub idle_timeout: Option<Duration>,
+}
+
+/// Minimum interval between two background eviction runs.
+const MIN_REAP_PERIOD: Duration = Duration::from_secs(1);
+
+impl ItemPoolPolicy {
+  /// Interval of the background eviction task, `None` when items never expire.
+  fn reap_period(&self) -> Option<Duration> {
+    self
+      .idle_timeout
+      .into_iter()
+      .chain(self.max_lifetime)
+      .min()
+      .map(|period| period.max(MIN_REAP_PERIOD))
+  }
+}
+
+/// Point-in-time snapshot of pool statistics.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PoolStats {
+  /// Maximum number of items.
+  pub limit: usize,
+
+  /// Items waiting in the pool.
+  pub idle: usize,
+
+  /// Items currently checked out.
+  pub active: usize,
+
+  /// Total number of checkouts.
+  pub acquired: u64,
+
+  /// Total time spent on waiting for an available slot.
+  pub wait_time: Duration,
+
+  /// Total number of newly allocated items.
+  pub allocated: u64,
+
+  /// Total number of failed allocations.
+  pub allocation_failures: u64,
+
+  /// Total number of deallocated items. Includes the evicted, discarded, dropped and detached ones.
+  pub deallocated: u64,
+}
+
+#[derive(Debug, Default)]
+struct PoolCounters {
+  idle: AtomicUsize,
+  acquired: AtomicU64,
+  wait_time_us: AtomicU64,
+  allocated: AtomicU64,
+  allocation_failures: AtomicU64,
+  deallocated: AtomicU64,
+}
+
 #[derive(Debug)]
 pub enum ItemPoolError<E> {
   PoolClosed,
@@ -62,8 +141,18 @@ where
   A: ItemAllocator,
 {
   allocator: A,
-  items: Mutex<VecDeque<A::Item>>,
+  items: Mutex<VecDeque<PoolItem<A::Item>>>,
   permits: Arc<Semaphore>,
+  policy: ItemPoolPolicy,
+  limit: usize,
+  counters: PoolCounters,
+}
+
+#[derive(Debug)]
+struct PoolItem<T> {
+  item: T,
+  created_at: Instant,
+  released_at: Instant,
 }
 
 pub struct ItemPool<A>
@@ -73,12 +162,16 @@ where
   inner: Arc<InnerPool<A>>,
 }
 
+/// Guard for a checked out pool item. Items are only recycled via [PoolGuard::release()];
+/// dropping the guard (e.g. when a future is cancelled mid-request) drops the item instead.
 pub struct PoolGuard<A>
 where
   A: ItemAllocator,
 {
   _permit: OwnedSemaphorePermit,
-  item: A::Item,
+  /// Always `Some` until the guard is released or consumed.
+  item: Option<A::Item>,
+  created_at: Instant,
   pool: Arc<InnerPool<A>>,
 }
 
@@ -97,18 +190,42 @@ impl<A> PoolGuard<A>
 where
   A: ItemAllocator,
 {
-  pub async fn release(self) {
-    self.pool.release(self.item).await;
+  pub async fn release(mut self) {
+    if let Some(item) = self.item.take() {
+      self.pool.release(item, self.created_at).await;
+    }
   }
 
-  pub fn into_inner(self) -> A::Item {
-    let item: A::Item = self.item;
-    drop(self._permit);
+  /// Detaches the item from the pool. Detached items are counted as deallocated.
+  pub fn into_inner(mut self) -> A::Item {
+    let item = self.item.take().expect("pool guard item is already taken");
+    self
+      .pool
+      .counters
+      .deallocated
+      .fetch_add(1, Ordering::Relaxed);
 
     item
   }
 }
 
+impl<A> Drop for PoolGuard<A>
+where
+  A: ItemAllocator,
+{
+  fn drop(&mut self) {
+    // NOTE: Dropped guards cannot call the async [ItemAllocator::dealloc()], the item is dropped
+    // in place but still counted as deallocated.
+    if self.item.take().is_some() {
+      self
+        .pool
+        .counters
+        .deallocated
+        .fetch_add(1, Ordering::Relaxed);
+    }
+  }
+}
+
 impl<A> Deref for PoolGuard<A>
 where
   A: ItemAllocator,
@@ -117,7 +234,10 @@ where
 
   #[inline]
   fn deref(&self) -> &Self::Target {
-    &self.item
+    self
+      .item
+      .as_ref()
+      .expect("pool guard item is already taken")
   }
 }
 
@@ -127,96 +247,154 @@ where
 {
   #[inline]
   fn deref_mut(&mut self) -> &mut Self::Target {
-    &mut self.item
+    self
+      .item
+      .as_mut()
+      .expect("pool guard item is already taken")
   }
 }
 
 impl<A> ItemPool<A>
 where
-  A: ItemAllocator,
+  A: ItemAllocator + Send + Sync + 'static,
+  A::Item: Send,
 {
-  pub fn new(limit: NonZeroUsize, allocator: A) -> Self {
+  /// Creates a new pool. When the policy has an idle timeout or a max lifetime, a background task
+  /// periodically evicts expired idle items, so an unused pool does not keep them open. The task
+  /// is only started when the pool is created within a tokio runtime, and it stops once the pool
+  /// is closed or dropped.
+  pub fn new(limit: NonZeroUsize, allocator: A, policy: ItemPoolPolicy) -> Self {
     let limit: usize = limit.into();
+    let inner = Arc::new(InnerPool {
+      allocator,
+      items: Mutex::new(VecDeque::with_capacity(limit)),
+      permits: Arc::new(Semaphore::new(limit)),
+      policy,
+      limit,
+      counters: PoolCounters::default(),
+    });
 
-    Self {
-      inner: Arc::new(InnerPool {
-        allocator,
-        items: Mutex::new(VecDeque::with_capacity(limit)),
-        permits: Arc::new(Semaphore::new(limit)),
-      }),
+    if let (Some(period), Ok(runtime)) =
+      (policy.reap_period(), tokio::runtime::Handle::try_current())
+    {
+      runtime.spawn(InnerPool::reap(Arc::downgrade(&inner), period));
     }
+
+    Self { inner }
   }
+}
 
-  /// Returns an item from pool with provided [ItemAllocator::try_check()] function until a valid
-  /// item got found or allocator returns new one.
+impl<A> ItemPool<A>
+where
+  A: ItemAllocator,
+{
+  /// Returns an item from pool with provided [ItemAllocator::prealloc_check()] function until a
+  /// valid item got found or allocator returns new one. Items released within the validation
+  /// window are returned without the check.
   pub async fn get_checked(&self) -> Result<PoolGuard<A>, ItemPoolError<A::Error>> {
-    let permit = self.inner.permits.clone().acquire_owned().await?;
+    let permit = self.inner.acquire().await?;
 
     loop {
-      let item = {
-        let mut items_lock = self.inner.items.lock().await;
-        items_lock.pop_front()
-      };
-
-      match item {
-        Some(item) => match self.inner.allocator.prealloc_check(item).await {
-          ItemState::Ready(item) => {
-            return Ok(PoolGuard {
-              _permit: permit,
-              item,
-              pool: self.inner.clone(),
-            });
+      match self.inner.pop_item().await {
+        Some(pool_item) => {
+          if pool_item.released_at.elapsed() < self.inner.policy.validation_window {
+            return Ok(self.guard(permit, pool_item.item, pool_item.created_at));
           }
-          ItemState::Destroy(item) => {
-            _ = self.inner.allocator.dealloc(item).await;
-            continue;
+
+          match self.inner.allocator.prealloc_check(pool_item.item).await {
+            ItemState::Ready(item) => {
+              return Ok(self.guard(permit, item, pool_item.created_at));
+            }
+            ItemState::Destroy(item) => {
+              self.inner.dealloc(item).await;
+              continue;
+            }
           }
-        },
-        None => {
-          return match self.inner.allocator.init().await {
-            Ok(item) => Ok(PoolGuard {
-              _permit: permit,
-              item,
-              pool: self.inner.clone(),
-            }),
-            Err(inner) => Err(ItemPoolError::AllocatorError { inner }),
-          };
         }
+        None => return self.allocate(permit).await,
       };
     }
   }
 
   /// Returns an item from pool without any check. Returned item might be in an invalid state.
   pub async fn get(&self) -> Result<PoolGuard<A>, ItemPoolError<A::Error>> {
-    let permit = self.inner.permits.clone().acquire_owned().await?;
-    let item = {
-      let mut items_lock = self.inner.items.lock().await;
-      items_lock.pop_front()
+    let permit = self.inner.acquire().await?;
+
+    match self.inner.pop_item().await {
+      Some(pool_item) => Ok(self.guard(permit, pool_item.item, pool_item.created_at)),
+      None => self.allocate(permit).await,
+    }
+  }
+
+  #[inline]
+  fn guard(
+    &self,
+    permit: OwnedSemaphorePermit,
+    item: A::Item,
+    created_at: Instant,
+  ) -> PoolGuard<A> {
+    PoolGuard {
+      _permit: permit,
+      item: Some(item),
+      created_at,
+      pool: self.inner.clone(),
+    }
+  }
+
+  async fn allocate(
+    &self,
+    permit: OwnedSemaphorePermit,
+  ) -> Result<PoolGuard<A>, ItemPoolError<A::Error>> {
+    match self.inner.allocator.init().await {
+      Ok(item) => {
+        self
+          .inner
+          .counters
+          .allocated
+          .fetch_add(1, Ordering::Relaxed);
+        Ok(self.guard(permit, item, Instant::now()))
+      }
+      Err(inner) => {
+        self
+          .inner
+          .counters
+          .allocation_failures
+          .fetch_add(1, Ordering::Relaxed);
+
+        Err(ItemPoolError::AllocatorError { inner })
+      }
+    }
+  }
+
+  /// Returns a snapshot of the pool statistics.
+  pub fn stats(&self) -> PoolStats {
+    let counters = &self.inner.counters;
+    let available = if self.inner.permits.is_closed() {
+      self.inner.limit
+    } else {
+      self.inner.permits.available_permits()
     };
 
-    match item {
-      Some(item) => Ok(PoolGuard {
-        _permit: permit,
-        item,
-        pool: self.inner.clone(),
-      }),
-      None => match self.inner.allocator.init().await {
-        Ok(item) => Ok(PoolGuard {
-          _permit: permit,
-          item,
-          pool: self.inner.clone(),
-        }),
-        Err(inner) => Err(ItemPoolError::AllocatorError { inner }),
-      },
+    PoolStats {
+      limit: self.inner.limit,
+      idle: counters.idle.load(Ordering::Relaxed),
+      active: self.inner.limit.saturating_sub(available),
+      acquired: counters.acquired.load(Ordering::Relaxed),
+      wait_time: Duration::from_micros(counters.wait_time_us.load(Ordering::Relaxed)),
+      allocated: counters.allocated.load(Ordering::Relaxed),
+      allocation_failures: counters.allocation_failures.load(Ordering::Relaxed),
+      deallocated: counters.deallocated.load(Ordering::Relaxed),
     }
   }
 
   pub async fn clear(&mut self) {
     let mut items = self.inner.items.lock().await;
 
-    while let Some(item) = items.pop_back() {
-      self.inner.allocator.dealloc(item).await;
+    while let Some(pool_item) = items.pop_back() {
+      self.inner.dealloc(pool_item.item).await;
     }
+
+    self.inner.counters.idle.store(0, Ordering::Relaxed);
   }
 
   pub async fn close(mut self) {
@@ -229,12 +407,361 @@ impl<A> InnerPool<A>
 where
   A: ItemAllocator,
 {
-  async fn release(&self, item: A::Item) {
-    if self.permits.is_closed() {
-      self.allocator.dealloc(item).await;
+  async fn release(&self, item: A::Item, created_at: Instant) {
+    if self.permits.is_closed()
+      || !self.allocator.is_recyclable(&item)
+      || self.is_expired(created_at, Instant::now())
+    {
+      self.dealloc(item).await;
     } else {
       let mut items = self.items.lock().await;
-      items.push_back(item);
+      items.push_back(PoolItem {
+        item,
+        created_at,
+        released_at: Instant::now(),
+      });
+
+      self.counters.idle.store(items.len(), Ordering::Relaxed);
     }
   }
+
+  async fn acquire<E>(&self) -> Result<OwnedSemaphorePermit, ItemPoolError<E>> {
+    let started = Instant::now();
+    let permit = self.permits.clone().acquire_owned().await?;
+    let wait_time = u64::try_from(started.elapsed().as_micros()).unwrap_or(u64::MAX);
+
+    self.counters.acquired.fetch_add(1, Ordering::Relaxed);
+    self
+      .counters
+      .wait_time_us
+      .fetch_add(wait_time, Ordering::Relaxed);
+
+    Ok(permit)
+  }
+
+  /// Evicts expired idle items until the pool is closed or dropped.
+  async fn reap(pool: Weak<Self>, period: Duration) {
+    loop {
+      tokio::time::sleep(period).await;
+
+      match pool.upgrade() {
+        Some(pool) if !pool.permits.is_closed() => pool.evict_idle().await,
+        _ => break,
+      }
+    }
+  }
+
+  async fn evict_idle(&self) {
+    let now = Instant::now();
+    let mut evicted = Vec::new();
+
+    {
+      let mut items_lock = self.items.lock().await;
+      let mut idx = 0;
+
+      while idx < items_lock.len() {
+        if self.is_evictable(&items_lock[idx], now) {
+          if let Some(pool_item) = items_lock.remove(idx) {
+            evicted.push(pool_item.item);
+          }
+        } else {
+          idx += 1;
+        }
+      }
+
+      self
+        .counters
+        .idle
+        .store(items_lock.len(), Ordering::Relaxed);
+    }
+
+    for item in evicted {
+      self.dealloc(item).await;
+    }
+  }
+
+  async fn dealloc(&self, item: A::Item) {
+    self.allocator.dealloc(item).await;
+    self.counters.deallocated.fetch_add(1, Ordering::Relaxed);
+  }
+
+  /// Pops the first item from the pool, discarding expired and idle items on the way.
+  async fn pop_item(&self) -> Option<PoolItem<A::Item>> {
+    let now = Instant::now();
+    let mut evicted = Vec::new();
+
+    let pool_item = {
+      let mut items_lock = self.items.lock().await;
+
+      loop {
+        match items_lock.pop_front() {
+          Some(pool_item) if self.is_evictable(&pool_item, now) => evicted.push(pool_item.item),
+          pool_item => {
+            self
+              .counters
+              .idle
+              .store(items_lock.len(), Ordering::Relaxed);
+            break pool_item;
+          }
+        }
+      }
+    };
+
+    for item in evicted {
+      self.dealloc(item).await;
+    }
+
+    pool_item
+  }
+
+  #[inline]
+  fn is_expired(&self, created_at: Instant, now: Instant) -> bool {
+    self
+      .policy
+      .max_lifetime
+      .is_some_and(|max_lifetime| now.duration_since(created_at) >= max_lifetime)
+  }
+
+  #[inline]
+  fn is_evictable(&self, pool_item: &PoolItem<A::Item>, now: Instant) -> bool {
+    self.is_expired(pool_item.created_at, now)
+      || self
+        .policy
+        .idle_timeout
+        .is_some_and(|idle_timeout| now.duration_since(pool_item.released_at) >= idle_timeout)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{ItemAllocator, ItemPool, ItemPoolPolicy, ItemState};
+  use core::{
+    num::NonZeroUsize,
+    pin::Pin,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::Duration,
+  };
+  use std::sync::Arc;
+
+  #[derive(Default)]
+  struct Counters {
+    init: AtomicUsize,
+    check: AtomicUsize,
+    dealloc: AtomicUsize,
+    poisoned: AtomicBool,
+  }
+
+  struct TestAllocator {
+    counters: Arc<Counters>,
+  }
+
+  impl ItemAllocator for TestAllocator {
+    type Item = usize;
+    type Error = ();
+
+    fn init(&self) -> Pin<Box<dyn Future<Output = Result<Self::Item, Self::Error>> + Send + '_>> {
+      Box::pin(async move { Ok(self.counters.init.fetch_add(1, Ordering::SeqCst)) })
+    }
+
+    fn dealloc(&self, _: Self::Item) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+      Box::pin(async move {
+        self.counters.dealloc.fetch_add(1, Ordering::SeqCst);
+      })
+    }
+
+    fn is_recyclable(&self, _: &Self::Item) -> bool {
+      !self.counters.poisoned.load(Ordering::SeqCst)
+    }
+
+    fn prealloc_check(
+      &self,
+      item: Self::Item,
+    ) -> Pin<Box<dyn Future<Output = ItemState<Self::Item>> + Send + '_>> {
+      Box::pin(async move {
+        self.counters.check.fetch_add(1, Ordering::SeqCst);
+        ItemState::Ready(item)
+      })
+    }
+  }
+
+  fn test_pool(policy: ItemPoolPolicy) -> (ItemPool<TestAllocator>, Arc<Counters>) {
+    let counters = Arc::new(Counters::default());
+    let allocator = TestAllocator {
+      counters: counters.clone(),
+    };
+
+    (
+      ItemPool::new(NonZeroUsize::new(1).unwrap(), allocator, policy),
+      counters,
+    )
+  }
+
+  #[tokio::test]
+  async fn checks_released_items() {
+    let (pool, counters) = test_pool(ItemPoolPolicy::default());
+
+    pool.get_checked().await.unwrap().release().await;
+    pool.get_checked().await.unwrap().release().await;
+
+    assert_eq!(counters.init.load(Ordering::SeqCst), 1);
+    assert_eq!(counters.check.load(Ordering::SeqCst), 1);
+  }
+
+  #[tokio::test]
+  async fn skips_check_within_validation_window() {
+    let (pool, counters) = test_pool(ItemPoolPolicy {
+      validation_window: Duration::from_secs(60),
+      ..Default::default()
+    });
+
+    pool.get_checked().await.unwrap().release().await;
+    pool.get_checked().await.unwrap().release().await;
+
+    assert_eq!(counters.init.load(Ordering::SeqCst), 1);
+    assert_eq!(counters.check.load(Ordering::SeqCst), 0);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn evicts_idle_items() {
+    let (pool, counters) = test_pool(ItemPoolPolicy {
+      idle_timeout: Some(Duration::from_secs(20)),
+      ..Default::default()
+    });
+
+    pool.get().await.unwrap().release().await;
+    tokio::time::advance(Duration::from_secs(20)).await;
+
+    let item = pool.get().await.unwrap();
+
+    assert_eq!(*item, 1);
+    assert_eq!(counters.init.load(Ordering::SeqCst), 2);
+    assert_eq!(counters.dealloc.load(Ordering::SeqCst), 1);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn evicts_expired_items() {
+    let (pool, counters) = test_pool(ItemPoolPolicy {
+      max_lifetime: Some(Duration::from_secs(20)),
+      ..Default::default()
+    });
+
+    let item = pool.get().await.unwrap();
+    tokio::time::advance(Duration::from_secs(20)).await;
+    item.release().await;
+
+    let item = pool.get_checked().await.unwrap();
+
+    assert_eq!(*item, 1);
+    assert_eq!(counters.init.load(Ordering::SeqCst), 2);
+    assert_eq!(counters.check.load(Ordering::SeqCst), 0);
+    assert_eq!(counters.dealloc.load(Ordering::SeqCst), 1);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn reaps_idle_items() {
+    let (pool, counters) = test_pool(ItemPoolPolicy {
+      idle_timeout: Some(Duration::from_secs(30)),
+      ..Default::default()
+    });
+
+    pool.get().await.unwrap().release().await;
+    tokio::time::sleep(Duration::from_secs(29)).await;
+
+    assert_eq!(pool.stats().idle, 1);
+
+    tokio::time::sleep(Duration::from_secs(30)).await;
+
+    assert_eq!(pool.stats().idle, 0);
+    assert_eq!(pool.stats().deallocated, 1);
+    assert_eq!(counters.dealloc.load(Ordering::SeqCst), 1);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn reaper_stops_with_pool() {
+    let (pool, _) = test_pool(ItemPoolPolicy {
+      idle_timeout: Some(Duration::from_secs(1)),
+      ..Default::default()
+    });
+
+    let inner = std::sync::Arc::downgrade(&pool.inner);
+    pool.close().await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    assert!(inner.upgrade().is_none());
+  }
+
+  #[tokio::test]
+  async fn discards_non_recyclable_items() {
+    let (pool, counters) = test_pool(ItemPoolPolicy::default());
+
+    let item = pool.get().await.unwrap();
+    counters.poisoned.store(true, Ordering::SeqCst);
+    item.release().await;
+    counters.poisoned.store(false, Ordering::SeqCst);
+
+    let item = pool.get().await.unwrap();
+
+    assert_eq!(*item, 1);
+    assert_eq!(counters.dealloc.load(Ordering::SeqCst), 1);
+  }
+
+  #[tokio::test]
+  async fn drops_cancelled_items() {
+    let (pool, counters) = test_pool(ItemPoolPolicy::default());
+
+    drop(pool.get().await.unwrap());
+
+    let item = pool.get().await.unwrap();
+
+    assert_eq!(*item, 1);
+    assert_eq!(counters.init.load(Ordering::SeqCst), 2);
+    assert_eq!(counters.dealloc.load(Ordering::SeqCst), 0);
+    assert_eq!(pool.stats().deallocated, 1);
+  }
+
+  #[tokio::test]
+  async fn counts_cancelled_requests() {
+    let (pool, _) = test_pool(ItemPoolPolicy::default());
+
+    let request = async {
+      let item = pool.get().await.unwrap();
+      core::future::pending::<()>().await;
+      item.release().await;
+    };
+
+    assert!(
+      tokio::time::timeout(Duration::from_millis(10), request)
+        .await
+        .is_err()
+    );
+
+    let detached = pool.get().await.unwrap().into_inner();
+    let stats = pool.stats();
+
+    assert_eq!(detached, 1);
+    assert_eq!(stats.allocated, 2);
+    assert_eq!(stats.deallocated, 2);
+    assert_eq!(stats.active, 0);
+  }
+
+  #[tokio::test]
+  async fn reports_stats() {
+    let (pool, _) = test_pool(ItemPoolPolicy::default());
+
+    let item = pool.get().await.unwrap();
+    let stats = pool.stats();
+
+    assert_eq!(stats.limit, 1);
+    assert_eq!(stats.active, 1);
+    assert_eq!(stats.idle, 0);
+    assert_eq!(stats.acquired, 1);
+    assert_eq!(stats.allocated, 1);
+
+    item.release().await;
+    let stats = pool.stats();
+
+    assert_eq!(stats.active, 0);
+    assert_eq!(stats.idle, 1);
+    assert_eq!(stats.deallocated, 0);
+  }
 }
@@ -0,0 +1,94 @@
+//! JSON serializers which keep the original text of decimal values, e.g. `12.50` stays `12.50`
+//! instead of `12.5`.
+//!
+//! Decimals are written with `serde_json`'s [`RawValue`], which only `serde_json` serializers
+//! understand. Use these only on types which are serialized to JSON, for example with
+//! `#[serde(serialize_with = "nut_webgui_upsmc::json::serialize_value")]`. The [`Value`]
+//! `Serialize` implementation writes decimals as `f64` for every other format.
+
+use crate::{Decimal, Value, ups_variables::UpsVariables};
+use serde::{
+  Serialize, Serializer,
+  ser::{SerializeMap, SerializeSeq},
+};
+use serde_json::value::RawValue;
+
+/// Serializes a [`Value`] with its original decimal text.
+#[derive(Clone, Copy, Debug)]
+pub struct JsonValue<'a>(pub &'a Value);
+
+/// Serializes a list of [`Value`]s with their original decimal texts.
+#[derive(Clone, Copy, Debug)]
+pub struct JsonValues<'a>(pub &'a [Value]);
+
+impl Serialize for JsonValue<'_> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    match self.0 {
+      Value::Decimal(decimal) => serialize_decimal(decimal, serializer),
+      value => value.serialize(serializer),
+    }
+  }
+}
+
+impl Serialize for JsonValues<'_> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+
+    for value in self.0 {
+      seq.serialize_element(&JsonValue(value))?;
+    }
+
+    seq.end()
+  }
+}
+
+/// Texts which are not valid JSON numbers, like `.5` or `+1.0`, fall back to `f64`.
+fn serialize_decimal<S>(decimal: &Decimal, serializer: S) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  match serde_json::from_str::<&RawValue>(decimal.as_str()) {
+    Ok(raw) => raw.serialize(serializer),
+    Err(_) => serializer.serialize_f64(decimal.value()),
+  }
+}
+
+pub fn serialize_value<S>(value: &Value, serializer: S) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  JsonValue(value).serialize(serializer)
+}
+
+pub fn serialize_option<S>(value: &Option<Value>, serializer: S) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  value.as_ref().map(JsonValue).serialize(serializer)
+}
+
+pub fn serialize_values<S>(values: &[Value], serializer: S) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  JsonValues(values).serialize(serializer)
+}
+
+pub fn serialize_variables<S>(variables: &UpsVariables, serializer: S) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  let mut map = serializer.serialize_map(Some(variables.len()))?;
+
+  for (name, value) in variables.iter() {
+    map.serialize_entry(name, &JsonValue(value))?;
+  }
+
+  map.end()
+}
@@ -1,19 +1,36 @@
+// Protocol internals are only used by clients, which are all behind feature flags.
+#![cfg_attr(not(any(feature = "tokio", feature = "blocking")), allow(dead_code))]
+
 mod cmd_name;
+// Driver and STARTTLS commands are only used by the async clients.
+#[cfg_attr(not(feature = "tokio"), allow(dead_code))]
 mod command;
+mod decimal;
 mod internal;
+mod ups_identifier;
 mod ups_name;
 mod value;
 mod var_name;
 mod var_type;
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod client;
+pub mod driver;
 pub mod error;
+#[cfg(feature = "serde_json")]
+pub mod json;
+#[cfg(feature = "tokio")]
+pub mod monitor;
 pub mod response;
+pub mod server;
 pub mod ups_event;
 pub mod ups_status;
 pub mod ups_variables;
 
 pub use cmd_name::*;
+pub use decimal::*;
+pub use ups_identifier::*;
 pub use ups_name::*;
 pub use value::*;
 pub use var_name::*;
@@ -0,0 +1,224 @@
+//! Polling based `ups.status` monitor.
+//!
+//! [`UpsMonitor`] periodically reads `ups.status` of the given devices with a single batched
+//! request, and reports status changes and communication loss as a [`futures_core::Stream`].
+//!
+//! ```ignore
+//! let mut events = UpsMonitor::new(pool, [ups_name])
+//!   .with_poll_interval(Duration::from_secs(2))
+//!   .into_stream();
+//!
+//! while let Some(event) = events.next().await {
+//!   match event {
+//!     MonitorEvent::StatusChanged { ups, events, .. } => { /* ... */ }
+//!     MonitorEvent::CommunicationLost { ups, error } => { /* ... */ }
+//!     MonitorEvent::CommunicationRestored { ups } => { /* ... */ }
+//!   }
+//! }
+//! ```
+
+use crate::{
+  UpsName, VarName,
+  client::{Batch, NutPoolClient},
+  error::Error,
+  ups_event::UpsEvents,
+  ups_status::UpsStatus,
+};
+use core::{
+  pin::Pin,
+  task::{Context, Poll},
+  time::Duration,
+};
+use futures_core::Stream;
+use std::collections::VecDeque;
+use tokio::time::{Interval, MissedTickBehavior, interval};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Events reported by [`UpsMonitorStream`].
+#[derive(Debug)]
+pub enum MonitorEvent {
+  /// Device status is changed. The first successful read is reported as a change from an empty
+  /// status.
+  StatusChanged {
+    ups: UpsName,
+    old: UpsStatus,
+    new: UpsStatus,
+    events: UpsEvents,
+  },
+
+  /// Device status cannot be read. Reported once until the communication is restored.
+  CommunicationLost { ups: UpsName, error: Error },
+
+  /// Device status is readable again after a [`MonitorEvent::CommunicationLost`].
+  CommunicationRestored { ups: UpsName },
+}
+
+/// Builder for [`UpsMonitorStream`].
+pub struct UpsMonitor {
+  client: NutPoolClient,
+  devices: Vec<UpsName>,
+  poll_interval: Duration,
+}
+
+impl UpsMonitor {
+  pub fn new<I>(client: NutPoolClient, devices: I) -> Self
+  where
+    I: IntoIterator<Item = UpsName>,
+  {
+    Self {
+      client,
+      devices: devices.into_iter().collect(),
+      poll_interval: DEFAULT_POLL_INTERVAL,
+    }
+  }
+
+  /// Sets the delay between status reads. Default is 2 seconds, values below 1 millisecond are
+  /// clamped to 1 millisecond.
+  pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+    self.poll_interval = poll_interval.max(MIN_POLL_INTERVAL);
+    self
+  }
+
+  /// Starts monitoring. The first read happens on the first poll of the stream.
+  pub fn into_stream(self) -> UpsMonitorStream {
+    let mut interval = interval(self.poll_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let task = MonitorTask {
+      client: self.client,
+      devices: self
+        .devices
+        .into_iter()
+        .map(|ups| (ups, DeviceState::Pending))
+        .collect(),
+      interval,
+    };
+
+    UpsMonitorStream {
+      buffer: VecDeque::new(),
+      task: Box::pin(task.poll()),
+    }
+  }
+}
+
+type PollFuture = Pin<Box<dyn Future<Output = (MonitorTask, Vec<MonitorEvent>)> + Send>>;
+
+/// Endless stream of [`MonitorEvent`]s created by [`UpsMonitor::into_stream`].
+pub struct UpsMonitorStream {
+  buffer: VecDeque<MonitorEvent>,
+  task: PollFuture,
+}
+
+impl Stream for UpsMonitorStream {
+  type Item = MonitorEvent;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    loop {
+      if let Some(event) = self.buffer.pop_front() {
+        return Poll::Ready(Some(event));
+      }
+
+      match self.task.as_mut().poll(cx) {
+        Poll::Ready((task, events)) => {
+          self.buffer.extend(events);
+          self.task = Box::pin(task.poll());
+        }
+        Poll::Pending => return Poll::Pending,
+      }
+    }
+  }
+}
+
+enum DeviceState {
+  Pending,
+  Online(UpsStatus),
+  Lost(UpsStatus),
+}
+
+struct MonitorTask {
+  client: NutPoolClient,
+  devices: Vec<(UpsName, DeviceState)>,
+  interval: Interval,
+}
+
+impl MonitorTask {
+  async fn poll(mut self) -> (Self, Vec<MonitorEvent>) {
+    _ = self.interval.tick().await;
+
+    let mut events = Vec::new();
+    let mut batch = Batch::with_capacity(self.devices.len());
+    let handles: Vec<_> = self
+      .devices
+      .iter()
+      .map(|(ups, _)| batch.get_var(ups, &VarName::UPS_STATUS))
+      .collect();
+
+    let results = self.client.send_batch(&batch).await;
+    drop(batch);
+
+    match results {
+      Ok(mut results) => {
+        for ((ups, state), handle) in self.devices.iter_mut().zip(handles) {
+          let result = results.take(handle).map(|var| UpsStatus::from(&var.value));
+
+          update_state(ups, state, result, &mut events);
+        }
+      }
+      Err(err) => {
+        for (ups, state) in self.devices.iter_mut() {
+          let error = Error::from(err.kind().clone());
+          update_state(ups, state, Err(error), &mut events);
+        }
+      }
+    }
+
+    (self, events)
+  }
+}
+
+fn update_state(
+  ups: &UpsName,
+  state: &mut DeviceState,
+  result: Result<UpsStatus, Error>,
+  events: &mut Vec<MonitorEvent>,
+) {
+  match result {
+    Ok(new) => {
+      let old = match *state {
+        DeviceState::Pending => UpsStatus::default(),
+        DeviceState::Online(status) => status,
+        DeviceState::Lost(status) => {
+          events.push(MonitorEvent::CommunicationRestored { ups: ups.clone() });
+          status
+        }
+      };
+
+      if old != new {
+        events.push(MonitorEvent::StatusChanged {
+          ups: ups.clone(),
+          old,
+          new,
+          events: UpsEvents::new(old, new),
+        });
+      }
+
+      *state = DeviceState::Online(new);
+    }
+    Err(error) => {
+      let last_status = match *state {
+        DeviceState::Lost(_) => return,
+        DeviceState::Pending => UpsStatus::default(),
+        DeviceState::Online(status) => status,
+      };
+
+      events.push(MonitorEvent::CommunicationLost {
+        ups: ups.clone(),
+        error,
+      });
+
+      *state = DeviceState::Lost(last_status);
+    }
+  }
+}
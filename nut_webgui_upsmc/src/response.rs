@@ -1,4 +1,5 @@
 mod get_cmd_desc;
+mod get_num_logins;
 mod get_ups_desc;
 mod get_var;
 mod get_var_desc;
@@ -11,8 +12,10 @@ mod list_rw;
 mod list_ups;
 mod list_var;
 mod misc;
+mod tracking;
 
 pub use get_cmd_desc::*;
+pub use get_num_logins::*;
 pub use get_ups_desc::*;
 pub use get_var::*;
 pub use get_var_desc::*;
@@ -25,3 +28,4 @@ pub use list_rw::*;
 pub use list_ups::*;
 pub use list_var::*;
 pub use misc::*;
+pub use tracking::*;
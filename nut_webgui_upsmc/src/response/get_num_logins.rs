@@ -0,0 +1,37 @@
+use crate::{
+  UpsName,
+  error::{Error, ErrorKind, ParseError},
+  internal::{Deserialize, lexer::Lexer, parser_utils::parse_line},
+};
+
+#[derive(Debug)]
+pub struct NumLogins {
+  pub ups_name: UpsName,
+  pub value: usize,
+}
+
+impl Deserialize for NumLogins {
+  type Error = Error;
+
+  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+    let (ups_name, value) =
+      parse_line!(lexer, "NUMLOGINS" {UPS, name = ups_name} {TEXT, name = value})?;
+
+    let value = value.parse::<usize>().map_err(|_| ErrorKind::ParseError {
+      inner: ParseError::InvalidNumber,
+      position: lexer.get_positon(),
+    })?;
+
+    if lexer.is_finished() {
+      Ok(Self { ups_name, value })
+    } else {
+      Err(
+        ErrorKind::ParseError {
+          inner: ParseError::InvalidToken,
+          position: lexer.get_positon(),
+        }
+        .into(),
+      )
+    }
+  }
+}
@@ -8,6 +8,11 @@ pub struct DaemonVer {
   pub value: String,
 }
 
+#[derive(Debug)]
+pub struct Help {
+  pub value: String,
+}
+
 macro_rules! impl_ok_parser {
   ($name:ident, {$($tokens:literal)+}) => {
     #[derive(Debug)]
@@ -41,3 +46,5 @@ impl_ok_parser!(ProtOk, { "OK" });
 impl_ok_parser!(ProtOkFsd, { "OK" "FSD-SET"});
 impl_ok_parser!(ProtOkDetach, { "OK"  "Goodbye"});
 impl_ok_parser!(ProtOkTls, { "OK" "STARTTLS"});
+impl_ok_parser!(ProtOkPrimary, { "OK" "PRIMARY-GRANTED"});
+impl_ok_parser!(ProtOkMaster, { "OK" "MASTER-GRANTED"});
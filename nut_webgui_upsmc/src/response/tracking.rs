@@ -0,0 +1,100 @@
+use crate::{
+  error::{Error, ErrorKind, ParseError, ProtocolError},
+  internal::{Deserialize, lexer::Lexer, parser_utils::parse_line},
+};
+
+/// Tracking id returned by `INSTCMD` and `SET VAR` when `SET TRACKING ON` is active.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackingId {
+  pub value: Box<str>,
+}
+
+impl TrackingId {
+  #[inline]
+  pub fn as_str(&self) -> &str {
+    &self.value
+  }
+}
+
+impl std::fmt::Display for TrackingId {
+  #[inline]
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&self.value)
+  }
+}
+
+impl Deserialize for TrackingId {
+  type Error = Error;
+
+  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+    let value = parse_line!(lexer, "OK" "TRACKING" {TEXT, name = id})?;
+
+    if lexer.is_finished() {
+      Ok(Self { value })
+    } else {
+      Err(
+        ErrorKind::ParseError {
+          inner: ParseError::InvalidToken,
+          position: lexer.get_positon(),
+        }
+        .into(),
+      )
+    }
+  }
+}
+
+/// Result of a `GET TRACKING <id>` request.
+///
+/// `upsd` reports failed commands with an `ERR` line, the client converts those into
+/// [`TrackingStatus::Failed`] instead of returning them as a protocol error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrackingStatus {
+  Pending,
+  Success,
+  Failed(ProtocolError),
+}
+
+impl TrackingStatus {
+  #[inline]
+  pub const fn is_pending(&self) -> bool {
+    matches!(self, Self::Pending)
+  }
+}
+
+impl Deserialize for TrackingStatus {
+  type Error = Error;
+
+  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+    let status = match lexer.peek_as_str() {
+      Some("PENDING") => {
+        parse_line!(lexer, "PENDING")?;
+        Self::Pending
+      }
+      Some("SUCCESS") => {
+        parse_line!(lexer, "SUCCESS")?;
+        Self::Success
+      }
+      _ => {
+        return Err(
+          ErrorKind::ParseError {
+            inner: ParseError::InvalidToken,
+            position: lexer.get_positon(),
+          }
+          .into(),
+        );
+      }
+    };
+
+    if lexer.is_finished() {
+      Ok(status)
+    } else {
+      Err(
+        ErrorKind::ParseError {
+          inner: ParseError::InvalidToken,
+          position: lexer.get_positon(),
+        }
+        .into(),
+      )
+    }
+  }
+}
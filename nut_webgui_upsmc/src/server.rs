@@ -0,0 +1,11 @@
+//! Building blocks for serving the NUT network protocol (RFC 9271) to other clients, e.g. a
+//! read-only upsd proxy.
+//!
+//! [`Request`] parses client request lines, and [`ResponseEncoder`] writes upsd compatible
+//! replies.
+
+mod request;
+mod response_encoder;
+
+pub use request::*;
+pub use response_encoder::*;
@@ -0,0 +1,277 @@
+use crate::{
+  CmdName, InferValueFrom, UpsName, Value, VarName,
+  error::{Error, ErrorKind, ParseError, ProtocolError},
+  internal::{
+    lexer::{Lexer, Token},
+    parser_utils::{
+      cmp_literal, end_parser, extract_cmd_name, extract_ups_name, extract_var_name, parse_line,
+    },
+  },
+};
+
+/// Client request line received by a NUT protocol server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Request {
+  /// `HELP`
+  Help,
+
+  /// `VER`
+  Ver,
+
+  /// `NETVER`, or the legacy `PROTVER` alias.
+  NetVer,
+
+  /// `LIST UPS`
+  ListUps,
+
+  /// `LIST VAR <ups>`
+  ListVar { ups: UpsName },
+
+  /// `LIST RW <ups>`
+  ListRw { ups: UpsName },
+
+  /// `LIST CMD <ups>`
+  ListCmd { ups: UpsName },
+
+  /// `LIST ENUM <ups> <var>`
+  ListEnum { ups: UpsName, var: VarName },
+
+  /// `LIST RANGE <ups> <var>`
+  ListRange { ups: UpsName, var: VarName },
+
+  /// `LIST CLIENT <ups>`
+  ListClient { ups: UpsName },
+
+  /// `GET VAR <ups> <var>`
+  GetVar { ups: UpsName, var: VarName },
+
+  /// `GET TYPE <ups> <var>`
+  GetType { ups: UpsName, var: VarName },
+
+  /// `GET DESC <ups> <var>`
+  GetDesc { ups: UpsName, var: VarName },
+
+  /// `GET CMDDESC <ups> <cmd>`
+  GetCmdDesc { ups: UpsName, cmd: CmdName },
+
+  /// `GET UPSDESC <ups>`
+  GetUpsDesc { ups: UpsName },
+
+  /// `GET NUMLOGINS <ups>`
+  GetNumLogins { ups: UpsName },
+
+  /// `GET TRACKING <id>`
+  GetTracking { id: Box<str> },
+
+  /// `SET VAR <ups> <var> "<value>"`
+  SetVar {
+    ups: UpsName,
+    var: VarName,
+    value: Value,
+  },
+
+  /// `SET TRACKING <ON|OFF>`
+  SetTracking { enabled: bool },
+
+  /// `INSTCMD <ups> <cmd> ["<value>"]`
+  InstCmd {
+    ups: UpsName,
+    cmd: CmdName,
+    value: Option<Value>,
+  },
+
+  /// `FSD <ups>`
+  Fsd { ups: UpsName },
+
+  /// `LOGIN <ups>`
+  Login { ups: UpsName },
+
+  /// `LOGOUT`
+  Logout,
+
+  /// `USERNAME <username>`
+  Username { username: Box<str> },
+
+  /// `PASSWORD <password>`
+  Password { password: Box<str> },
+
+  /// `PRIMARY <ups>`, or the legacy `MASTER` alias.
+  Primary { ups: UpsName },
+
+  /// `STARTTLS`
+  StartTls,
+}
+
+impl core::str::FromStr for Request {
+  type Err = ProtocolError;
+
+  /// Parses a single request line. Unknown commands are reported as
+  /// [`ProtocolError::UnknownCommand`], and malformed arguments as
+  /// [`ProtocolError::InvalidArgument`], same as upsd.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut lexer = Lexer::new(s);
+    let request = parse_request(&mut lexer)?;
+
+    if lexer.is_finished() {
+      Ok(request)
+    } else {
+      Err(ProtocolError::InvalidArgument)
+    }
+  }
+}
+
+fn parse_request(lexer: &mut Lexer) -> Result<Request, ProtocolError> {
+  let request = match lexer.peek_as_str() {
+    Some("HELP") => parse_line!(lexer, "HELP").map(|_| Request::Help),
+    Some("VER") => parse_line!(lexer, "VER").map(|_| Request::Ver),
+    Some("NETVER") => parse_line!(lexer, "NETVER").map(|_| Request::NetVer),
+    Some("PROTVER") => parse_line!(lexer, "PROTVER").map(|_| Request::NetVer),
+    Some("LOGOUT") => parse_line!(lexer, "LOGOUT").map(|_| Request::Logout),
+    Some("STARTTLS") => parse_line!(lexer, "STARTTLS").map(|_| Request::StartTls),
+    Some("FSD") => parse_line!(lexer, "FSD" {UPS, name = ups}).map(|ups| Request::Fsd { ups }),
+    Some("LOGIN") => {
+      parse_line!(lexer, "LOGIN" {UPS, name = ups}).map(|ups| Request::Login { ups })
+    }
+    Some("PRIMARY") => {
+      parse_line!(lexer, "PRIMARY" {UPS, name = ups}).map(|ups| Request::Primary { ups })
+    }
+    Some("MASTER") => {
+      parse_line!(lexer, "MASTER" {UPS, name = ups}).map(|ups| Request::Primary { ups })
+    }
+    Some("USERNAME") => {
+      parse_single_arg(lexer, "USERNAME").map(|username| Request::Username { username })
+    }
+    Some("PASSWORD") => {
+      parse_single_arg(lexer, "PASSWORD").map(|password| Request::Password { password })
+    }
+    Some("INSTCMD") => parse_instcmd(lexer),
+    Some("LIST") => {
+      cmp_literal(lexer, "LIST").map_err(|_| ProtocolError::InvalidArgument)?;
+      parse_list(lexer)?
+    }
+    Some("GET") => {
+      cmp_literal(lexer, "GET").map_err(|_| ProtocolError::InvalidArgument)?;
+      parse_get(lexer)?
+    }
+    Some("SET") => {
+      cmp_literal(lexer, "SET").map_err(|_| ProtocolError::InvalidArgument)?;
+      parse_set(lexer)?
+    }
+    _ => return Err(ProtocolError::UnknownCommand),
+  };
+
+  request.map_err(|_| ProtocolError::InvalidArgument)
+}
+
+fn parse_list(lexer: &mut Lexer) -> Result<Result<Request, Error>, ProtocolError> {
+  let request = match lexer.peek_as_str() {
+    Some("UPS") => parse_line!(lexer, "UPS").map(|_| Request::ListUps),
+    Some("VAR") => parse_line!(lexer, "VAR" {UPS, name = ups}).map(|ups| Request::ListVar { ups }),
+    Some("RW") => parse_line!(lexer, "RW" {UPS, name = ups}).map(|ups| Request::ListRw { ups }),
+    Some("CMD") => parse_line!(lexer, "CMD" {UPS, name = ups}).map(|ups| Request::ListCmd { ups }),
+    Some("CLIENT") => {
+      parse_line!(lexer, "CLIENT" {UPS, name = ups}).map(|ups| Request::ListClient { ups })
+    }
+    Some("ENUM") => parse_line!(lexer, "ENUM" {UPS, name = ups} {VAR, name = var})
+      .map(|(ups, var)| Request::ListEnum { ups, var }),
+    Some("RANGE") => parse_line!(lexer, "RANGE" {UPS, name = ups} {VAR, name = var})
+      .map(|(ups, var)| Request::ListRange { ups, var }),
+    Some(_) => return Err(ProtocolError::InvalidArgument),
+    None => return Err(ProtocolError::UnknownCommand),
+  };
+
+  Ok(request)
+}
+
+fn parse_get(lexer: &mut Lexer) -> Result<Result<Request, Error>, ProtocolError> {
+  let request = match lexer.peek_as_str() {
+    Some("VAR") => parse_line!(lexer, "VAR" {UPS, name = ups} {VAR, name = var})
+      .map(|(ups, var)| Request::GetVar { ups, var }),
+    Some("TYPE") => parse_line!(lexer, "TYPE" {UPS, name = ups} {VAR, name = var})
+      .map(|(ups, var)| Request::GetType { ups, var }),
+    Some("DESC") => parse_line!(lexer, "DESC" {UPS, name = ups} {VAR, name = var})
+      .map(|(ups, var)| Request::GetDesc { ups, var }),
+    Some("CMDDESC") => parse_line!(lexer, "CMDDESC" {UPS, name = ups} {CMD, name = cmd})
+      .map(|(ups, cmd)| Request::GetCmdDesc { ups, cmd }),
+    Some("UPSDESC") => {
+      parse_line!(lexer, "UPSDESC" {UPS, name = ups}).map(|ups| Request::GetUpsDesc { ups })
+    }
+    Some("NUMLOGINS") => {
+      parse_line!(lexer, "NUMLOGINS" {UPS, name = ups}).map(|ups| Request::GetNumLogins { ups })
+    }
+    Some("TRACKING") => {
+      parse_line!(lexer, "TRACKING" {TEXT, name = id}).map(|id| Request::GetTracking { id })
+    }
+    Some(_) => return Err(ProtocolError::InvalidArgument),
+    None => return Err(ProtocolError::UnknownCommand),
+  };
+
+  Ok(request)
+}
+
+fn parse_set(lexer: &mut Lexer) -> Result<Result<Request, Error>, ProtocolError> {
+  let request = match lexer.peek_as_str() {
+    Some("VAR") => parse_set_var(lexer),
+    Some("TRACKING") => match parse_line!(lexer, "TRACKING" {TEXT, name = mode}) {
+      Ok(mode) if mode.eq_ignore_ascii_case("ON") => Ok(Request::SetTracking { enabled: true }),
+      Ok(mode) if mode.eq_ignore_ascii_case("OFF") => Ok(Request::SetTracking { enabled: false }),
+      Ok(_) => return Err(ProtocolError::InvalidArgument),
+      Err(err) => Err(err),
+    },
+    Some(_) => return Err(ProtocolError::InvalidArgument),
+    None => return Err(ProtocolError::UnknownCommand),
+  };
+
+  Ok(request)
+}
+
+fn parse_set_var(lexer: &mut Lexer) -> Result<Request, Error> {
+  cmp_literal(lexer, "VAR")?;
+  let ups = extract_ups_name(lexer)?;
+  let var = extract_var_name(lexer)?;
+  let value = Value::infer_from(extract_arg(lexer)?);
+  end_parser(lexer)?;
+
+  Ok(Request::SetVar { ups, var, value })
+}
+
+fn parse_instcmd(lexer: &mut Lexer) -> Result<Request, Error> {
+  cmp_literal(lexer, "INSTCMD")?;
+  let ups = extract_ups_name(lexer)?;
+  let cmd = extract_cmd_name(lexer)?;
+
+  let value = match lexer.peek() {
+    Some(Token::Text { .. } | Token::QuotedText { .. }) => {
+      Some(Value::infer_from(extract_arg(lexer)?))
+    }
+    _ => None,
+  };
+
+  end_parser(lexer)?;
+
+  Ok(Request::InstCmd { ups, cmd, value })
+}
+
+fn parse_single_arg(lexer: &mut Lexer, literal: &str) -> Result<Box<str>, Error> {
+  cmp_literal(lexer, literal)?;
+  let arg = extract_arg(lexer)?;
+  end_parser(lexer)?;
+
+  Ok(arg)
+}
+
+/// Extracts a plain or double-quoted text argument.
+fn extract_arg(lexer: &mut Lexer) -> Result<Box<str>, Error> {
+  match lexer.next_token()? {
+    Some(token @ (Token::Text { .. } | Token::QuotedText { .. })) => {
+      Ok(lexer.extract_from_token(&token).into())
+    }
+    _ => Err(
+      ErrorKind::ParseError {
+        inner: ParseError::ExpectedTextToken,
+        position: lexer.get_positon(),
+      }
+      .into(),
+    ),
+  }
+}
@@ -0,0 +1,268 @@
+use crate::{
+  CmdName, UpsName, Value, VarName, VarType, error::ProtocolError, internal::escape::escape_nut_str,
+};
+use core::fmt::Write;
+use std::net::IpAddr;
+
+/// Writes upsd compatible replies into a reusable buffer.
+///
+/// Each call appends a complete reply, including the trailing line feed. List replies are wrapped
+/// with `BEGIN LIST` and `END LIST` lines.
+///
+/// ## Example
+/// ```
+/// use nut_webgui_upsmc::{UpsName, Value, VarName, server::ResponseEncoder};
+///
+/// let ups = UpsName::new_unchecked("ups");
+/// let mut encoder = ResponseEncoder::new();
+/// encoder.var(&ups, &VarName::UPS_STATUS, &Value::from("OL"));
+///
+/// assert_eq!(encoder.as_str(), "VAR ups ups.status \"OL\"\n");
+/// ```
+#[derive(Debug, Default)]
+pub struct ResponseEncoder {
+  buffer: String,
+}
+
+impl ResponseEncoder {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  #[inline]
+  pub fn as_str(&self) -> &str {
+    &self.buffer
+  }
+
+  #[inline]
+  pub fn as_bytes(&self) -> &[u8] {
+    self.buffer.as_bytes()
+  }
+
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.buffer.is_empty()
+  }
+
+  #[inline]
+  pub fn clear(&mut self) {
+    self.buffer.clear();
+  }
+
+  #[inline]
+  pub fn into_string(self) -> String {
+    self.buffer
+  }
+
+  /// `OK`
+  #[inline]
+  pub fn ok(&mut self) {
+    self.buffer.push_str("OK\n");
+  }
+
+  /// `OK FSD-SET`
+  #[inline]
+  pub fn ok_fsd(&mut self) {
+    self.buffer.push_str("OK FSD-SET\n");
+  }
+
+  /// `OK Goodbye`
+  #[inline]
+  pub fn ok_goodbye(&mut self) {
+    self.buffer.push_str("OK Goodbye\n");
+  }
+
+  /// `ERR <error>`
+  pub fn err(&mut self, error: &ProtocolError) {
+    _ = writeln!(self.buffer, "ERR {error}");
+  }
+
+  /// Plain text line, used by `VER`, `NETVER` and `HELP` replies.
+  pub fn text(&mut self, text: &str) {
+    self.buffer.push_str(text);
+    self.buffer.push('\n');
+  }
+
+  /// `VAR <ups> <var> "<value>"`
+  pub fn var(&mut self, ups: &UpsName, var: &VarName, value: &Value) {
+    _ = writeln!(
+      self.buffer,
+      "VAR {ups} {var} \"{value}\"",
+      ups = ups.as_escaped_str(),
+      value = value.as_escaped_str()
+    );
+  }
+
+  /// `TYPE <ups> <var> <type>...`
+  pub fn var_type(&mut self, ups: &UpsName, var: &VarName, var_types: &[VarType]) {
+    _ = write!(self.buffer, "TYPE {ups} {var}", ups = ups.as_escaped_str());
+
+    for var_type in var_types {
+      _ = write!(self.buffer, " {var_type}");
+    }
+
+    self.buffer.push('\n');
+  }
+
+  /// `DESC <ups> <var> "<description>"`
+  pub fn var_desc(&mut self, ups: &UpsName, var: &VarName, desc: &str) {
+    _ = writeln!(
+      self.buffer,
+      "DESC {ups} {var} \"{desc}\"",
+      ups = ups.as_escaped_str(),
+      desc = escape_nut_str(desc)
+    );
+  }
+
+  /// `CMDDESC <ups> <cmd> "<description>"`
+  pub fn cmd_desc(&mut self, ups: &UpsName, cmd: &CmdName, desc: &str) {
+    _ = writeln!(
+      self.buffer,
+      "CMDDESC {ups} {cmd} \"{desc}\"",
+      ups = ups.as_escaped_str(),
+      desc = escape_nut_str(desc)
+    );
+  }
+
+  /// `UPSDESC <ups> "<description>"`
+  pub fn ups_desc(&mut self, ups: &UpsName, desc: &str) {
+    _ = writeln!(
+      self.buffer,
+      "UPSDESC {ups} \"{desc}\"",
+      ups = ups.as_escaped_str(),
+      desc = escape_nut_str(desc)
+    );
+  }
+
+  /// `NUMLOGINS <ups> <count>`
+  pub fn num_logins(&mut self, ups: &UpsName, count: usize) {
+    _ = writeln!(
+      self.buffer,
+      "NUMLOGINS {ups} {count}",
+      ups = ups.as_escaped_str()
+    );
+  }
+
+  /// `LIST UPS` reply with `(name, description)` pairs.
+  pub fn list_ups<'a, I>(&mut self, devices: I)
+  where
+    I: IntoIterator<Item = (&'a UpsName, &'a str)>,
+  {
+    self.buffer.push_str("BEGIN LIST UPS\n");
+
+    for (ups, desc) in devices {
+      _ = writeln!(
+        self.buffer,
+        "UPS {ups} \"{desc}\"",
+        ups = ups.as_escaped_str(),
+        desc = escape_nut_str(desc)
+      );
+    }
+
+    self.buffer.push_str("END LIST UPS\n");
+  }
+
+  /// `LIST VAR <ups>` reply.
+  pub fn list_var<'a, I>(&mut self, ups: &UpsName, variables: I)
+  where
+    I: IntoIterator<Item = (&'a VarName, &'a Value)>,
+  {
+    self.list_values("VAR", ups, variables);
+  }
+
+  /// `LIST RW <ups>` reply.
+  pub fn list_rw<'a, I>(&mut self, ups: &UpsName, variables: I)
+  where
+    I: IntoIterator<Item = (&'a VarName, &'a Value)>,
+  {
+    self.list_values("RW", ups, variables);
+  }
+
+  /// `LIST CMD <ups>` reply.
+  pub fn list_cmd<'a, I>(&mut self, ups: &UpsName, commands: I)
+  where
+    I: IntoIterator<Item = &'a CmdName>,
+  {
+    let ups = ups.as_escaped_str();
+    _ = writeln!(self.buffer, "BEGIN LIST CMD {ups}");
+
+    for cmd in commands {
+      _ = writeln!(self.buffer, "CMD {ups} {cmd}");
+    }
+
+    _ = writeln!(self.buffer, "END LIST CMD {ups}");
+  }
+
+  /// `LIST ENUM <ups> <var>` reply.
+  pub fn list_enum<'a, I>(&mut self, ups: &UpsName, var: &VarName, options: I)
+  where
+    I: IntoIterator<Item = &'a Value>,
+  {
+    let ups = ups.as_escaped_str();
+    _ = writeln!(self.buffer, "BEGIN LIST ENUM {ups} {var}");
+
+    for value in options {
+      _ = writeln!(
+        self.buffer,
+        "ENUM {ups} {var} \"{value}\"",
+        value = value.as_escaped_str()
+      );
+    }
+
+    _ = writeln!(self.buffer, "END LIST ENUM {ups} {var}");
+  }
+
+  /// `LIST RANGE <ups> <var>` reply with `(min, max)` pairs.
+  pub fn list_range<'a, I>(&mut self, ups: &UpsName, var: &VarName, ranges: I)
+  where
+    I: IntoIterator<Item = (&'a Value, &'a Value)>,
+  {
+    let ups = ups.as_escaped_str();
+    _ = writeln!(self.buffer, "BEGIN LIST RANGE {ups} {var}");
+
+    for (min, max) in ranges {
+      _ = writeln!(
+        self.buffer,
+        "RANGE {ups} {var} \"{min}\" \"{max}\"",
+        min = min.as_escaped_str(),
+        max = max.as_escaped_str()
+      );
+    }
+
+    _ = writeln!(self.buffer, "END LIST RANGE {ups} {var}");
+  }
+
+  /// `LIST CLIENT <ups>` reply.
+  pub fn list_client<'a, I>(&mut self, ups: &UpsName, clients: I)
+  where
+    I: IntoIterator<Item = &'a IpAddr>,
+  {
+    let ups = ups.as_escaped_str();
+    _ = writeln!(self.buffer, "BEGIN LIST CLIENT {ups}");
+
+    for addr in clients {
+      _ = writeln!(self.buffer, "CLIENT {ups} {addr}");
+    }
+
+    _ = writeln!(self.buffer, "END LIST CLIENT {ups}");
+  }
+
+  fn list_values<'a, I>(&mut self, kind: &str, ups: &UpsName, variables: I)
+  where
+    I: IntoIterator<Item = (&'a VarName, &'a Value)>,
+  {
+    let ups = ups.as_escaped_str();
+    _ = writeln!(self.buffer, "BEGIN LIST {kind} {ups}");
+
+    for (var, value) in variables {
+      _ = writeln!(
+        self.buffer,
+        "{kind} {ups} {var} \"{value}\"",
+        value = value.as_escaped_str()
+      );
+    }
+
+    _ = writeln!(self.buffer, "END LIST {kind} {ups}");
+  }
+}
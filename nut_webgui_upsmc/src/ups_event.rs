@@ -89,6 +89,48 @@ pub enum UpsEvent {
 
   /// Ups communicating
   COMM,
+
+  /// Ups is in ECO mode
+  EcoMode,
+
+  /// ECO mode ended
+  EcoModeEnded,
+
+  /// Ups is in high efficiency mode
+  HighEfficiency,
+
+  /// High efficiency mode ended
+  HighEfficiencyEnded,
+
+  /// Battery level is high
+  HighBattery,
+
+  /// Battery level is no longer high
+  HighBatteryEnded,
+
+  /// Fan failure
+  FanFailure,
+
+  /// Fan failure resolved
+  FanFailureEnded,
+
+  /// Ups is overheated
+  Overheat,
+
+  /// Ups temperature normal
+  OverheatEnded,
+
+  /// Battery is not connected
+  NoBattery,
+
+  /// Battery is connected again
+  NoBatteryEnded,
+
+  /// Communication with the ups is unreliable
+  CommBad,
+
+  /// Communication with the ups is reliable again
+  CommBadEnded,
 }
 
 #[derive(Debug, Clone)]
@@ -115,6 +157,13 @@ impl UpsEvents {
         UpsStatus::REPLACE_BATTERY => events.insert(UpsEvent::ReplaceBatteryEnded),
         UpsStatus::TEST => events.insert(UpsEvent::TestCompleted),
         UpsStatus::TRIM => events.insert(UpsEvent::TrimmingEnded),
+        UpsStatus::COMM_BAD => events.insert(UpsEvent::CommBadEnded),
+        UpsStatus::ECO => events.insert(UpsEvent::EcoModeEnded),
+        UpsStatus::FAN_FAILURE => events.insert(UpsEvent::FanFailureEnded),
+        UpsStatus::HIGH_BATTERY => events.insert(UpsEvent::HighBatteryEnded),
+        UpsStatus::HIGH_EFFICIENCY => events.insert(UpsEvent::HighEfficiencyEnded),
+        UpsStatus::NO_BATTERY => events.insert(UpsEvent::NoBatteryEnded),
+        UpsStatus::OVERHEAT => events.insert(UpsEvent::OverheatEnded),
         _ => false,
       };
     }
@@ -140,6 +189,13 @@ impl UpsEvents {
         UpsStatus::REPLACE_BATTERY => events.insert(UpsEvent::ReplaceBattery),
         UpsStatus::TEST => events.insert(UpsEvent::Testing),
         UpsStatus::TRIM => events.insert(UpsEvent::Trimming),
+        UpsStatus::COMM_BAD => events.insert(UpsEvent::CommBad),
+        UpsStatus::ECO => events.insert(UpsEvent::EcoMode),
+        UpsStatus::FAN_FAILURE => events.insert(UpsEvent::FanFailure),
+        UpsStatus::HIGH_BATTERY => events.insert(UpsEvent::HighBattery),
+        UpsStatus::HIGH_EFFICIENCY => events.insert(UpsEvent::HighEfficiency),
+        UpsStatus::NO_BATTERY => events.insert(UpsEvent::NoBattery),
+        UpsStatus::OVERHEAT => events.insert(UpsEvent::Overheat),
         _ => false,
       }
     }
@@ -201,6 +257,20 @@ impl UpsEvent {
       UpsEvent::TrimmingEnded => "TrimmingEnded",
       UpsEvent::NoCOMM => "NoCOMM",
       UpsEvent::COMM => "COMM",
+      UpsEvent::EcoMode => "EcoMode",
+      UpsEvent::EcoModeEnded => "EcoModeEnded",
+      UpsEvent::HighEfficiency => "HighEfficiency",
+      UpsEvent::HighEfficiencyEnded => "HighEfficiencyEnded",
+      UpsEvent::HighBattery => "HighBattery",
+      UpsEvent::HighBatteryEnded => "HighBatteryEnded",
+      UpsEvent::FanFailure => "FanFailure",
+      UpsEvent::FanFailureEnded => "FanFailureEnded",
+      UpsEvent::Overheat => "Overheat",
+      UpsEvent::OverheatEnded => "OverheatEnded",
+      UpsEvent::NoBattery => "NoBattery",
+      UpsEvent::NoBatteryEnded => "NoBatteryEnded",
+      UpsEvent::CommBad => "CommBad",
+      UpsEvent::CommBadEnded => "CommBadEnded",
     }
   }
 }
@@ -0,0 +1,215 @@
+use crate::{UpsName, error::UpsIdentifierParseError};
+use std::net::Ipv6Addr;
+
+/// NUT device identifier in `upsname[@hostname[:port]]` form, as used by `upsc` and `upsmon`
+/// `MONITOR` lines.
+///
+/// IPv6 hosts with a port are written in brackets, e.g. `ups@[::1]:3493`. Unbracketed IPv6 hosts
+/// which can also be read as `<ipv6>:<port>`, such as `ups@::1:3493`, are rejected as ambiguous.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct UpsIdentifier {
+  name: UpsName,
+  host: Option<Box<str>>,
+  port: Option<u16>,
+}
+
+fn is_host(host: &str) -> Result<(), UpsIdentifierParseError> {
+  if host.is_empty()
+    || host
+      .bytes()
+      .any(|byte| byte.is_ascii_whitespace() || matches!(byte, b'@' | b'/' | b':' | b'[' | b']'))
+  {
+    Err(UpsIdentifierParseError::InvalidHost)
+  } else {
+    Ok(())
+  }
+}
+
+fn parse_port(port: &str) -> Result<u16, UpsIdentifierParseError> {
+  match port.parse::<u16>() {
+    Ok(port) if port > 0 => Ok(port),
+    _ => Err(UpsIdentifierParseError::InvalidPort),
+  }
+}
+
+/// Checks if an unbracketed IPv6 address also splits into an address and a port. Addresses ending
+/// with `::` are excluded, so `::1` and `fd00::3` are still read as plain addresses.
+fn has_trailing_port(value: &str) -> bool {
+  value.rsplit_once(':').is_some_and(|(host, port)| {
+    !host.ends_with("::")
+      && !port.is_empty()
+      && port.bytes().all(|byte| byte.is_ascii_digit())
+      && host.parse::<Ipv6Addr>().is_ok()
+  })
+}
+
+fn parse_host(value: &str) -> Result<(&str, Option<u16>), UpsIdentifierParseError> {
+  if let Some(bracketed) = value.strip_prefix('[') {
+    let (host, rest) = bracketed
+      .split_once(']')
+      .ok_or(UpsIdentifierParseError::InvalidHost)?;
+
+    if host.parse::<Ipv6Addr>().is_err() {
+      return Err(UpsIdentifierParseError::InvalidHost);
+    }
+
+    match rest {
+      "" => Ok((host, None)),
+      _ => match rest.strip_prefix(':') {
+        Some(port) => Ok((host, Some(parse_port(port)?))),
+        None => Err(UpsIdentifierParseError::InvalidHost),
+      },
+    }
+  } else if value.parse::<Ipv6Addr>().is_ok() {
+    if has_trailing_port(value) {
+      Err(UpsIdentifierParseError::UnbracketedIpv6)
+    } else {
+      Ok((value, None))
+    }
+  } else if let Some((host, port)) = value.split_once(':') {
+    is_host(host)?;
+    Ok((host, Some(parse_port(port)?)))
+  } else {
+    is_host(value)?;
+    Ok((value, None))
+  }
+}
+
+impl UpsIdentifier {
+  /// Default upsd port used when the identifier does not specify one.
+  pub const DEFAULT_PORT: u16 = 3493;
+
+  pub fn new(
+    name: UpsName,
+    host: Option<&str>,
+    port: Option<u16>,
+  ) -> Result<Self, UpsIdentifierParseError> {
+    if let Some(host) = host {
+      is_host(host).or_else(|err| host.parse::<Ipv6Addr>().map(|_| ()).map_err(|_| err))?;
+    } else if port.is_some() {
+      return Err(UpsIdentifierParseError::InvalidHost);
+    }
+
+    if port == Some(0) {
+      return Err(UpsIdentifierParseError::InvalidPort);
+    }
+
+    Ok(Self {
+      name,
+      host: host.map(Box::from),
+      port,
+    })
+  }
+
+  #[inline]
+  pub const fn name(&self) -> &UpsName {
+    &self.name
+  }
+
+  #[inline]
+  pub fn host(&self) -> Option<&str> {
+    self.host.as_deref()
+  }
+
+  #[inline]
+  pub const fn port(&self) -> Option<u16> {
+    self.port
+  }
+
+  /// Returns the port, or [`UpsIdentifier::DEFAULT_PORT`] if it's not specified.
+  #[inline]
+  pub fn port_or_default(&self) -> u16 {
+    self.port.unwrap_or(Self::DEFAULT_PORT)
+  }
+
+  #[inline]
+  pub fn into_name(self) -> UpsName {
+    self.name
+  }
+}
+
+impl core::str::FromStr for UpsIdentifier {
+  type Err = UpsIdentifierParseError;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    let (name, host) = match value.split_once('@') {
+      Some((name, host)) => (name, Some(parse_host(host)?)),
+      None => (value, None),
+    };
+
+    let name = UpsName::new(name).map_err(UpsIdentifierParseError::UpsName)?;
+
+    Ok(Self {
+      name,
+      host: host.map(|(host, _)| Box::from(host)),
+      port: host.and_then(|(_, port)| port),
+    })
+  }
+}
+
+impl From<UpsName> for UpsIdentifier {
+  #[inline]
+  fn from(name: UpsName) -> Self {
+    Self {
+      name,
+      host: None,
+      port: None,
+    }
+  }
+}
+
+impl std::fmt::Display for UpsIdentifier {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.name.as_str())?;
+
+    match (self.host.as_deref(), self.port) {
+      (Some(host), Some(port)) if host.contains(':') => write!(f, "@[{host}]:{port}"),
+      (Some(host), Some(port)) => write!(f, "@{host}:{port}"),
+      (Some(host), None) if has_trailing_port(host) => write!(f, "@[{host}]"),
+      (Some(host), None) => write!(f, "@{host}"),
+      (None, _) => Ok(()),
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+  use super::UpsIdentifier;
+  use serde::de::Visitor;
+
+  impl serde::Serialize for UpsIdentifier {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+      S: serde::Serializer,
+    {
+      serializer.collect_str(self)
+    }
+  }
+
+  struct UpsIdentifierVisitor;
+
+  impl<'de> serde::Deserialize<'de> for UpsIdentifier {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+      D: serde::Deserializer<'de>,
+    {
+      deserializer.deserialize_str(UpsIdentifierVisitor)
+    }
+  }
+
+  impl<'de> Visitor<'de> for UpsIdentifierVisitor {
+    type Value = UpsIdentifier;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+      formatter.write_str("expecting an ups identifier string, e.g. upsname@hostname:port")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+      E: serde::de::Error,
+    {
+      v.parse().map_err(|err| E::custom(err))
+    }
+  }
+}
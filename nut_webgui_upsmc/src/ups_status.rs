@@ -23,6 +23,12 @@ macro_rules! impl_status {
 
         result
       }
+
+      /// Returns status tokens that are not represented by any [`UpsStatus`] flag, in their
+      /// original order.
+      pub fn unknown_tokens(value: &str) -> impl Iterator<Item = &str> {
+        value.split_whitespace().filter(|status| !matches!(*status, $($value)|+))
+      }
     }
 
     fn get_state_str(value: $crate::ups_status::UpsStatus) -> &'static str {
@@ -49,8 +55,8 @@ macro_rules! impl_status {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct UpsStatus(u32);
 
-// NOTE: networkupstools may have more options like ECO, FANFAIL, OVERHEAT etc.
-// Current implementation follows RFC version and ignores any other status texts.
+// NOTE: Flags after `TRIM` are not part of RFC-9271, but reported by networkupstools drivers.
+// Any other status texts can be read with `UpsStatus::unknown_tokens`.
 impl_status!(
 (ALARM,           "ALARM");
 (BOOST,           "BOOST");
@@ -71,6 +77,13 @@ impl_status!(
 (TICK,            "TICK");
 (TOCK,            "TOCK");
 (TRIM,            "TRIM");
+(COMM_BAD,        "COMMBAD");
+(ECO,             "ECO");
+(FAN_FAILURE,     "FANFAIL");
+(HIGH_BATTERY,    "HB");
+(HIGH_EFFICIENCY, "HE");
+(NO_BATTERY,      "NOBATT");
+(OVERHEAT,        "OVERHEAT");
 );
 
 impl std::fmt::Display for UpsStatus {
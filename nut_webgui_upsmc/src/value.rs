@@ -1,4 +1,4 @@
-use crate::{error::NumberParseError, internal::escape::escape_nut_str};
+use crate::{Decimal, error::NumberParseError, internal::escape::escape_nut_str};
 use std::borrow::Cow;
 
 macro_rules! impl_value_from {
@@ -20,9 +20,13 @@ macro_rules! impl_value_from {
 }
 
 /// Basic container type for variable values.
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+///
+/// Decimal numbers keep their original text, so `GET VAR` → `SET VAR` round trips are byte-exact.
+/// Numbers are compared by their numeric value regardless of the variant, e.g. `12.50`, `12.5`
+/// and `Value::from(12.5)` are all equal, and numbers are ordered before texts.
+#[derive(Debug, Clone)]
 pub enum Value {
-  Float(f64),
+  Decimal(Decimal),
   Int(i64),
   String(Box<str>),
 }
@@ -38,7 +42,7 @@ impl Value {
   #[inline]
   pub const fn is_numeric(&self) -> bool {
     match self {
-      Value::Float(_) | Value::Int(_) => true,
+      Value::Decimal(_) | Value::Int(_) => true,
       _ => false,
     }
   }
@@ -53,7 +57,7 @@ impl Value {
 
   pub fn as_escaped_str(&self) -> Cow<'_, str> {
     match self {
-      Value::Float(num) => Cow::Owned(format!("{:.2}", num)),
+      Value::Decimal(num) => Cow::Borrowed(num.as_str()),
       Value::Int(num) => Cow::Owned(num.to_string()),
       Value::String(text) => escape_nut_str(text),
     }
@@ -61,7 +65,7 @@ impl Value {
 
   pub fn as_str(&self) -> Cow<'_, str> {
     match self {
-      Value::Float(num) => Cow::Owned(format!("{:.2}", num)),
+      Value::Decimal(num) => Cow::Borrowed(num.as_str()),
       Value::Int(num) => Cow::Owned(num.to_string()),
       Value::String(text) => Cow::Borrowed(text),
     }
@@ -70,7 +74,7 @@ impl Value {
   #[inline]
   pub const fn as_lossy_i64(&self) -> Option<i64> {
     match self {
-      Value::Float(num) => Some(*num as i64),
+      Value::Decimal(num) => Some(num.value() as i64),
       Value::Int(num) => Some(*num),
       _ => None,
     }
@@ -79,7 +83,7 @@ impl Value {
   #[inline]
   pub const fn as_lossy_f64(&self) -> Option<f64> {
     match self {
-      Value::Float(num) => Some(*num),
+      Value::Decimal(num) => Some(num.value()),
       Value::Int(num) => Some(*num as f64),
       _ => None,
     }
@@ -128,8 +132,8 @@ impl InferValueFrom<Box<str>> for Value {
         .map_or_else(|_| Self::String(value), |v| Self::Int(v)),
 
       InferredType::Float => value
-        .parse::<f64>()
-        .map_or_else(|_| Self::String(value), |v| Self::Float(v)),
+        .parse::<Decimal>()
+        .map_or_else(|_| Self::String(value), Self::Decimal),
 
       _ => Self::String(value),
     }
@@ -149,8 +153,8 @@ impl InferValueFrom<String> for Value {
         .map_or_else(|_| Self::String(value.into_boxed_str()), |v| Self::Int(v)),
 
       InferredType::Float => value
-        .parse::<f64>()
-        .map_or_else(|_| Self::String(value.into_boxed_str()), |v| Self::Float(v)),
+        .parse::<Decimal>()
+        .map_or_else(|_| Self::String(value.into_boxed_str()), Self::Decimal),
 
       _ => Self::String(value.into_boxed_str()),
     }
@@ -170,8 +174,8 @@ impl InferValueFrom<&str> for Value {
         .map_or_else(|_| Self::String(Box::from(value)), |v| Self::Int(v)),
 
       InferredType::Float => value
-        .parse::<f64>()
-        .map_or_else(|_| Self::String(Box::from(value)), |v| Self::Float(v)),
+        .parse::<Decimal>()
+        .map_or_else(|_| Self::String(Box::from(value)), Self::Decimal),
 
       _ => Self::String(Box::from(value)),
     }
@@ -179,10 +183,7 @@ impl InferValueFrom<&str> for Value {
 
   fn infer_number_from(value: &str) -> Result<Value, NumberParseError> {
     if value.contains('.') {
-      value
-        .parse::<f64>()
-        .map(|v| Value::from(v))
-        .map_err(|_| NumberParseError)
+      value.parse::<Decimal>().map(Value::Decimal)
     } else {
       value
         .parse::<i64>()
@@ -210,15 +211,30 @@ impl InferValueFrom<Cow<'_, str>> for Value {
 impl std::fmt::Display for Value {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
-      Value::Float(v) => f.write_fmt(format_args!("{v:.2}")),
+      Value::Decimal(v) => f.write_str(v.as_str()),
       Value::Int(v) => f.write_fmt(format_args!("{v}")),
       Value::String(v) => f.write_str(v),
     }
   }
 }
 
-impl_value_from!(f64, Float);
-impl_value_from!(f32, Float, f64);
+impl_value_from!(Decimal, Decimal);
+
+impl From<f64> for Value {
+  /// Finite numbers are converted to [Value::Decimal], `NaN` and infinities are kept as text.
+  #[inline]
+  fn from(value: f64) -> Self {
+    Decimal::try_from(value).map_or_else(|_| Self::String(value.to_string().into()), Self::Decimal)
+  }
+}
+
+impl From<f32> for Value {
+  /// Finite numbers are converted to [Value::Decimal], `NaN` and infinities are kept as text.
+  #[inline]
+  fn from(value: f32) -> Self {
+    Decimal::try_from(value).map_or_else(|_| Self::String(value.to_string().into()), Self::Decimal)
+  }
+}
 
 impl_value_from!(i16, Int, i64);
 impl_value_from!(i32, Int, i64);
@@ -261,11 +277,36 @@ impl From<&str> for Value {
   }
 }
 
+impl PartialEq for Value {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Value::Int(lhs), Value::Int(rhs)) => lhs.eq(rhs),
+      (Value::String(lhs), Value::String(rhs)) => lhs.eq(rhs),
+      (Value::String(_), _) | (_, Value::String(_)) => false,
+      _ => self.as_lossy_f64() == other.as_lossy_f64(),
+    }
+  }
+}
+
+impl PartialOrd for Value {
+  fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+    use core::cmp::Ordering;
+
+    match (self, other) {
+      (Value::Int(lhs), Value::Int(rhs)) => lhs.partial_cmp(rhs),
+      (Value::String(lhs), Value::String(rhs)) => lhs.partial_cmp(rhs),
+      (Value::String(_), _) => Some(Ordering::Greater),
+      (_, Value::String(_)) => Some(Ordering::Less),
+      _ => self.as_lossy_f64().partial_cmp(&other.as_lossy_f64()),
+    }
+  }
+}
+
 impl PartialEq<i64> for Value {
   #[inline]
   fn eq(&self, other: &i64) -> bool {
     match self {
-      Value::Float(_) => false,
+      Value::Decimal(val) => val.value().eq(&(*other as f64)),
       Value::Int(val) => val.eq(other),
       Value::String(_) => false,
     }
@@ -276,8 +317,8 @@ impl PartialEq<f64> for Value {
   #[inline]
   fn eq(&self, other: &f64) -> bool {
     match self {
-      Value::Float(val) => val.eq(other),
-      Value::Int(_) => false,
+      Value::Decimal(val) => val.value().eq(other),
+      Value::Int(val) => (*val as f64).eq(other),
       Value::String(_) => false,
     }
   }
@@ -287,7 +328,7 @@ impl PartialEq<str> for Value {
   #[inline]
   fn eq(&self, other: &str) -> bool {
     match self {
-      Value::Float(_) => false,
+      Value::Decimal(_) => false,
       Value::Int(_) => false,
       Value::String(inner) => inner.as_ref().eq(other),
     }
@@ -298,7 +339,7 @@ impl PartialEq<Box<str>> for Value {
   #[inline]
   fn eq(&self, other: &Box<str>) -> bool {
     match self {
-      Value::Float(_) => false,
+      Value::Decimal(_) => false,
       Value::Int(_) => false,
       Value::String(inner) => inner.as_ref().eq(other.as_ref()),
     }
@@ -309,7 +350,7 @@ impl PartialEq<String> for Value {
   #[inline]
   fn eq(&self, other: &String) -> bool {
     match self {
-      Value::Float(_) => false,
+      Value::Decimal(_) => false,
       Value::Int(_) => false,
       Value::String(inner) => inner.as_ref().eq(other),
     }
@@ -358,6 +399,7 @@ impl PartialEq<String> for &Value {
   }
 }
 
+/// Decimals are serialized as `f64`, see the `json` module for keeping their original text in JSON.
 #[cfg(feature = "serde")]
 mod serde {
   use super::Value;
@@ -369,7 +411,7 @@ mod serde {
       S: serde::Serializer,
     {
       match self {
-        Value::Float(val) => serializer.serialize_f64(*val),
+        Value::Decimal(val) => serializer.serialize_f64(val.value()),
         Value::Int(val) => serializer.serialize_i64(*val),
         Value::String(val) => serializer.serialize_str(val),
       }
@@ -43,7 +43,7 @@ impl std::fmt::Display for VarType {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
       VarType::ReadWrite => f.write_str("RW"),
-      VarType::Enum => f.write_str("RW"),
+      VarType::Enum => f.write_str("ENUM"),
       VarType::Range => f.write_str("RANGE"),
       VarType::String { max_len } => f.write_fmt(format_args!("STRING:{max_len}")),
       VarType::Number => f.write_str("NUMBER"),
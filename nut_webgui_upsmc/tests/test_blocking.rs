@@ -0,0 +1,112 @@
+#![cfg(feature = "blocking")]
+
+use nut_webgui_upsmc::{
+  UpsName, Value, VarName,
+  blocking::{BlockingNutClient, NutClient},
+  error::{ErrorKind, ProtocolError},
+};
+use std::io::{Cursor, Read, Write};
+
+/// Replays canned upsd responses and records the written requests.
+struct MockStream {
+  input: Cursor<Vec<u8>>,
+  output: Vec<u8>,
+}
+
+impl MockStream {
+  fn new(responses: &[u8]) -> Self {
+    Self {
+      input: Cursor::new(responses.to_vec()),
+      output: Vec::new(),
+    }
+  }
+}
+
+impl Read for MockStream {
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    self.input.read(buf)
+  }
+}
+
+impl Write for MockStream {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    self.output.write(buf)
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
+
+#[test]
+fn get_and_list() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let stream = MockStream::new(
+    b"VAR bx1600mi battery.charge \"100\"\n\
+      BEGIN LIST VAR bx1600mi\n\
+      VAR bx1600mi ups.status \"OL\"\n\
+      VAR bx1600mi input.voltage \"230.0\"\n\
+      END LIST VAR bx1600mi\n\
+      ERR VAR-NOT-SUPPORTED\n",
+  );
+
+  let mut client = NutClient::from(stream);
+
+  let charge = client.get_var(&ups, VarName::BATTERY_CHARGE).unwrap();
+  assert_eq!(charge.value, Value::from(100));
+
+  let variables = client.list_var(&ups).unwrap().variables;
+  assert_eq!(variables.get(VarName::UPS_STATUS), Some(&Value::from("OL")));
+  assert_eq!(
+    variables.get(VarName::INPUT_VOLTAGE).map(|v| v.as_str()),
+    Some("230.0".into())
+  );
+
+  let err = client.get_var(&ups, VarName::UPS_MODEL).unwrap_err();
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ProtocolError {
+      inner: ProtocolError::VarNotSupported
+    }
+  ));
+  assert!(!client.is_poisoned());
+
+  assert_eq!(
+    client.into_inner().output,
+    b"GET VAR bx1600mi battery.charge\n\
+      LIST VAR bx1600mi\n\
+      GET VAR bx1600mi ups.model\n"
+  );
+}
+
+#[test]
+fn authenticated_set_var() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let stream = MockStream::new(b"OK\nOK\nOK\nOK Goodbye\n");
+
+  let mut client = NutClient::from(stream)
+    .authenticate("user", "password")
+    .unwrap();
+
+  client
+    .set_var(&ups, VarName::BATTERY_RUNTIME_LOW, Value::from(32))
+    .unwrap();
+
+  assert_eq!(
+    client.into_inner().output,
+    b"USERNAME user\n\
+      PASSWORD password\n\
+      SET VAR bx1600mi battery.runtime.low \"32\"\n"
+  );
+}
+
+#[test]
+fn unterminated_list() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let stream = MockStream::new(b"BEGIN LIST VAR bx1600mi\nVAR bx1600mi ups.status \"OL\"\n");
+
+  let mut client = NutClient::from(stream);
+
+  assert!(client.list_var(&ups).is_err());
+  assert!(client.is_poisoned());
+}
@@ -1,4 +1,6 @@
-use nut_webgui_upsmc::client::AsyncNutClient;
+use nut_webgui_upsmc::client::{AsyncNutClient, Batch, ProtVerCommand};
+use nut_webgui_upsmc::error::{ErrorKind, ProtocolError};
+use nut_webgui_upsmc::response::TrackingStatus;
 use nut_webgui_upsmc::{CmdName, UpsName, Value, VarName, VarType};
 
 #[tokio::test]
@@ -449,6 +451,86 @@ async fn get_versions() {
   assert_eq!(protver.value, "1.3");
 }
 
+#[tokio::test]
+async fn get_protver_variants() {
+  let stream = tokio_test::io::Builder::new()
+    .write(b"PROTVER\n")
+    .read(b"1.3\n")
+    .write(b"NETVER\n")
+    .read(b"1.2\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream);
+  let protver = client
+    .get_protver_with(ProtVerCommand::ProtVer)
+    .await
+    .unwrap();
+  let netver = client
+    .get_protver_with(ProtVerCommand::NetVer)
+    .await
+    .unwrap();
+
+  assert_eq!(protver.value, "1.3");
+  assert_eq!(netver.value, "1.2");
+}
+
+#[tokio::test]
+async fn get_num_logins() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"GET NUMLOGINS bx1600mi\n")
+    .read(b"NUMLOGINS bx1600mi 3\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream);
+  let num_logins = client.get_num_logins(&ups).await.unwrap();
+
+  assert_eq!(num_logins.ups_name, ups);
+  assert_eq!(num_logins.value, 3);
+}
+
+#[tokio::test]
+async fn help() {
+  const HELP: &[u8] =
+    b"Commands: HELP VER GET LIST SET INSTCMD LOGIN LOGOUT USERNAME PASSWORD STARTTLS\n";
+
+  let stream = tokio_test::io::Builder::new()
+    .write(b"HELP\n")
+    .read(HELP)
+    .build();
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream);
+  let help = client.help().await.unwrap();
+
+  assert_eq!(
+    help.value,
+    "Commands: HELP VER GET LIST SET INSTCMD LOGIN LOGOUT USERNAME PASSWORD STARTTLS"
+  );
+}
+
+#[tokio::test]
+async fn primary_and_master() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"USERNAME user\n")
+    .read(b"OK\n")
+    .write(b"PASSWORD password\n")
+    .read(b"OK\n")
+    .write(b"PRIMARY bx1600mi\n")
+    .read(b"OK PRIMARY-GRANTED\n")
+    .write(b"MASTER bx1600mi\n")
+    .read(b"OK MASTER-GRANTED\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream)
+    .authenticate("user", "password")
+    .await
+    .unwrap();
+
+  client.primary(&ups).await.unwrap();
+  client.master(&ups).await.unwrap();
+}
+
 #[tokio::test]
 async fn instcmd() {
   let ups = nut_webgui_upsmc::UpsName::new_unchecked("bx1600mi");
@@ -472,6 +554,33 @@ async fn instcmd() {
     .unwrap();
 }
 
+#[tokio::test]
+async fn instcmd_with_value() {
+  let ups = nut_webgui_upsmc::UpsName::new_unchecked("bx1600mi");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"USERNAME user\n")
+    .read(b"OK\n")
+    .write(b"PASSWORD password\n")
+    .read(b"OK\n")
+    .write(b"INSTCMD bx1600mi load.off.delay \"120\"\n")
+    .read(b"OK\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream)
+    .authenticate("user", "password")
+    .await
+    .unwrap();
+
+  client
+    .instcmd_with_arg(
+      ups,
+      CmdName::new_unchecked("load.off.delay"),
+      Value::from(120),
+    )
+    .await
+    .unwrap();
+}
+
 #[tokio::test]
 async fn fsd() {
   let ups = nut_webgui_upsmc::UpsName::new_unchecked("home:bx1600mi@localhost:4242");
@@ -523,6 +632,118 @@ async fn set_var() {
     .unwrap();
 }
 
+#[tokio::test]
+async fn get_set_var_round_trip() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let var = VarName::new_unchecked("battery.voltage.nominal");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"USERNAME user\n")
+    .read(b"OK\n")
+    .write(b"PASSWORD password\n")
+    .read(b"OK\n")
+    .write(b"GET VAR bx1600mi battery.voltage.nominal\n")
+    .read(b"VAR bx1600mi battery.voltage.nominal \"12.50\"\n")
+    .write(b"SET VAR bx1600mi battery.voltage.nominal \"12.50\"\n")
+    .read(b"OK\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream)
+    .authenticate("user", "password")
+    .await
+    .unwrap();
+
+  let value = client.get_var(&ups, &var).await.unwrap().value;
+  assert_eq!(value, 12.5);
+
+  client.set_var(&ups, &var, value).await.unwrap();
+}
+
+#[tokio::test]
+async fn instcmd_tracked() {
+  let ups = nut_webgui_upsmc::UpsName::new_unchecked("bx1600mi");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"USERNAME user\n")
+    .read(b"OK\n")
+    .write(b"PASSWORD password\n")
+    .read(b"OK\n")
+    .write(b"SET TRACKING ON\n")
+    .read(b"OK\n")
+    .write(b"INSTCMD bx1600mi beeper.on\n")
+    .read(b"OK TRACKING 1bd31808-cb49-4aec-9d75-d056e6f018d2\n")
+    .write(b"GET TRACKING 1bd31808-cb49-4aec-9d75-d056e6f018d2\n")
+    .read(b"PENDING\n")
+    .write(b"GET TRACKING 1bd31808-cb49-4aec-9d75-d056e6f018d2\n")
+    .read(b"SUCCESS\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream)
+    .authenticate("user", "password")
+    .await
+    .unwrap();
+
+  let id = client
+    .instcmd_tracked(ups, CmdName::new_unchecked("beeper.on"), None)
+    .await
+    .unwrap();
+
+  assert_eq!(id.as_str(), "1bd31808-cb49-4aec-9d75-d056e6f018d2");
+  assert!(client.is_tracking());
+
+  let status = client
+    .wait_tracking(&id, std::time::Duration::from_secs(5))
+    .await
+    .unwrap();
+
+  assert_eq!(status, TrackingStatus::Success);
+}
+
+#[tokio::test]
+async fn set_var_tracked_failed() {
+  let ups = nut_webgui_upsmc::UpsName::new_unchecked("bx1600mi");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"USERNAME user\n")
+    .read(b"OK\n")
+    .write(b"PASSWORD password\n")
+    .read(b"OK\n")
+    .write(b"SET TRACKING ON\n")
+    .read(b"OK\n")
+    .write(b"SET VAR bx1600mi battery.runtime.low \"32\"\n")
+    .read(b"OK TRACKING 7d4a4b3e-0f3c-4b8a-a6f0-6b7c3f1d9e21\n")
+    .write(b"GET TRACKING 7d4a4b3e-0f3c-4b8a-a6f0-6b7c3f1d9e21\n")
+    .read(b"ERR INVALID-ARGUMENT\n")
+    .write(b"SET TRACKING OFF\n")
+    .read(b"OK\n")
+    .write(b"SET VAR bx1600mi battery.runtime.low \"32\"\n")
+    .read(b"OK\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream)
+    .authenticate("user", "password")
+    .await
+    .unwrap();
+
+  let id = client
+    .set_var_tracked(&ups, VarName::BATTERY_RUNTIME_LOW, Value::from(32))
+    .await
+    .unwrap();
+
+  let status = client
+    .wait_tracking(&id, std::time::Duration::from_secs(5))
+    .await
+    .unwrap();
+
+  assert_eq!(
+    status,
+    TrackingStatus::Failed(ProtocolError::InvalidArgument)
+  );
+
+  client.set_tracking(false).await.unwrap();
+  client
+    .set_var(&ups, VarName::BATTERY_RUNTIME_LOW, Value::from(32))
+    .await
+    .unwrap();
+}
+
 #[tokio::test]
 async fn attach_ups_and_detach() {
   let ups = nut_webgui_upsmc::UpsName::new_unchecked("bx1600mi");
@@ -545,3 +766,210 @@ async fn attach_ups_and_detach() {
   client.attach(&ups).await.unwrap();
   client.detach().await.unwrap();
 }
+
+#[tokio::test]
+async fn send_batch() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let unknown = UpsName::new_unchecked("unknown");
+  let status_var = VarName::UPS_STATUS;
+
+  let stream = tokio_test::io::Builder::new()
+    .write(b"GET VAR bx1600mi ups.status\nGET VAR unknown ups.status\nLIST CLIENT bx1600mi\nGET NUMLOGINS bx1600mi\n")
+    .read(b"VAR bx1600mi ups.status \"OL\"\n")
+    .read(b"ERR UNKNOWN-UPS\n")
+    .read(b"BEGIN LIST CLIENT bx1600mi\nCLIENT bx1600mi 127.0.0.1\nEND LIST CLIENT bx1600mi\n")
+    .read(b"NUMLOGINS bx1600mi 1\n")
+    .build();
+
+  let mut batch = Batch::new();
+  let status = batch.get_var(&ups, &status_var);
+  let unknown_status = batch.get_var(&unknown, &status_var);
+  let clients = batch.list_client(&ups);
+  let num_logins = batch.get_num_logins(&ups);
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream);
+  let mut results = client.send_batch(&batch).await.unwrap();
+
+  assert_eq!(results.len(), 4);
+
+  let status = results.take(status).unwrap();
+  assert_eq!(status.ups_name, ups);
+  assert_eq!(status.value, Value::from("OL"));
+
+  let err = results.take(unknown_status).unwrap_err();
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ProtocolError {
+      inner: ProtocolError::UnknownUps
+    }
+  ));
+
+  let clients = results.take(clients).unwrap();
+  assert_eq!(clients.ips.len(), 1);
+
+  let num_logins = results.take(num_logins).unwrap();
+  assert_eq!(num_logins.value, 1);
+}
+
+#[tokio::test]
+async fn send_batch_connection_closed() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+
+  let stream = tokio_test::io::Builder::new()
+    .write(b"LIST CMD bx1600mi\nLIST VAR bx1600mi\n")
+    .read(b"BEGIN LIST CMD bx1600mi\nCMD bx1600mi beeper.on\nEND LIST CMD bx1600mi\n")
+    .build();
+
+  let mut batch = Batch::new();
+  _ = batch.list_cmd(&ups);
+  _ = batch.list_var(&ups);
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream);
+  let err = client.send_batch(&batch).await.unwrap_err();
+
+  assert!(matches!(err.kind(), ErrorKind::EmptyResponse));
+}
+
+#[tokio::test]
+async fn send_batch_timeout_per_reply() {
+  use tokio::io::AsyncReadExt;
+
+  let ups0 = UpsName::new_unchecked("ups0");
+  let ups1 = UpsName::new_unchecked("ups1");
+  let ups2 = UpsName::new_unchecked("ups2");
+  let status_var = VarName::UPS_STATUS;
+
+  // Each reply fits the timeout, but the whole batch does not.
+  let stream = tokio_test::io::Builder::new()
+    .write(b"GET VAR ups0 ups.status\nGET VAR ups1 ups.status\nGET VAR ups2 ups.status\n")
+    .wait(std::time::Duration::from_millis(30))
+    .read(b"VAR ups0 ups.status \"OL\"\n")
+    .wait(std::time::Duration::from_millis(30))
+    .read(b"VAR ups1 ups.status \"OL\"\n")
+    .wait(std::time::Duration::from_millis(30))
+    .read(b"VAR ups2 ups.status \"OB\"\n")
+    .build();
+
+  let mut batch = Batch::new();
+  _ = batch.get_var(&ups0, &status_var);
+  _ = batch.get_var(&ups1, &status_var);
+  let status = batch.get_var(&ups2, &status_var);
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream);
+  client.set_timeout(std::time::Duration::from_millis(60));
+
+  let mut results = client.send_batch(&batch).await.unwrap();
+  assert_eq!(results.take(status).unwrap().value, Value::from("OB"));
+
+  // A single stalled reply still times out.
+  let stream = tokio_test::io::Builder::new()
+    .write(b"GET VAR ups0 ups.status\nGET VAR ups1 ups.status\n")
+    .read(b"VAR ups0 ups.status \"OL\"\n")
+    .wait(std::time::Duration::from_millis(100))
+    .read(b"VAR ups1 ups.status \"OL\"\n")
+    .build();
+
+  let mut batch = Batch::new();
+  _ = batch.get_var(&ups0, &status_var);
+  _ = batch.get_var(&ups1, &status_var);
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream);
+  client.set_timeout(std::time::Duration::from_millis(20));
+
+  let err = client.send_batch(&batch).await.unwrap_err();
+  assert!(matches!(err.kind(), ErrorKind::RequestTimeout));
+  assert!(client.is_poisoned());
+
+  let mut stale = String::new();
+  _ = client
+    .into_inner()
+    .read_to_string(&mut stale)
+    .await
+    .unwrap();
+  assert_eq!(stale, "VAR ups1 ups.status \"OL\"\n");
+}
+
+#[tokio::test]
+async fn poisoned_on_timeout() {
+  use tokio::io::AsyncReadExt;
+
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"LIST VAR bx1600mi\n")
+    .read(b"BEGIN LIST VAR bx1600mi\n")
+    .wait(std::time::Duration::from_millis(100))
+    .read(b"VAR bx1600mi ups.status \"OL\"\nEND LIST VAR bx1600mi\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream);
+  client.set_timeout(std::time::Duration::from_millis(20));
+
+  let err = client.list_var(&ups).await.unwrap_err();
+
+  assert!(matches!(err.kind(), ErrorKind::RequestTimeout));
+  assert!(client.is_poisoned());
+
+  // Remaining list lines are still in the stream.
+  let mut stale = String::new();
+  _ = client
+    .into_inner()
+    .read_to_string(&mut stale)
+    .await
+    .unwrap();
+  assert!(stale.ends_with("END LIST VAR bx1600mi\n"));
+}
+
+#[tokio::test]
+async fn poisoned_on_cancel() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"LIST CLIENT bx1600mi\n")
+    .read(b"BEGIN LIST CLIENT bx1600mi\n")
+    .wait(std::time::Duration::from_millis(100))
+    .read(b"END LIST CLIENT bx1600mi\n")
+    .write(b"LIST CLIENT bx1600mi\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream);
+  let cancelled = tokio::time::timeout(
+    std::time::Duration::from_millis(20),
+    client.list_client(&ups),
+  )
+  .await;
+
+  assert!(cancelled.is_err());
+  assert!(client.is_poisoned());
+
+  // A poisoned client reads the stale reply, which is why pools must not recycle it.
+  let err = client.list_client(&ups).await.unwrap_err();
+  assert!(matches!(err.kind(), ErrorKind::ParseError { .. }));
+}
+
+#[tokio::test]
+async fn not_poisoned_on_protocol_error() {
+  let ups = UpsName::new_unchecked("unknown");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"GET VAR unknown ups.status\n")
+    .read(b"ERR UNKNOWN-UPS\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream);
+  let err = client.get_var(&ups, VarName::UPS_STATUS).await.unwrap_err();
+
+  assert!(matches!(err.kind(), ErrorKind::ProtocolError { .. }));
+  assert!(!client.is_poisoned());
+}
+
+#[tokio::test]
+async fn poisoned_on_closed_connection() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"GET VAR bx1600mi ups.status\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::client::NutClient::from(stream);
+  let err = client.get_var(&ups, VarName::UPS_STATUS).await.unwrap_err();
+
+  assert!(matches!(err.kind(), ErrorKind::EmptyResponse));
+  assert!(client.is_poisoned());
+}
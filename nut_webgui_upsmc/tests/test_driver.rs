@@ -0,0 +1,273 @@
+use nut_webgui_upsmc::driver::{DriverClient, DriverMessage, VarFlags};
+use nut_webgui_upsmc::error::ProtocolError;
+use nut_webgui_upsmc::response::TrackingStatus;
+use nut_webgui_upsmc::{CmdName, Value, VarName, VarType};
+use std::time::Duration;
+
+const DUMP: &[u8] = b"SETINFO ups.status \"OL\"
+SETINFO battery.charge \"100\"
+SETINFO ups.delay.shutdown \"20\"
+SETFLAGS ups.delay.shutdown RW NUMBER
+ADDRANGE ups.delay.shutdown 0 600
+SETINFO input.transfer.high \"140\"
+SETFLAGS input.transfer.high RW STRING
+SETAUX input.transfer.high 3
+ADDENUM input.transfer.high \"140\"
+ADDENUM input.transfer.high \"145\"
+SETINFO ups.test.result \"Done and \\\"passed\\\"\"
+ADDCMD beeper.enable
+ADDCMD test.battery.start.quick
+SOMETHING unknown
+DATAOK
+DUMPDONE
+";
+
+#[tokio::test]
+async fn dump_all() {
+  let stream = tokio_test::io::Builder::new()
+    .write(b"DUMPALL\n")
+    .read(DUMP)
+    .build();
+
+  let mut client = DriverClient::from(stream);
+  let state = client.dump_all().await.unwrap();
+
+  assert!(!state.is_stale());
+  assert_eq!(
+    state.variables().get(VarName::UPS_STATUS),
+    Some(&Value::from("OL"))
+  );
+  assert_eq!(
+    state
+      .variables()
+      .get(VarName::new_unchecked("ups.test.result")),
+    Some(&Value::from("Done and \"passed\""))
+  );
+  assert_eq!(state.variables().len(), 5);
+  assert_eq!(
+    state.commands(),
+    &[
+      CmdName::new_unchecked("beeper.enable"),
+      CmdName::new_unchecked("test.battery.start.quick")
+    ]
+  );
+
+  let delay = VarName::new_unchecked("ups.delay.shutdown");
+  let transfer = VarName::new_unchecked("input.transfer.high");
+
+  assert_eq!(
+    state.var_types(&delay),
+    vec![VarType::ReadWrite, VarType::Range, VarType::Number]
+  );
+  assert_eq!(
+    state.var_types(&transfer),
+    vec![
+      VarType::ReadWrite,
+      VarType::Enum,
+      VarType::String { max_len: 3 }
+    ]
+  );
+  assert_eq!(
+    state.var_info(&delay).unwrap().ranges,
+    vec![(Value::from(0), Value::from(600))]
+  );
+  assert_eq!(
+    state.var_info(&transfer).unwrap().enums,
+    vec![Value::from(140), Value::from(145)]
+  );
+  assert_eq!(state.rw_variables().count(), 2);
+  assert!(state.var_types(VarName::UPS_STATUS).is_empty());
+}
+
+#[tokio::test]
+async fn stream_updates() {
+  let stream = tokio_test::io::Builder::new()
+    .write(b"DUMPALL\n")
+    .read(b"SETINFO ups.status \"OL\"\nADDCMD beeper.enable\nDUMPDONE\n")
+    .read(b"SETINFO ups.status \"OB\"\n")
+    .read(b"DELCMD beeper.enable\n")
+    .read(b"DATASTALE\n")
+    .build();
+
+  let mut client = DriverClient::from(stream);
+  let mut state = client.dump_all().await.unwrap();
+
+  let message = client.next_message().await.unwrap();
+  assert_eq!(
+    message,
+    DriverMessage::SetInfo {
+      name: VarName::UPS_STATUS,
+      value: Value::from("OB")
+    }
+  );
+  state.apply(message);
+
+  state.apply(client.next_message().await.unwrap());
+  state.apply(client.next_message().await.unwrap());
+
+  assert_eq!(
+    state.variables().get(VarName::UPS_STATUS),
+    Some(&Value::from("OB"))
+  );
+  assert!(state.commands().is_empty());
+  assert!(state.is_stale());
+  assert!(client.next_message().await.is_err());
+}
+
+#[tokio::test]
+async fn set_flags() {
+  let stream = tokio_test::io::Builder::new()
+    .read(b"SETFLAGS ups.delay.start\nSETFLAGS ups.id RW STRING UNKNOWN\n")
+    .build();
+
+  let mut client = DriverClient::from(stream);
+
+  assert_eq!(
+    client.next_message().await.unwrap(),
+    DriverMessage::SetFlags {
+      name: VarName::new_unchecked("ups.delay.start"),
+      flags: VarFlags::default()
+    }
+  );
+  assert_eq!(
+    client.next_message().await.unwrap(),
+    DriverMessage::SetFlags {
+      name: VarName::new_unchecked("ups.id"),
+      flags: VarFlags {
+        rw: true,
+        string: true,
+        number: false
+      }
+    }
+  );
+}
+
+#[cfg(unix)]
+mod unix_socket {
+  use super::*;
+  use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::UnixListener,
+    task::JoinHandle,
+  };
+
+  /// Fake driver socket, answers `DUMPALL` and tracked requests with the configured status code.
+  fn spawn_driver(path: &std::path::Path, tracking_status: i32) -> JoinHandle<Vec<String>> {
+    let listener = UnixListener::bind(path).unwrap();
+
+    tokio::spawn(async move {
+      let (stream, _) = listener.accept().await.unwrap();
+      let (reader, mut writer) = stream.into_split();
+      let mut lines = BufReader::new(reader).lines();
+      let mut received = Vec::new();
+
+      while let Ok(Some(line)) = lines.next_line().await {
+        let mut parts = line.split_whitespace();
+
+        match parts.next() {
+          Some("DUMPALL") => writer.write_all(DUMP).await.unwrap(),
+          Some("PING") => writer.write_all(b"PONG\n").await.unwrap(),
+          Some("INSTCMD" | "SET") => {
+            let id = parts.skip_while(|v| *v != "TRACKING").nth(1).unwrap();
+
+            // Unrelated updates may arrive before the tracking reply.
+            writer
+              .write_all(b"SETINFO battery.charge \"99\"\n")
+              .await
+              .unwrap();
+            writer
+              .write_all(format!("TRACKING {id} {tracking_status}\n").as_bytes())
+              .await
+              .unwrap();
+          }
+          _ => {}
+        }
+
+        received.push(line);
+      }
+
+      received
+    })
+  }
+
+  fn socket_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("upsmc-{}-{}", name, std::process::id()));
+    _ = std::fs::remove_file(&path);
+    path
+  }
+
+  #[tokio::test]
+  async fn connect_and_dump() {
+    let path = socket_path("dump");
+    let driver = spawn_driver(&path, 0);
+
+    let mut client = DriverClient::connect(&path).await.unwrap();
+    let state = client.dump_all().await.unwrap();
+
+    client.ping().await.unwrap();
+    assert_eq!(client.next_message().await.unwrap(), DriverMessage::Pong);
+    client.close().await.unwrap();
+
+    assert_eq!(state.variables().len(), 5);
+    assert_eq!(driver.await.unwrap(), vec!["DUMPALL", "PING"]);
+    _ = std::fs::remove_file(&path);
+  }
+
+  #[tokio::test]
+  async fn tracked_instcmd() {
+    let path = socket_path("instcmd");
+    let driver = spawn_driver(&path, 0);
+    let cmd = CmdName::new_unchecked("beeper.enable");
+
+    let mut client = DriverClient::connect(&path).await.unwrap();
+    let status = client.instcmd(&cmd, None).await.unwrap();
+    client.close().await.unwrap();
+
+    assert_eq!(status, TrackingStatus::Success);
+
+    let received = driver.await.unwrap();
+    assert!(received[0].starts_with("INSTCMD beeper.enable TRACKING "));
+    _ = std::fs::remove_file(&path);
+  }
+
+  #[tokio::test]
+  async fn tracked_set_var_failed() {
+    let path = socket_path("set");
+    let driver = spawn_driver(&path, 2);
+    let var = VarName::new_unchecked("ups.delay.shutdown");
+
+    let mut client = DriverClient::connect(&path).await.unwrap();
+    let status = client.set_var(&var, &Value::from("abc")).await.unwrap();
+    client.close().await.unwrap();
+
+    assert_eq!(status, TrackingStatus::Failed(ProtocolError::InvalidValue));
+
+    let received = driver.await.unwrap();
+    assert!(received[0].starts_with("SET ups.delay.shutdown \"abc\" TRACKING "));
+    _ = std::fs::remove_file(&path);
+  }
+
+  #[tokio::test]
+  async fn untracked_driver_times_out_as_pending() {
+    let path = socket_path("pending");
+    let listener = UnixListener::bind(&path).unwrap();
+    let driver = tokio::spawn(async move {
+      let (stream, _) = listener.accept().await.unwrap();
+      let mut lines = BufReader::new(stream).lines();
+      while let Ok(Some(_)) = lines.next_line().await {}
+    });
+
+    let mut client = DriverClient::connect(&path).await.unwrap();
+    client.set_timeout(Duration::from_millis(50));
+
+    let status = client
+      .instcmd(&CmdName::new_unchecked("load.off"), None)
+      .await
+      .unwrap();
+    client.close().await.unwrap();
+
+    assert_eq!(status, TrackingStatus::Pending);
+    driver.await.unwrap();
+    _ = std::fs::remove_file(&path);
+  }
+}
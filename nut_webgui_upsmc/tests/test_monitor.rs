@@ -0,0 +1,151 @@
+use core::{pin::Pin, time::Duration};
+use futures_core::Stream;
+use nut_webgui_upsmc::{
+  UpsName,
+  client::{NutPoolClientBuilder, ServerAddr},
+  monitor::{MonitorEvent, UpsMonitor, UpsMonitorStream},
+  ups_event::UpsEvent,
+  ups_status::UpsStatus,
+};
+use std::sync::{Arc, Mutex};
+use tokio::{
+  io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+  net::TcpListener,
+  task::JoinHandle,
+};
+
+/// Minimal upsd stub answering `GET VAR <ups> ups.status` from a list of scripted replies. The
+/// last reply is repeated once the list is exhausted.
+fn spawn_upsd(listener: TcpListener, replies: Vec<&'static str>) -> JoinHandle<()> {
+  let replies = Arc::new(Mutex::new(replies.into_iter().collect::<Vec<_>>()));
+
+  tokio::spawn(async move {
+    while let Ok((stream, _)) = listener.accept().await {
+      let replies = replies.clone();
+
+      tokio::spawn(async move {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        while let Ok(Some(line)) = lines.next_line().await {
+          let response = match line.as_str() {
+            "NETVER" => "1.3\n".to_owned(),
+            "STARTTLS" => "ERR FEATURE-NOT-CONFIGURED\n".to_owned(),
+            "GET VAR ups ups.status" => {
+              let mut replies = replies.lock().unwrap();
+              let reply = if replies.len() > 1 {
+                replies.remove(0)
+              } else {
+                replies[0]
+              };
+
+              match reply.strip_prefix("ERR ") {
+                Some(err) => format!("ERR {err}\n"),
+                None => format!("VAR ups ups.status \"{reply}\"\n"),
+              }
+            }
+            _ => "ERR UNKNOWN-COMMAND\n".to_owned(),
+          };
+
+          if writer.write_all(response.as_bytes()).await.is_err() {
+            break;
+          }
+        }
+      });
+    }
+  })
+}
+
+async fn next_event(stream: &mut UpsMonitorStream) -> MonitorEvent {
+  let next = core::future::poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx));
+
+  tokio::time::timeout(Duration::from_secs(5), next)
+    .await
+    .expect("monitor event timeout")
+    .expect("monitor stream ended")
+}
+
+#[tokio::test]
+async fn status_changes_and_communication_loss() {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  let task = spawn_upsd(listener, vec!["OL", "OB DISCHRG", "ERR DATA-STALE", "OL"]);
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(addr)).build();
+  let ups = UpsName::new_unchecked("ups");
+
+  let mut stream = UpsMonitor::new(pool.clone(), [ups.clone()])
+    .with_poll_interval(Duration::from_millis(10))
+    .into_stream();
+
+  match next_event(&mut stream).await {
+    MonitorEvent::StatusChanged { old, new, .. } => {
+      assert_eq!(old, UpsStatus::default());
+      assert_eq!(new, UpsStatus::ONLINE);
+    }
+    event => panic!("unexpected event {event:?}"),
+  }
+
+  match next_event(&mut stream).await {
+    MonitorEvent::StatusChanged {
+      old, new, events, ..
+    } => {
+      assert_eq!(old, UpsStatus::ONLINE);
+      assert_eq!(new, UpsStatus::ON_BATTERY | UpsStatus::DISCHARGE);
+      assert!(events.contains(UpsEvent::OnBattery));
+      assert!(events.contains(UpsEvent::Discharging));
+    }
+    event => panic!("unexpected event {event:?}"),
+  }
+
+  match next_event(&mut stream).await {
+    MonitorEvent::CommunicationLost { ups: name, .. } => assert_eq!(name, ups),
+    event => panic!("unexpected event {event:?}"),
+  }
+
+  match next_event(&mut stream).await {
+    MonitorEvent::CommunicationRestored { ups: name } => assert_eq!(name, ups),
+    event => panic!("unexpected event {event:?}"),
+  }
+
+  match next_event(&mut stream).await {
+    MonitorEvent::StatusChanged {
+      old, new, events, ..
+    } => {
+      assert_eq!(old, UpsStatus::ON_BATTERY | UpsStatus::DISCHARGE);
+      assert_eq!(new, UpsStatus::ONLINE);
+      assert!(events.contains(UpsEvent::Online));
+    }
+    event => panic!("unexpected event {event:?}"),
+  }
+
+  drop(stream);
+  pool.close().await;
+  task.abort();
+}
+
+#[tokio::test]
+async fn zero_poll_interval_is_clamped() {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  let task = spawn_upsd(listener, vec!["OL"]);
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(addr)).build();
+  let ups = UpsName::new_unchecked("ups");
+
+  let mut stream = UpsMonitor::new(pool.clone(), [ups.clone()])
+    .with_poll_interval(Duration::ZERO)
+    .into_stream();
+
+  match next_event(&mut stream).await {
+    MonitorEvent::StatusChanged { ups: name, new, .. } => {
+      assert_eq!(name, ups);
+      assert_eq!(new, UpsStatus::ONLINE);
+    }
+    event => panic!("unexpected event {event:?}"),
+  }
+
+  drop(stream);
+  pool.close().await;
+  task.abort();
+}
@@ -0,0 +1,476 @@
+use core::{num::NonZeroUsize, time::Duration};
+use nut_webgui_upsmc::client::{AsyncNutClient, NutPoolClientBuilder, ServerAddr};
+use std::{
+  net::SocketAddr,
+  sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+  },
+};
+use tokio::{
+  io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+  net::TcpListener,
+  task::JoinHandle,
+};
+
+/// Minimal upsd stub answering `LIST UPS` with a single device named `device_name`. TLS is not
+/// configured on the stub.
+fn spawn_upsd(listener: TcpListener, device_name: &'static str) -> JoinHandle<()> {
+  tokio::spawn(async move {
+    while let Ok((stream, _)) = listener.accept().await {
+      tokio::spawn(async move {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        while let Ok(Some(line)) = lines.next_line().await {
+          let response = match line.as_str() {
+            "LIST UPS" => {
+              format!("BEGIN LIST UPS\nUPS {device_name} \"test device\"\nEND LIST UPS\n")
+            }
+            "STARTTLS" => "ERR FEATURE-NOT-CONFIGURED\n".to_owned(),
+            _ => "ERR UNKNOWN-COMMAND\n".to_owned(),
+          };
+
+          if writer.write_all(response.as_bytes()).await.is_err() {
+            break;
+          }
+        }
+      });
+    }
+  })
+}
+
+/// Reply of a scripted upsd stub.
+enum Reply {
+  Send(&'static str),
+  /// Sends a partial response and closes the connection.
+  Close(&'static str),
+  /// Sends a partial response and never completes it.
+  Stall(&'static str),
+}
+
+/// Upsd stub answering requests with `script(connection_index, request_line)`. Returns the number
+/// of accepted connections.
+fn spawn_scripted_upsd(
+  listener: TcpListener,
+  script: fn(usize, &str) -> Reply,
+) -> (Arc<AtomicUsize>, JoinHandle<()>) {
+  let connections = Arc::new(AtomicUsize::new(0));
+  let counter = connections.clone();
+
+  let task = tokio::spawn(async move {
+    while let Ok((stream, _)) = listener.accept().await {
+      let index = counter.fetch_add(1, Ordering::SeqCst);
+
+      tokio::spawn(async move {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        while let Ok(Some(line)) = lines.next_line().await {
+          match script(index, &line) {
+            Reply::Send(response) => {
+              if writer.write_all(response.as_bytes()).await.is_err() {
+                break;
+              }
+            }
+            Reply::Close(response) => {
+              _ = writer.write_all(response.as_bytes()).await;
+              break;
+            }
+            Reply::Stall(response) => {
+              _ = writer.write_all(response.as_bytes()).await;
+              core::future::pending::<()>().await;
+            }
+          }
+        }
+      });
+    }
+  });
+
+  (connections, task)
+}
+
+const UPS_LIST: &str = "BEGIN LIST UPS\nUPS ups \"test device\"\nEND LIST UPS\n";
+const PARTIAL_UPS_LIST: &str = "BEGIN LIST UPS\nUPS ups \"test";
+
+async fn unused_addr() -> SocketAddr {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  listener.local_addr().unwrap()
+}
+
+#[tokio::test]
+async fn failover_and_failback() {
+  let primary_addr = unused_addr().await;
+  let fallback = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let fallback_addr = fallback.local_addr().unwrap();
+  let fallback_task = spawn_upsd(fallback, "fallback");
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(primary_addr))
+    .with_fallback(ServerAddr::from(fallback_addr))
+    .with_failback_interval(Duration::ZERO)
+    .with_limit(NonZeroUsize::new(1).unwrap())
+    .build();
+
+  let ups_list = pool.list_ups().await.unwrap();
+  let status = pool.active_endpoint();
+
+  assert_eq!(ups_list.devices[0].ups_name.as_str(), "fallback");
+  assert_eq!(status.index, 1);
+  assert_eq!(status.addr.as_ref(), fallback_addr.to_string());
+  assert!(status.last_failover.is_some());
+
+  let primary = TcpListener::bind(primary_addr).await.unwrap();
+  let primary_task = spawn_upsd(primary, "primary");
+
+  let ups_list = pool.list_ups().await.unwrap();
+  let status = pool.active_endpoint();
+
+  assert_eq!(ups_list.devices[0].ups_name.as_str(), "primary");
+  assert_eq!(status.index, 0);
+  assert_eq!(status.addr.as_ref(), primary_addr.to_string());
+
+  pool.close().await;
+  primary_task.abort();
+  fallback_task.abort();
+}
+
+#[tokio::test]
+async fn remembers_last_good_endpoint() {
+  let primary_addr = unused_addr().await;
+  let fallback = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let fallback_addr = fallback.local_addr().unwrap();
+  let fallback_task = spawn_upsd(fallback, "fallback");
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(primary_addr))
+    .with_fallback(ServerAddr::from(fallback_addr))
+    .with_failback_interval(Duration::from_secs(60))
+    .with_limit(NonZeroUsize::new(1).unwrap())
+    .build();
+
+  _ = pool.list_ups().await.unwrap();
+
+  assert_eq!(pool.active_endpoint().index, 1);
+
+  // Primary is reachable again, but new connections stay on the fallback endpoint until the
+  // failback interval is elapsed.
+  let primary = TcpListener::bind(primary_addr).await.unwrap();
+  let (primary_connections, primary_task) =
+    spawn_scripted_upsd(primary, |_, _| Reply::Send("ERR UNKNOWN-COMMAND\n"));
+
+  for _ in 0..2 {
+    _ = pool.get_client().await.unwrap();
+  }
+
+  assert_eq!(pool.active_endpoint().index, 1);
+  assert_eq!(primary_connections.load(Ordering::SeqCst), 0);
+
+  pool.close().await;
+  primary_task.abort();
+  fallback_task.abort();
+}
+
+#[tokio::test]
+async fn single_endpoint_has_no_failover() {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  let task = spawn_upsd(listener, "primary");
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(addr)).build();
+
+  _ = pool.list_ups().await.unwrap();
+  let status = pool.active_endpoint();
+
+  assert_eq!(status.index, 0);
+  assert!(status.last_failover.is_none());
+
+  pool.close().await;
+  task.abort();
+}
+
+#[tokio::test]
+async fn drops_connection_closed_mid_request() {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  let (connections, task) = spawn_scripted_upsd(listener, |index, line| match (index, line) {
+    (0, "LIST UPS") => Reply::Close(PARTIAL_UPS_LIST),
+    (_, "LIST UPS") => Reply::Send(UPS_LIST),
+    _ => Reply::Send("ERR UNKNOWN-COMMAND\n"),
+  });
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(addr))
+    .with_limit(NonZeroUsize::new(1).unwrap())
+    .with_validation_window(Duration::from_secs(60))
+    .build();
+
+  // First connection fails with an IO error, the retry gets a fresh connection.
+  let ups_list = pool.list_ups().await.unwrap();
+  let stats = pool.stats();
+
+  assert_eq!(ups_list.devices[0].ups_name.as_str(), "ups");
+  assert_eq!(connections.load(Ordering::SeqCst), 2);
+  assert_eq!(stats.allocated, 2);
+  assert_eq!(stats.deallocated, 1);
+  assert_eq!(stats.idle, 1);
+
+  _ = pool.list_ups().await.unwrap();
+
+  assert_eq!(connections.load(Ordering::SeqCst), 2);
+
+  pool.close().await;
+  task.abort();
+}
+
+#[tokio::test]
+async fn drops_timed_out_connection() {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  let (connections, task) = spawn_scripted_upsd(listener, |index, line| match (index, line) {
+    (0, "LIST UPS") => Reply::Stall(PARTIAL_UPS_LIST),
+    (_, "LIST UPS") => Reply::Send(UPS_LIST),
+    _ => Reply::Send("ERR UNKNOWN-COMMAND\n"),
+  });
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(addr))
+    .with_limit(NonZeroUsize::new(1).unwrap())
+    .with_timeout(Duration::from_millis(200))
+    .with_validation_window(Duration::from_secs(60))
+    .build();
+
+  // Unread response data is left on the timed out connection, reusing it would return the rest of
+  // the first response to the next request.
+  let ups_list = pool.list_ups().await.unwrap();
+  let stats = pool.stats();
+
+  assert_eq!(ups_list.devices.len(), 1);
+  assert_eq!(connections.load(Ordering::SeqCst), 2);
+  assert_eq!(stats.allocated, 2);
+  assert_eq!(stats.deallocated, 1);
+  assert_eq!(stats.idle, 1);
+
+  pool.close().await;
+  task.abort();
+}
+
+#[tokio::test]
+async fn keeps_connection_after_protocol_error() {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  let (connections, task) = spawn_scripted_upsd(listener, |_, line| match line {
+    "LIST UPS" => Reply::Send(UPS_LIST),
+    _ => Reply::Send("ERR UNKNOWN-UPS\n"),
+  });
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(addr))
+    .with_limit(NonZeroUsize::new(1).unwrap())
+    .with_validation_window(Duration::from_secs(60))
+    .build();
+
+  let err = pool
+    .list_var(nut_webgui_upsmc::UpsName::new_unchecked("missing"))
+    .await
+    .unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    nut_webgui_upsmc::error::ErrorKind::ProtocolError {
+      inner: nut_webgui_upsmc::error::ProtocolError::UnknownUps
+    }
+  ));
+
+  _ = pool.list_ups().await.unwrap();
+
+  assert_eq!(connections.load(Ordering::SeqCst), 1);
+  assert_eq!(pool.stats().deallocated, 0);
+
+  pool.close().await;
+  task.abort();
+}
+
+#[cfg(feature = "rustls")]
+fn tls_config() -> std::sync::Arc<nut_webgui_upsmc::rustls::ClientConfig> {
+  use nut_webgui_upsmc::rustls::{ClientConfig, RootCertStore};
+
+  std::sync::Arc::new(
+    ClientConfig::builder()
+      .with_root_certificates(RootCertStore::empty())
+      .with_no_client_auth(),
+  )
+}
+
+#[cfg(feature = "rustls")]
+#[tokio::test]
+async fn prefer_tls_falls_back_to_plaintext() {
+  use nut_webgui_upsmc::{client::NegotiatedTls, rustls::pki_types::ServerName};
+
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  let task = spawn_upsd(listener, "primary");
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(addr))
+    .with_prefer_tls(ServerName::try_from("localhost").unwrap(), tls_config())
+    .build();
+
+  assert_eq!(pool.negotiated_tls(), NegotiatedTls::Pending);
+
+  let ups_list = pool.list_ups().await.unwrap();
+
+  assert_eq!(ups_list.devices[0].ups_name.as_str(), "primary");
+  assert_eq!(pool.negotiated_tls(), NegotiatedTls::Plaintext);
+
+  pool.close().await;
+  task.abort();
+}
+
+#[cfg(feature = "rustls")]
+#[tokio::test]
+async fn strict_tls_refuses_plaintext() {
+  use nut_webgui_upsmc::{
+    client::NegotiatedTls,
+    error::{ErrorKind, ProtocolError},
+    rustls::pki_types::ServerName,
+  };
+
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  let task = spawn_upsd(listener, "primary");
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(addr))
+    .with_tls(ServerName::try_from("localhost").unwrap(), tls_config())
+    .build();
+
+  let err = pool.list_ups().await.unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ProtocolError {
+      inner: ProtocolError::FeatureNotConfigured
+    }
+  ));
+  assert_eq!(pool.negotiated_tls(), NegotiatedTls::Pending);
+
+  pool.close().await;
+  task.abort();
+}
+
+/// Upsd stub accepting STARTTLS only on the first connection. Later connections answer STARTTLS
+/// with `ERR FEATURE-NOT-CONFIGURED`, like a server whose TLS setup is stripped on the way.
+#[cfg(feature = "rustls")]
+fn spawn_downgrading_upsd(listener: TcpListener) -> JoinHandle<()> {
+  use nut_webgui_upsmc::rustls::{
+    ServerConfig,
+    pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+  };
+  use tokio::io::{AsyncBufRead, AsyncWrite};
+  use tokio_rustls::TlsAcceptor;
+
+  async fn serve<S>(stream: S)
+  where
+    S: AsyncBufRead + AsyncWrite + Unpin,
+  {
+    let mut lines = stream.lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+      let response = match line.as_str() {
+        "LIST UPS" => UPS_LIST,
+        _ => "ERR UNKNOWN-COMMAND\n",
+      };
+
+      if lines
+        .get_mut()
+        .write_all(response.as_bytes())
+        .await
+        .is_err()
+      {
+        break;
+      }
+    }
+  }
+
+  let certs = CertificateDer::pem_slice_iter(include_bytes!("fixtures/tls/server.pem"))
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+  let key = PrivateKeyDer::from_pem_slice(include_bytes!("fixtures/tls/server.key")).unwrap();
+  let acceptor = TlsAcceptor::from(Arc::new(
+    ServerConfig::builder()
+      .with_no_client_auth()
+      .with_single_cert(certs, key)
+      .unwrap(),
+  ));
+
+  tokio::spawn(async move {
+    let mut connections = 0;
+
+    while let Ok((stream, _)) = listener.accept().await {
+      let offer_tls = connections == 0;
+      let acceptor = acceptor.clone();
+      connections += 1;
+
+      tokio::spawn(async move {
+        let mut stream = BufReader::new(stream);
+        let mut line = String::new();
+
+        if stream.read_line(&mut line).await.is_err() || line.trim_end() != "STARTTLS" {
+          return;
+        }
+
+        if offer_tls {
+          _ = stream.write_all(b"OK STARTTLS\n").await;
+
+          if let Ok(tls_stream) = acceptor.accept(stream.into_inner()).await {
+            serve(BufReader::new(tls_stream)).await;
+          }
+        } else {
+          _ = stream.write_all(b"ERR FEATURE-NOT-CONFIGURED\n").await;
+          serve(stream).await;
+        }
+      });
+    }
+  })
+}
+
+#[cfg(feature = "rustls")]
+#[tokio::test]
+async fn prefer_tls_refuses_downgrade() {
+  use nut_webgui_upsmc::{
+    client::NegotiatedTls,
+    error::ErrorKind,
+    rustls::{
+      ClientConfig, RootCertStore,
+      pki_types::{CertificateDer, ServerName, pem::PemObject},
+    },
+  };
+
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  let task = spawn_downgrading_upsd(listener);
+
+  let mut root_store = RootCertStore::empty();
+  root_store
+    .add(CertificateDer::from_pem_slice(include_bytes!("fixtures/tls/ca.pem")).unwrap())
+    .unwrap();
+
+  let tls_config = Arc::new(
+    ClientConfig::builder()
+      .with_root_certificates(root_store)
+      .with_no_client_auth(),
+  );
+
+  let mut pool = NutPoolClientBuilder::new(ServerAddr::from(addr))
+    .with_prefer_tls(ServerName::try_from("localhost").unwrap(), tls_config)
+    .build();
+
+  let ups_list = pool.list_ups().await.unwrap();
+
+  assert_eq!(ups_list.devices[0].ups_name.as_str(), "ups");
+  assert_eq!(pool.negotiated_tls(), NegotiatedTls::Tls);
+
+  pool.clear().await;
+
+  let err = pool.list_ups().await.unwrap_err();
+
+  assert!(matches!(err.kind(), ErrorKind::TlsDowngradeRefused));
+  assert_eq!(pool.negotiated_tls(), NegotiatedTls::Tls);
+
+  pool.close().await;
+  task.abort();
+}
@@ -0,0 +1,271 @@
+use core::num::NonZeroUsize;
+use nut_webgui_upsmc::{
+  client::{AsyncNutClient, NutPoolClientBuilder, Proxy, ServerAddr},
+  error::{ErrorKind, ProxyError},
+};
+use std::{net::SocketAddr, time::Duration};
+use tokio::{
+  io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+  net::{TcpListener, TcpStream},
+  task::JoinHandle,
+};
+
+/// Minimal upsd stub answering `LIST UPS` with a single device named `proxied`.
+fn spawn_upsd(listener: TcpListener) -> JoinHandle<()> {
+  tokio::spawn(async move {
+    while let Ok((stream, _)) = listener.accept().await {
+      tokio::spawn(async move {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        while let Ok(Some(line)) = lines.next_line().await {
+          let response = match line.as_str() {
+            "LIST UPS" => "BEGIN LIST UPS\nUPS proxied \"test device\"\nEND LIST UPS\n",
+            "STARTTLS" => "ERR FEATURE-NOT-CONFIGURED\n",
+            _ => "ERR UNKNOWN-COMMAND\n",
+          };
+
+          if writer.write_all(response.as_bytes()).await.is_err() {
+            break;
+          }
+        }
+      });
+    }
+  })
+}
+
+/// SOCKS5 stub supporting IPv4 targets, with optional username/password authentication.
+fn spawn_socks5(
+  listener: TcpListener,
+  credentials: Option<(&'static str, &'static str)>,
+) -> JoinHandle<()> {
+  tokio::spawn(async move {
+    while let Ok((mut stream, _)) = listener.accept().await {
+      tokio::spawn(async move {
+        let mut header = [0u8; 2];
+        stream.read_exact(&mut header).await.unwrap();
+
+        let mut methods = vec![0u8; header[1] as usize];
+        stream.read_exact(&mut methods).await.unwrap();
+
+        match credentials {
+          Some((username, password)) => {
+            stream.write_all(&[0x05, 0x02]).await.unwrap();
+
+            let mut version_and_len = [0u8; 2];
+            stream.read_exact(&mut version_and_len).await.unwrap();
+            let mut user = vec![0u8; version_and_len[1] as usize];
+            stream.read_exact(&mut user).await.unwrap();
+            let mut pass = vec![0u8; stream.read_u8().await.unwrap() as usize];
+            stream.read_exact(&mut pass).await.unwrap();
+
+            if user != username.as_bytes() || pass != password.as_bytes() {
+              stream.write_all(&[0x01, 0x01]).await.unwrap();
+              return;
+            }
+
+            stream.write_all(&[0x01, 0x00]).await.unwrap();
+          }
+          None => stream.write_all(&[0x05, 0x00]).await.unwrap(),
+        }
+
+        let mut request = [0u8; 4];
+        stream.read_exact(&mut request).await.unwrap();
+        assert_eq!(request, [0x05, 0x01, 0x00, 0x01]);
+
+        let mut ip = [0u8; 4];
+        stream.read_exact(&mut ip).await.unwrap();
+        let port = stream.read_u16().await.unwrap();
+
+        let mut upstream = TcpStream::connect(SocketAddr::from((ip, port)))
+          .await
+          .unwrap();
+
+        stream
+          .write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+          .await
+          .unwrap();
+
+        _ = tokio::io::copy_bidirectional(&mut stream, &mut upstream).await;
+      });
+    }
+  })
+}
+
+/// HTTP CONNECT stub, with optional basic authentication.
+fn spawn_http_connect(
+  listener: TcpListener,
+  authorization: Option<&'static str>,
+) -> JoinHandle<()> {
+  tokio::spawn(async move {
+    while let Ok((mut stream, _)) = listener.accept().await {
+      tokio::spawn(async move {
+        let mut request = Vec::new();
+
+        while !request.ends_with(b"\r\n\r\n") {
+          request.push(stream.read_u8().await.unwrap());
+        }
+
+        let request = String::from_utf8(request).unwrap();
+        let target = request
+          .strip_prefix("CONNECT ")
+          .and_then(|v| v.split_once(' '))
+          .map(|(target, _)| target.to_owned())
+          .unwrap();
+
+        if let Some(authorization) = authorization {
+          let expected = format!("Proxy-Authorization: Basic {authorization}\r\n");
+
+          if !request.contains(&expected) {
+            stream
+              .write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n")
+              .await
+              .unwrap();
+            return;
+          }
+        }
+
+        let mut upstream = TcpStream::connect(target).await.unwrap();
+
+        stream
+          .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+          .await
+          .unwrap();
+
+        _ = tokio::io::copy_bidirectional(&mut stream, &mut upstream).await;
+      });
+    }
+  })
+}
+
+async fn bind() -> (TcpListener, SocketAddr) {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+
+  (listener, addr)
+}
+
+#[tokio::test]
+async fn connect_through_socks5() {
+  let (upsd, upsd_addr) = bind().await;
+  let (proxy, proxy_addr) = bind().await;
+  let upsd_task = spawn_upsd(upsd);
+  let proxy_task = spawn_socks5(proxy, Some(("nut", "secret")));
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(upsd_addr))
+    .with_limit(NonZeroUsize::new(1).unwrap())
+    .with_proxy(Proxy::socks5(proxy_addr.to_string()).with_auth("nut", "secret"))
+    .build();
+
+  let ups_list = pool.list_ups().await.unwrap();
+  assert_eq!(ups_list.devices[0].ups_name.as_str(), "proxied");
+
+  pool.close().await;
+  proxy_task.abort();
+  upsd_task.abort();
+}
+
+#[tokio::test]
+async fn socks5_authentication_failure() {
+  let (upsd, upsd_addr) = bind().await;
+  let (proxy, proxy_addr) = bind().await;
+  let upsd_task = spawn_upsd(upsd);
+  let proxy_task = spawn_socks5(proxy, Some(("nut", "secret")));
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(upsd_addr))
+    .with_limit(NonZeroUsize::new(1).unwrap())
+    .with_proxy(Proxy::socks5(proxy_addr.to_string()).with_auth("nut", "wrong"))
+    .build();
+
+  let err = pool.list_ups().await.unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ProxyError {
+      inner: ProxyError::AuthenticationFailed
+    }
+  ));
+
+  pool.close().await;
+  proxy_task.abort();
+  upsd_task.abort();
+}
+
+#[tokio::test]
+async fn connect_through_http_proxy() {
+  let (upsd, upsd_addr) = bind().await;
+  let (proxy, proxy_addr) = bind().await;
+  let upsd_task = spawn_upsd(upsd);
+  // base64("nut:secret")
+  let proxy_task = spawn_http_connect(proxy, Some("bnV0OnNlY3JldA=="));
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(upsd_addr))
+    .with_limit(NonZeroUsize::new(1).unwrap())
+    .with_proxy(Proxy::http_connect(proxy_addr.to_string()).with_auth("nut", "secret"))
+    .build();
+
+  let ups_list = pool.list_ups().await.unwrap();
+  assert_eq!(ups_list.devices[0].ups_name.as_str(), "proxied");
+
+  pool.close().await;
+  proxy_task.abort();
+  upsd_task.abort();
+}
+
+#[tokio::test]
+async fn http_proxy_authentication_required() {
+  let (upsd, upsd_addr) = bind().await;
+  let (proxy, proxy_addr) = bind().await;
+  let upsd_task = spawn_upsd(upsd);
+  let proxy_task = spawn_http_connect(proxy, Some("bnV0OnNlY3JldA=="));
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(upsd_addr))
+    .with_limit(NonZeroUsize::new(1).unwrap())
+    .with_proxy(Proxy::http_connect(proxy_addr.to_string()))
+    .build();
+
+  let err = pool.list_ups().await.unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ProxyError {
+      inner: ProxyError::AuthenticationFailed
+    }
+  ));
+
+  pool.close().await;
+  proxy_task.abort();
+  upsd_task.abort();
+}
+
+#[tokio::test]
+async fn connect_timeout_on_unresponsive_proxy() {
+  let (proxy, proxy_addr) = bind().await;
+
+  // Accepts connections, but never completes the SOCKS5 handshake.
+  let proxy_task = tokio::spawn(async move {
+    let mut streams = Vec::new();
+
+    while let Ok((stream, _)) = proxy.accept().await {
+      streams.push(stream);
+    }
+  });
+
+  let pool = NutPoolClientBuilder::new(ServerAddr::from(proxy_addr))
+    .with_limit(NonZeroUsize::new(1).unwrap())
+    .with_connect_timeout(Duration::from_millis(200))
+    .with_proxy(Proxy::socks5(proxy_addr.to_string()))
+    .build();
+
+  let err = pool.list_ups().await.unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::IOError {
+      kind: std::io::ErrorKind::TimedOut
+    }
+  ));
+
+  pool.close().await;
+  proxy_task.abort();
+}
@@ -0,0 +1,104 @@
+use nut_webgui_upsmc::{
+  UpsName, VarName,
+  client::{AsyncNutClient, Fixture, NutClient, SessionRecorder},
+  error::ErrorKind,
+};
+use tokio::io::{AsyncReadExt, duplex};
+use tokio_test::io::Builder;
+
+#[tokio::test]
+async fn record_session() {
+  let stream = Builder::new()
+    .write(b"USERNAME admin\n")
+    .read(b"OK\n")
+    .write(b"PASSWORD secret\n")
+    .read(b"OK\n")
+    .write(b"GET VAR ups ups.status\n")
+    .read(b"VAR ups ups.status \"OL\"\n")
+    .build();
+
+  let (writer, mut reader) = duplex(4096);
+  let recorder = SessionRecorder::new(writer);
+  let mut client = NutClient::from(recorder.wrap(stream))
+    .authenticate("admin", "secret")
+    .await
+    .unwrap();
+
+  let ups = UpsName::new_unchecked("ups");
+  _ = client.get_var(&ups, &VarName::UPS_STATUS).await.unwrap();
+
+  drop(client);
+  drop(recorder);
+
+  let mut recording = String::new();
+  reader.read_to_string(&mut recording).await.unwrap();
+
+  assert_eq!(
+    recording,
+    "1> USERNAME admin\n\
+     1< OK\n\
+     1> PASSWORD ******\n\
+     1< OK\n\
+     1> GET VAR ups ups.status\n\
+     1< VAR ups ups.status \"OL\"\n"
+  );
+}
+
+#[tokio::test]
+async fn replay_fixture() {
+  let fixture = Fixture::load(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/session_auth.txt"
+  ))
+  .unwrap();
+
+  assert_eq!(fixture.len(), 1);
+
+  let mut client = NutClient::from(fixture.session(0).unwrap())
+    .authenticate("admin", "another-password")
+    .await
+    .unwrap();
+
+  let ups_list = client.list_ups().await.unwrap();
+  assert_eq!(ups_list.devices[0].ups_name.as_str(), "ups");
+
+  let ups = UpsName::new_unchecked("ups");
+  let status = client.get_var(&ups, &VarName::UPS_STATUS).await.unwrap();
+  assert_eq!(status.value.as_str(), "OL CHRG");
+}
+
+#[tokio::test]
+async fn replay_rejects_unexpected_request() {
+  let fixture = Fixture::parse("1> LIST UPS\n1< BEGIN LIST UPS\n1< END LIST UPS\n").unwrap();
+  let mut client = NutClient::from(fixture.session(0).unwrap());
+
+  let ups = UpsName::new_unchecked("ups");
+  let err = client
+    .get_var(&ups, &VarName::UPS_STATUS)
+    .await
+    .unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::IOError {
+      kind: std::io::ErrorKind::InvalidData
+    }
+  ));
+}
+
+#[test]
+fn fixture_sessions() {
+  let fixture = Fixture::parse(
+    "# two interleaved sessions\n\
+     1> LIST UPS\n\
+     2> GET VAR ups ups.status\n\
+     1< BEGIN LIST UPS\n\
+     2< VAR ups ups.status \"OL\"\n\
+     1< END LIST UPS\n",
+  )
+  .unwrap();
+
+  assert_eq!(fixture.len(), 2);
+  assert!(fixture.session(2).is_none());
+  assert!(Fixture::parse("LIST UPS").is_err());
+}
@@ -1,7 +1,8 @@
 #![cfg(feature = "serde")]
 
 use nut_webgui_upsmc::{
-  CmdName, UpsName, Value, VarName, ups_status::UpsStatus, ups_variables::UpsVariables,
+  CmdName, UpsIdentifier, UpsName, Value, VarName, ups_status::UpsStatus,
+  ups_variables::UpsVariables,
 };
 
 #[test]
@@ -44,6 +45,22 @@ fn ups_name() {
   }
 }
 
+#[test]
+fn ups_identifier() {
+  let input: Vec<UpsIdentifier> = vec![
+    "test0@localhost:12345".parse().unwrap(),
+    "test1@[::1]:3493".parse().unwrap(),
+    "test2@10.0.0.2".parse().unwrap(),
+    "test3".parse().unwrap(),
+  ];
+
+  let json_str = serde_json::to_string_pretty(&input).unwrap();
+  let deserialized: Vec<UpsIdentifier> = serde_json::from_str(json_str.as_str()).unwrap();
+
+  assert_eq!(input, deserialized);
+  assert!(serde_json::from_str::<UpsIdentifier>("\"test@local host\"").is_err());
+}
+
 #[test]
 fn variables() {
   let mut input = UpsVariables::new();
@@ -85,3 +102,46 @@ fn ups_status() {
     assert_eq!(r, l);
   }
 }
+
+#[test]
+fn decimal_as_f64() {
+  use nut_webgui_upsmc::InferValueFrom;
+
+  let input = vec![Value::infer_from("12.50"), Value::from(42)];
+
+  let json_str = serde_json::to_string(&input).unwrap();
+  assert_eq!(json_str, "[12.5,42]");
+
+  let deserialized: Vec<Value> = serde_json::from_str(&json_str).unwrap();
+  assert_eq!(deserialized, input);
+  assert!(matches!(deserialized[1], Value::Int(42)));
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_decimal_keeps_text() {
+  use nut_webgui_upsmc::{
+    InferValueFrom,
+    json::{JsonValues, serialize_option, serialize_variables},
+  };
+
+  let input = vec![
+    Value::infer_from("12.50"),
+    Value::infer_from("230.0"),
+    Value::from(0.1),
+    Value::from(42),
+    Value::from("OL"),
+  ];
+
+  let json_str = serde_json::to_string(&JsonValues(&input)).unwrap();
+  assert_eq!(json_str, r#"[12.50,230.0,0.1,42,"OL"]"#);
+
+  let variables = UpsVariables::from([(VarName::BATTERY_VOLTAGE, Value::infer_from("13.60"))]);
+  let mut serializer = serde_json::Serializer::new(Vec::new());
+  serialize_variables(&variables, &mut serializer).unwrap();
+  assert_eq!(serializer.into_inner(), br#"{"battery.voltage":13.60}"#);
+
+  let mut serializer = serde_json::Serializer::new(Vec::new());
+  serialize_option(&None, &mut serializer).unwrap();
+  assert_eq!(serializer.into_inner(), b"null");
+}
@@ -0,0 +1,186 @@
+use nut_webgui_upsmc::client::{AsyncNutClient, NutClient};
+use nut_webgui_upsmc::error::ProtocolError;
+use nut_webgui_upsmc::server::{Request, ResponseEncoder};
+use nut_webgui_upsmc::{CmdName, UpsName, Value, VarName, VarType};
+
+#[test]
+fn parse_requests() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let var = VarName::new_unchecked("input.transfer.high");
+
+  assert_eq!("LIST UPS\n".parse(), Ok(Request::ListUps));
+  assert_eq!("PROTVER\n".parse(), Ok(Request::NetVer));
+  assert_eq!(
+    "LIST ENUM bx1600mi input.transfer.high\n".parse(),
+    Ok(Request::ListEnum {
+      ups: ups.clone(),
+      var: var.clone()
+    })
+  );
+  assert_eq!(
+    "GET TYPE bx1600mi input.transfer.high\n".parse(),
+    Ok(Request::GetType {
+      ups: ups.clone(),
+      var: var.clone()
+    })
+  );
+  assert_eq!(
+    "SET VAR bx1600mi input.transfer.high \"140\"\n".parse(),
+    Ok(Request::SetVar {
+      ups: ups.clone(),
+      var: var.clone(),
+      value: Value::from(140)
+    })
+  );
+  assert_eq!(
+    "INSTCMD bx1600mi load.off.delay 30\n".parse(),
+    Ok(Request::InstCmd {
+      ups: ups.clone(),
+      cmd: CmdName::new_unchecked("load.off.delay"),
+      value: Some(Value::from(30))
+    })
+  );
+  assert_eq!(
+    "INSTCMD bx1600mi beeper.enable\n".parse(),
+    Ok(Request::InstCmd {
+      ups: ups.clone(),
+      cmd: CmdName::new_unchecked("beeper.enable"),
+      value: None
+    })
+  );
+  assert_eq!(
+    "PASSWORD \"secret with space\"\n".parse(),
+    Ok(Request::Password {
+      password: Box::from("secret with space")
+    })
+  );
+  assert_eq!(
+    "MASTER bx1600mi\n".parse(),
+    Ok(Request::Primary { ups: ups.clone() })
+  );
+  assert_eq!(
+    "SET TRACKING ON\n".parse(),
+    Ok(Request::SetTracking { enabled: true })
+  );
+}
+
+#[test]
+fn parse_invalid_requests() {
+  assert_eq!(
+    "UNKNOWN bx1600mi\n".parse::<Request>(),
+    Err(ProtocolError::UnknownCommand)
+  );
+  assert_eq!(
+    "LIST ALL\n".parse::<Request>(),
+    Err(ProtocolError::InvalidArgument)
+  );
+  assert_eq!(
+    "GET VAR bx1600mi\n".parse::<Request>(),
+    Err(ProtocolError::InvalidArgument)
+  );
+  assert_eq!(
+    "LOGOUT now\n".parse::<Request>(),
+    Err(ProtocolError::InvalidArgument)
+  );
+  assert_eq!(
+    "SET TRACKING MAYBE\n".parse::<Request>(),
+    Err(ProtocolError::InvalidArgument)
+  );
+}
+
+#[test]
+fn encode_replies() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let var = VarName::new_unchecked("ups.test.result");
+  let mut encoder = ResponseEncoder::new();
+
+  encoder.var(&ups, &var, &Value::from("Done and \"passed\""));
+  encoder.var_type(
+    &ups,
+    &var,
+    &[
+      VarType::ReadWrite,
+      VarType::Enum,
+      VarType::String { max_len: 3 },
+    ],
+  );
+  encoder.err(&ProtocolError::VarNotSupported);
+  encoder.ok_fsd();
+
+  assert_eq!(
+    encoder.as_str(),
+    "VAR bx1600mi ups.test.result \"Done and \\\"passed\\\"\"\n\
+     TYPE bx1600mi ups.test.result RW ENUM STRING:3\n\
+     ERR VAR-NOT-SUPPORTED\n\
+     OK FSD-SET\n"
+  );
+
+  encoder.clear();
+  assert!(encoder.is_empty());
+}
+
+#[tokio::test]
+async fn encoded_lists_are_readable_by_client() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let var = VarName::new_unchecked("input.transfer.high");
+  let cmd = CmdName::new_unchecked("beeper.enable");
+  let status = Value::from("OL");
+  let charge = Value::from(100);
+  let options = [Value::from(140), Value::from(145)];
+  let (min, max) = (Value::from(0), Value::from(600));
+
+  let mut list_ups = ResponseEncoder::new();
+  list_ups.list_ups([(&ups, "Back-UPS \"BX\"")]);
+
+  let mut list_var = ResponseEncoder::new();
+  list_var.list_var(
+    &ups,
+    [
+      (&VarName::UPS_STATUS, &status),
+      (&VarName::BATTERY_CHARGE, &charge),
+    ],
+  );
+
+  let mut list_cmd = ResponseEncoder::new();
+  list_cmd.list_cmd(&ups, [&cmd]);
+
+  let mut list_enum = ResponseEncoder::new();
+  list_enum.list_enum(&ups, &var, &options);
+
+  let mut list_range = ResponseEncoder::new();
+  list_range.list_range(&ups, &var, [(&min, &max)]);
+
+  let stream = tokio_test::io::Builder::new()
+    .write(b"LIST UPS\n")
+    .read(list_ups.as_bytes())
+    .write(b"LIST VAR bx1600mi\n")
+    .read(list_var.as_bytes())
+    .write(b"LIST CMD bx1600mi\n")
+    .read(list_cmd.as_bytes())
+    .write(b"LIST ENUM bx1600mi input.transfer.high\n")
+    .read(list_enum.as_bytes())
+    .write(b"LIST RANGE bx1600mi input.transfer.high\n")
+    .read(list_range.as_bytes())
+    .build();
+
+  let mut client = NutClient::from(stream);
+
+  let devices = client.list_ups().await.unwrap().devices;
+  assert_eq!(devices.len(), 1);
+  assert_eq!(devices[0].ups_name, ups);
+  assert_eq!(devices[0].desc.as_ref(), "Back-UPS \"BX\"");
+
+  let variables = client.list_var(&ups).await.unwrap().variables;
+  assert_eq!(variables.get(VarName::UPS_STATUS), Some(&status));
+  assert_eq!(variables.get(VarName::BATTERY_CHARGE), Some(&charge));
+
+  assert_eq!(client.list_cmd(&ups).await.unwrap().cmds, vec![cmd]);
+  assert_eq!(
+    client.list_enum(&ups, &var).await.unwrap().values,
+    options.to_vec()
+  );
+  assert_eq!(
+    client.list_range(&ups, &var).await.unwrap().ranges,
+    vec![(min, max)]
+  );
+}
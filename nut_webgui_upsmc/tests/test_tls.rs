@@ -0,0 +1,62 @@
+#![cfg(feature = "rustls")]
+
+use nut_webgui_upsmc::client::{AsyncNutClient, NutClient, TlsNegotiation};
+use nut_webgui_upsmc::error::{ErrorKind, ProtocolError};
+use nut_webgui_upsmc::rustls::{ClientConfig, RootCertStore, pki_types::ServerName};
+use std::sync::Arc;
+
+fn tls_config() -> Arc<ClientConfig> {
+  Arc::new(
+    ClientConfig::builder()
+      .with_root_certificates(RootCertStore::empty())
+      .with_no_client_auth(),
+  )
+}
+
+#[tokio::test]
+async fn start_tls_not_configured() {
+  let stream = tokio_test::io::Builder::new()
+    .write(b"STARTTLS\n")
+    .read(b"ERR FEATURE-NOT-CONFIGURED\n")
+    .write(b"LIST UPS\n")
+    .read(b"BEGIN LIST UPS\nUPS bx1600mi \"Test device\"\nEND LIST UPS\n")
+    .build();
+
+  let client = NutClient::from(stream);
+  let negotiation = client
+    .start_tls(ServerName::try_from("localhost").unwrap(), tls_config())
+    .await
+    .unwrap();
+
+  let TlsNegotiation::NotConfigured(mut client) = negotiation else {
+    panic!("expected plaintext fallback");
+  };
+
+  let ups_list = client.list_ups().await.unwrap();
+
+  assert_eq!(ups_list.devices.len(), 1);
+  assert!(!client.is_poisoned());
+}
+
+#[tokio::test]
+async fn start_tls_protocol_error() {
+  let stream = tokio_test::io::Builder::new()
+    .write(b"STARTTLS\n")
+    .read(b"ERR ACCESS-DENIED\n")
+    .build();
+
+  let client = NutClient::from(stream);
+  let result = client
+    .start_tls(ServerName::try_from("localhost").unwrap(), tls_config())
+    .await;
+
+  match result {
+    Err(err) => assert!(matches!(
+      err.kind(),
+      ErrorKind::ProtocolError {
+        inner: ProtocolError::AccessDenied
+      }
+    )),
+    Ok(_) => panic!("expected protocol error"),
+  }
+}
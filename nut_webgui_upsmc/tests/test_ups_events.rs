@@ -72,3 +72,18 @@ fn ups_status_simulation() {
     (UpsStatus::OFFLINE                                                    => [UpsEvent::DeviceOff])
   );
 }
+
+#[test]
+fn non_rfc_events() {
+  test_event_stream!(
+    (UpsStatus::ONLINE | UpsStatus::ECO                              => [UpsEvent::Online, UpsEvent::EcoMode]);
+    (UpsStatus::ONLINE | UpsStatus::HIGH_EFFICIENCY                  => [UpsEvent::EcoModeEnded, UpsEvent::HighEfficiency]);
+    (UpsStatus::ONLINE | UpsStatus::HIGH_BATTERY                     => [UpsEvent::HighEfficiencyEnded, UpsEvent::HighBattery]);
+    (UpsStatus::ONLINE | UpsStatus::FAN_FAILURE | UpsStatus::OVERHEAT => [UpsEvent::HighBatteryEnded, UpsEvent::FanFailure, UpsEvent::Overheat]);
+    (UpsStatus::ONLINE | UpsStatus::NO_BATTERY                       => [UpsEvent::FanFailureEnded, UpsEvent::OverheatEnded, UpsEvent::NoBattery]);
+    (UpsStatus::ONLINE | UpsStatus::COMM_BAD                         => [UpsEvent::NoBatteryEnded, UpsEvent::CommBad]);
+    (UpsStatus::ONLINE | UpsStatus::COMM_BAD | UpsStatus::NOCOMM     => [UpsEvent::NoCOMM]);
+    (UpsStatus::ONLINE | UpsStatus::COMM                             => [UpsEvent::CommBadEnded, UpsEvent::COMM]);
+    (UpsStatus::ONLINE                                               => [])
+  );
+}
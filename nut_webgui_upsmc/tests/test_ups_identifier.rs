@@ -0,0 +1,130 @@
+use nut_webgui_upsmc::{
+  UpsIdentifier, UpsName,
+  error::{UpsIdentifierParseError, UpsNameParseError},
+};
+
+macro_rules! ups_identifier_test {
+  ($test_name:ident, $input:literal, $name:literal, $host:expr, $port:expr) => {
+    #[test]
+    fn $test_name() {
+      let identifier: UpsIdentifier = $input.parse().unwrap();
+
+      assert_eq!(identifier.name(), &UpsName::new_unchecked($name));
+      assert_eq!(identifier.host(), $host);
+      assert_eq!(identifier.port(), $port);
+      assert_eq!(identifier.to_string(), $input);
+    }
+  };
+
+  ($test_name:ident, $input:literal, $fail_reason:pat_param) => {
+    #[test]
+    fn $test_name() {
+      match $input.parse::<UpsIdentifier>() {
+        Err($fail_reason) => {}
+        Err(err) => panic!("Ups identifier failed but error type does not match error={err:?}"),
+        Ok(_) => panic!("Ups identifier was expected to fail but it succeed unexpectedly"),
+      }
+    }
+  };
+}
+
+ups_identifier_test!(name_only, "myups", "myups", None, None);
+ups_identifier_test!(
+  name_host,
+  "myups@localhost",
+  "myups",
+  Some("localhost"),
+  None
+);
+ups_identifier_test!(
+  name_host_port,
+  "myups@10.0.0.2:3494",
+  "myups",
+  Some("10.0.0.2"),
+  Some(3494)
+);
+ups_identifier_test!(name_ipv6, "myups@::1", "myups", Some("::1"), None);
+ups_identifier_test!(
+  name_ipv6_port,
+  "myups@[fe80::1]:3493",
+  "myups",
+  Some("fe80::1"),
+  Some(3493)
+);
+ups_identifier_test!(
+  name_ipv6_full,
+  "myups@fd00:0:0:0:0:0:0:1",
+  "myups",
+  Some("fd00:0:0:0:0:0:0:1"),
+  None
+);
+
+ups_identifier_test!(
+  empty_name,
+  "@localhost",
+  UpsIdentifierParseError::UpsName(UpsNameParseError::Empty)
+);
+ups_identifier_test!(empty_host, "myups@", UpsIdentifierParseError::InvalidHost);
+ups_identifier_test!(
+  whitespace_host,
+  "myups@local host",
+  UpsIdentifierParseError::InvalidHost
+);
+ups_identifier_test!(
+  invalid_ipv6,
+  "myups@[localhost]:3493",
+  UpsIdentifierParseError::InvalidHost
+);
+ups_identifier_test!(
+  unbracketed_ipv6_port,
+  "myups@::1:3493",
+  UpsIdentifierParseError::UnbracketedIpv6
+);
+ups_identifier_test!(
+  unbracketed_ipv6_short_port,
+  "myups@fe80::1:80",
+  UpsIdentifierParseError::UnbracketedIpv6
+);
+ups_identifier_test!(
+  zero_port,
+  "myups@localhost:0",
+  UpsIdentifierParseError::InvalidPort
+);
+ups_identifier_test!(
+  invalid_port,
+  "myups@localhost:port",
+  UpsIdentifierParseError::InvalidPort
+);
+ups_identifier_test!(
+  port_overflow,
+  "myups@localhost:65536",
+  UpsIdentifierParseError::InvalidPort
+);
+
+#[test]
+fn default_port() {
+  let identifier: UpsIdentifier = "myups@localhost".parse().unwrap();
+  assert_eq!(identifier.port_or_default(), UpsIdentifier::DEFAULT_PORT);
+}
+
+#[test]
+fn from_parts() {
+  let name = UpsName::new_unchecked("myups");
+
+  let identifier = UpsIdentifier::new(name.clone(), Some("::1"), Some(3493)).unwrap();
+  assert_eq!(identifier.to_string(), "myups@[::1]:3493");
+
+  let identifier = UpsIdentifier::new(name.clone(), Some("fe80::1:80"), None).unwrap();
+  assert_eq!(identifier.to_string(), "myups@[fe80::1:80]");
+  assert_eq!(
+    identifier.to_string().parse::<UpsIdentifier>().unwrap(),
+    identifier
+  );
+
+  assert!(matches!(
+    UpsIdentifier::new(name.clone(), None, Some(3493)),
+    Err(UpsIdentifierParseError::InvalidHost)
+  ));
+
+  assert_eq!(UpsIdentifier::from(name).to_string(), "myups");
+}
@@ -41,23 +41,44 @@ fn single_status() {
     ("TICK",    UpsStatus::TICK);
     ("TOCK",    UpsStatus::TOCK);
     ("TRIM",    UpsStatus::TRIM);
+    ("COMMBAD", UpsStatus::COMM_BAD);
+    ("ECO",     UpsStatus::ECO);
+    ("FANFAIL", UpsStatus::FAN_FAILURE);
+    ("HB",      UpsStatus::HIGH_BATTERY);
+    ("HE",      UpsStatus::HIGH_EFFICIENCY);
+    ("NOBATT",  UpsStatus::NO_BATTERY);
+    ("OVERHEAT",UpsStatus::OVERHEAT);
   );
 }
 
 #[test]
 fn with_multip_unspecified_status() {
-  let status = UpsStatus::new("OB TEST OVERHEAT ECO MEGUSTA OL");
+  let input = "OB TEST OVERHEAT ECO MEGUSTA OL";
+  let status = UpsStatus::new(input);
+
   assert_eq!(
-    UpsStatus::ON_BATTERY | UpsStatus::TEST | UpsStatus::ONLINE,
+    UpsStatus::ON_BATTERY
+      | UpsStatus::TEST
+      | UpsStatus::OVERHEAT
+      | UpsStatus::ECO
+      | UpsStatus::ONLINE,
     status
-  )
+  );
+  assert_eq!(
+    UpsStatus::unknown_tokens(input).collect::<Vec<_>>(),
+    vec!["MEGUSTA"]
+  );
 }
 
 #[test]
 fn with_single_unspecified_status() {
-  let status = UpsStatus::new("FANFAIL");
+  let status = UpsStatus::new("WATERLOGGED");
 
-  assert_eq!(UpsStatus::default(), status)
+  assert_eq!(UpsStatus::default(), status);
+  assert_eq!(
+    UpsStatus::unknown_tokens("WATERLOGGED").collect::<Vec<_>>(),
+    vec!["WATERLOGGED"]
+  );
 }
 
 #[test]
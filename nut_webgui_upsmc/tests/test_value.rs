@@ -0,0 +1,68 @@
+use nut_webgui_upsmc::{Decimal, InferValueFrom, Value};
+
+#[test]
+fn decimal_keeps_wire_text() {
+  for text in ["0.125", "230.0", "12.50", "-0.05", "0.000001"] {
+    let value = Value::infer_from(text);
+
+    assert!(
+      matches!(value, Value::Decimal(_)),
+      "{text} is not a decimal"
+    );
+    assert_eq!(value.as_str(), text);
+    assert_eq!(value.as_escaped_str(), text);
+    assert_eq!(value.to_string(), text);
+  }
+}
+
+#[test]
+fn decimal_numeric_value() {
+  let value = Value::infer_from("0.125");
+
+  assert_eq!(value, 0.125);
+  assert_eq!(value.as_lossy_f64(), Some(0.125));
+  assert_eq!(value.as_lossy_i64(), Some(0));
+}
+
+#[test]
+fn infer_number() {
+  assert_eq!(
+    Value::infer_number_from("230.00").unwrap().as_str(),
+    "230.00"
+  );
+  assert_eq!(Value::infer_number_from("42").unwrap(), 42);
+  assert!(Value::infer_number_from("inf.").is_err());
+  assert!(Value::infer_number_from("text").is_err());
+}
+
+#[test]
+fn decimal_from_float() {
+  assert_eq!(Value::from(0.1_f64).as_str(), "0.1");
+  assert_eq!(Value::from(0.1_f32).as_str(), "0.1");
+  assert_eq!(Value::from(12.0).as_str(), "12");
+  assert_eq!("1.0".parse::<Decimal>().unwrap().value(), 1.0);
+  assert!("NaN".parse::<Decimal>().is_err());
+}
+
+#[test]
+fn decimal_rejects_non_finite() {
+  assert!(Decimal::try_from(f64::NAN).is_err());
+  assert!(Decimal::try_from(f64::INFINITY).is_err());
+  assert!(Decimal::try_from(f32::NEG_INFINITY).is_err());
+  assert_eq!(Decimal::try_from(0.5_f64).unwrap().as_str(), "0.5");
+  assert!(Value::from(f64::NAN).is_text());
+}
+
+#[test]
+fn numeric_equality() {
+  assert_eq!(Value::infer_from("12.50"), Value::from(12.5));
+  assert_eq!(Value::infer_from("12.0"), Value::from(12));
+  assert_eq!(Value::infer_from("12.0"), 12);
+  assert_eq!(Value::from(12), 12.0);
+  assert_ne!(Value::infer_from("12.5"), Value::from("12.5"));
+  assert!(Value::infer_from("9.5") < Value::from(10));
+
+  // Numbers sort before strings, even when the string looks like a smaller number.
+  let text = Value::from("1");
+  assert!(Value::from(10) < text);
+}
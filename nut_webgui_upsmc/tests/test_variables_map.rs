@@ -5,7 +5,7 @@ fn initialize() {
   let vars = UpsVariables::from([
     (VarName::UPS_STATUS, Value::String("OL".into())),
     (VarName::UPS_BEEPER_STATUS, Value::String("enabled".into())),
-    (VarName::INPUT_LOAD, Value::Float(12.0)),
+    (VarName::INPUT_LOAD, Value::from(12.0)),
   ]);
 
   assert_eq!(vars.len(), 3);
@@ -16,7 +16,7 @@ fn remove() {
   let mut vars = UpsVariables::from([
     (VarName::UPS_STATUS, Value::String("OL".into())),
     (VarName::UPS_BEEPER_STATUS, Value::String("enabled".into())),
-    (VarName::INPUT_LOAD, Value::Float(12.0)),
+    (VarName::INPUT_LOAD, Value::from(12.0)),
   ]);
 
   match vars.remove(VarName::UPS_STATUS) {
@@ -40,10 +40,10 @@ fn insert_new() {
   let mut vars = UpsVariables::from([
     (VarName::UPS_STATUS, Value::String("OL".into())),
     (VarName::UPS_BEEPER_STATUS, Value::String("enabled".into())),
-    (VarName::INPUT_LOAD, Value::Float(12.0)),
+    (VarName::INPUT_LOAD, Value::from(12.0)),
   ]);
 
-  match vars.insert(VarName::UPS_LOAD, Value::Float(100.0)) {
+  match vars.insert(VarName::UPS_LOAD, Value::from(100.0)) {
     Some(value) => {
       assert!(
         false,
@@ -56,7 +56,7 @@ fn insert_new() {
 
   match vars.get(VarName::UPS_LOAD) {
     Some(value) => {
-      assert_eq!(&Value::Float(100.0), value);
+      assert_eq!(&Value::from(100.0), value);
     }
     None => assert!(false, "Unable to read new value."),
   };
@@ -69,7 +69,7 @@ fn update() {
   let mut vars = UpsVariables::from([
     (VarName::UPS_STATUS, Value::String("OL".into())),
     (VarName::UPS_BEEPER_STATUS, Value::String("enabled".into())),
-    (VarName::INPUT_LOAD, Value::Float(12.0)),
+    (VarName::INPUT_LOAD, Value::from(12.0)),
   ]);
 
   match vars.insert(VarName::UPS_STATUS, Value::String("OB".into())) {